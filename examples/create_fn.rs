@@ -1,4 +1,24 @@
 #![allow(dead_code)]
+// The generator is kept as written, these lints came with newer clippy.
+#![allow(
+    clippy::collapsible_if,
+    clippy::explicit_auto_deref,
+    clippy::explicit_counter_loop,
+    clippy::if_same_then_else,
+    clippy::large_enum_variant,
+    clippy::len_zero,
+    clippy::match_like_matches_macro,
+    clippy::match_single_binding,
+    clippy::needless_borrow,
+    clippy::needless_ifs,
+    clippy::needless_return,
+    clippy::result_large_err,
+    clippy::single_match,
+    clippy::type_complexity,
+    clippy::unused_enumerate_index,
+    clippy::useless_asref,
+    clippy::while_let_loop
+)]
 
 use crate::error::DError;
 use crate::mapp::{
@@ -65,6 +85,7 @@ fn main() -> Result<(), DError> {
     let mut it = txt.as_ref();
     let mut file = None;
     let mut mod_name_ = String::from("");
+    let mut returns = Vec::new();
     loop {
         let (rest, spec) = parse::parse(&it).expect("mods");
         match spec {
@@ -80,6 +101,9 @@ fn main() -> Result<(), DError> {
             }
             Spec::Func(mut fun) => {
                 fun.mod_ = mod_name_.clone();
+                if let Some(ret) = &fun.ret {
+                    returns.push((fun.fun.clone(), ret.clone()));
+                }
 
                 // println!("{:?}", fun.name);
                 if etc_fn(&fun) {
@@ -100,11 +124,35 @@ fn main() -> Result<(), DError> {
         it = rest;
     }
 
+    let mut f = File::create("src/generated/returns.rs")?;
+    generate_returns(&mut f, returns)?;
+
     println!("fine.");
 
     Ok(())
 }
 
+fn generate_returns(f: &mut File, mut returns: Vec<(String, String)>) -> Result<(), DError> {
+    returns.sort_by(|a, b| a.0.cmp(&b.0));
+    returns.dedup_by(|a, b| a.0 == b.0);
+
+    writeln!(
+        f,
+        "//! Return types of the functions as given in the OpenFormula specification."
+    )?;
+    writeln!(f)?;
+    writeln!(
+        f,
+        "/// Function names and their return types, sorted by name."
+    )?;
+    writeln!(f, "pub(crate) const RETURNS: &[(&str, &str)] = &[")?;
+    for (fun, ret) in returns {
+        writeln!(f, "    ({:?}, {:?}),", fun, ret)?;
+    }
+    writeln!(f, "];")?;
+    Ok(())
+}
+
 fn generate_mod(f: &mut File, m: &Mod) -> Result<(), DError> {
     for l in m.desc.lines() {
         writeln!(f, "//! {}", l)?;
@@ -442,6 +490,7 @@ mod mapp {
 
     pub fn manual_fn(fun: &Func) -> bool {
         match fun.name.as_str() {
+            "CONVERT" => true,
             _ => false,
         }
     }
@@ -513,7 +562,20 @@ mod mapp {
             (_, "DATEDIF", "Text", "Format") => None,
             (_, "AGGREGATE", "Integer", "Function") => None,
             (_, "AGGREGATE", "Integer", "Option") => None,
-
+            (_, "BASE", "Integer", "Radix") => None,
+            (_, "DECIMAL", "Integer", "Radix") => None,
+            (_, "DDE", "Integer", "Mode") => None,
+            (_, "NUMBERVALUE", "Text", "DecimalSeparator") => None,
+            (_, "NUMBERVALUE", "Text", "GroupSeparator") => None,
+            (_, "EUROCONVERT", "Text", "From") => None,
+            (_, "EUROCONVERT", "Text", "To") => None,
+            (_, "CEILING.MATH", "Number", "Mode") => None,
+            (_, "RANK", "Number", "Order") => None,
+            (_, "TTEST", "Integer", "Tails") => None,
+            (_, "TTEST", "Integer", "Type") => None,
+
+            ("fin", _, "Number", "PayType") => None,
+            ("fin", _, "Number", "Type") => None,
             ("fin", _, "Basis", "B") => None,
             ("fin", _, "Basis", "Basis") => None,
             ("fin", _, "Basis", "Bas") => None,
@@ -614,7 +676,21 @@ mod mapp {
             (_, "DATEDIF", "Text", "Format") => ("DateDifMethod", false),
             (_, "AGGREGATE", "Integer", "Function") => ("AggregateFunction", false),
             (_, "AGGREGATE", "Integer", "Option") => ("AggregateOption", false),
-
+            (_, "BASE", "Integer", "Radix") => ("Radix", false),
+            (_, "DECIMAL", "Integer", "Radix") => ("Radix", false),
+            (_, "DDE", "Integer", "Mode") => ("DDEConversion", false),
+            (_, "NUMBERVALUE", "Text", "DecimalSeparator") => ("NumberSeparator", false),
+            (_, "NUMBERVALUE", "Text", "GroupSeparator") => ("NumberSeparator", false),
+            (_, "EUROCONVERT", "Text", "From") => ("EuroCurrency", false),
+            (_, "EUROCONVERT", "Text", "To") => ("EuroCurrency", false),
+            (_, "CEILING.MATH", "Number", "Mode") => ("RoundingMode", false),
+            (_, "RANK", "Number", "Order") => ("RankOrder", false),
+            (_, "TTEST", "Integer", "Tails") => ("TTestTails", false),
+            (_, "TTEST", "Integer", "Type") => ("TTestType", false),
+            (_, "LEGACY.TDIST", "Integer", "Tails") => ("TTestTails", false),
+
+            ("fin", _, "Number", "PayType") => ("PayType", false),
+            ("fin", _, "Number", "Type") => ("PayType", false),
             ("fin", _, "Basis", "B") => ("YearFracMethod", false),
             ("fin", _, "Basis", "Basis") => ("YearFracMethod", false),
             ("fin", _, "Basis", "Bas") => ("YearFracMethod", false),
@@ -0,0 +1,238 @@
+//!
+//! Canonical form of a formula.
+//!
+//! Formulas that differ only in their spelling have the same canonical form:
+//!
+//! * function and named expression names are upper case,
+//! * redundant parentheses and a unary plus are dropped,
+//! * CONCATENATE with two or more parameters is written with `&`,
+//! * references to the table of the formula are written without the table,
+//! * `[.A1]:[.B2]` is written as the range `[.A1:.B2]`.
+//!
+//! Everything else is kept. `1+2` and `2+1` are different formulas, as are
+//! `[.A1]` and `[.$A$1]`.
+//!
+//! ```
+//! use spreadsheet_ods_formula::canon::Canonical;
+//!
+//! let a = Canonical::parse("of:=concatenate(\"x\";([$Sheet1.A1]))", Some("Sheet1"));
+//! let b = Canonical::parse("of:=\"x\"&[.A1]", Some("Sheet1"));
+//! assert_eq!(a.expect("a"), b.expect("b"));
+//! ```
+//!
+
+use crate::Any;
+use crate::expr::{Expr, ExprRef, InfixOp, ParseError, UnaryOp};
+use crate::sheet::FormulaType;
+use spreadsheet_ods::{CellRange, CellRef, ColRange, RowRange};
+use std::fmt::{self, Write};
+use std::hash::{Hash, Hasher};
+
+/// Formula in canonical form.
+///
+/// Equality and hashing use the canonical formula, so this can be used
+/// as key of a HashMap to deduplicate formulas.
+#[derive(Debug, Clone)]
+pub struct Canonical {
+    expr: Expr,
+    text: String,
+}
+
+impl Canonical {
+    /// Canonical form of the expression. References to `table` are written
+    /// without the table. Use None if the table of the formula is unknown.
+    pub fn new(expr: &Expr, table: Option<&str>) -> Self {
+        let expr = canonical(expr, table);
+        let mut text = String::new();
+        let _ = expr.write_formula(&mut text);
+        Self { expr, text }
+    }
+
+    /// Parses an OpenFormula formula and returns its canonical form.
+    pub fn parse(formula: &str, table: Option<&str>) -> Result<Self, ParseError> {
+        Ok(Self::new(&Expr::parse(formula)?, table))
+    }
+
+    /// Canonical form of any formula expression of this crate.
+    pub fn from_formula<T: Any + ?Sized>(f: &T, table: Option<&str>) -> Result<Self, ParseError> {
+        Ok(Self::new(&Expr::from_formula(f)?, table))
+    }
+
+    /// The canonical expression.
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// The canonical expression.
+    pub fn into_expr(self) -> Expr {
+        self.expr
+    }
+
+    /// The canonical formula without the prefix "of:=".
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The canonical formula of the expression without the prefix "of:=".
+    /// Expressions with the same key are the same formula.
+    pub fn key(expr: &Expr, table: Option<&str>) -> String {
+        Self::new(expr, table).text
+    }
+}
+
+impl PartialEq for Canonical {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for Canonical {}
+
+impl Hash for Canonical {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+impl Any for Canonical {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(&self.text)
+    }
+
+    fn formula_type(&self) -> FormulaType {
+        self.expr.formula_type()
+    }
+}
+
+/// Rewrites the expression into its canonical form. References to `table`
+/// are written without the table.
+pub fn canonical(expr: &Expr, table: Option<&str>) -> Expr {
+    match expr {
+        Expr::Number(v) => Expr::Number(if *v == 0.0 { 0.0 } else { *v }),
+        Expr::Text(_) | Expr::Error(_) | Expr::Empty => expr.clone(),
+        Expr::Ref(r) => Expr::Ref(local_ref(r, table)),
+        Expr::Array(rows) => Expr::Array(
+            rows.iter()
+                .map(|r| r.iter().map(|v| canonical(v, table)).collect())
+                .collect(),
+        ),
+        Expr::Func(name, args) => {
+            let name = name.to_uppercase();
+            let args: Vec<Expr> = args.iter().map(|v| canonical(v, table)).collect();
+            if name == "CONCATENATE" && args.len() >= 2 && !args.contains(&Expr::Empty) {
+                let mut it = args.into_iter();
+                let first = it.next().expect("args");
+                it.fold(first, concat)
+            } else {
+                Expr::Func(name, args)
+            }
+        }
+        Expr::Name(v) => Expr::Name(v.to_uppercase()),
+        Expr::Unary(UnaryOp::Plus, v) => canonical(v, table),
+        Expr::Unary(op, v) => Expr::Unary(*op, Box::new(canonical(v, table))),
+        Expr::Infix(a, op, b) => {
+            let a = canonical(a, table);
+            let b = canonical(b, table);
+            match op {
+                InfixOp::Concat => concat(a, b),
+                InfixOp::Range => match (a, b) {
+                    (Expr::Ref(ExprRef::Cell(a)), Expr::Ref(ExprRef::Cell(b))) => cell_range(a, b),
+                    (a, b) => Expr::infix(a, *op, b),
+                },
+                _ => Expr::infix(a, *op, b),
+            }
+        }
+        Expr::Paren(v) => canonical(v, table),
+    }
+}
+
+/// `&` is associative, the chain is always nested to the left.
+fn concat(a: Expr, b: Expr) -> Expr {
+    match b {
+        Expr::Infix(l, InfixOp::Concat, r) => concat(concat(a, *l), *r),
+        b => Expr::infix(a, InfixOp::Concat, b),
+    }
+}
+
+/// `[.A1]:[.B2]` as a single range, if both cells are on the same table
+/// and the corners are in order.
+fn cell_range(a: CellRef, b: CellRef) -> Expr {
+    if a.iri() == b.iri() && a.table() == b.table() && a.row() <= b.row() && a.col() <= b.col() {
+        Expr::Ref(ExprRef::Range(CellRange::new_all(
+            a.iri().cloned(),
+            a.table().cloned(),
+            a.row_abs(),
+            a.row(),
+            a.col_abs(),
+            a.col(),
+            None,
+            b.row_abs(),
+            b.row(),
+            b.col_abs(),
+            b.col(),
+        )))
+    } else {
+        Expr::infix(
+            Expr::Ref(ExprRef::Cell(a)),
+            InfixOp::Range,
+            Expr::Ref(ExprRef::Cell(b)),
+        )
+    }
+}
+
+/// Drops the table if it is the table of the formula, and the second
+/// table if it is the same as the first one.
+fn local_ref(r: &ExprRef, table: Option<&str>) -> ExprRef {
+    let from = r
+        .table()
+        .filter(|t| r.iri().is_some() || Some(t.as_str()) != table);
+    let to = r.to_table().filter(|t| Some(*t) != r.table()).cloned();
+    let to = match (from, to) {
+        (None, Some(t)) if r.iri().is_none() && Some(t.as_str()) == table => None,
+        (_, to) => to,
+    };
+    let from = from.cloned();
+    let iri = r.iri().cloned();
+    match r {
+        ExprRef::Cell(v) => ExprRef::Cell(CellRef::new_all(
+            iri,
+            from,
+            v.row_abs(),
+            v.row(),
+            v.col_abs(),
+            v.col(),
+        )),
+        ExprRef::Range(v) => ExprRef::Range(CellRange::new_all(
+            iri,
+            from,
+            v.row_abs(),
+            v.row(),
+            v.col_abs(),
+            v.col(),
+            to,
+            v.to_row_abs(),
+            v.to_row(),
+            v.to_col_abs(),
+            v.to_col(),
+        )),
+        ExprRef::Cols(v) => ExprRef::Cols(ColRange::new_all(
+            iri,
+            from,
+            v.col_abs(),
+            v.col(),
+            to,
+            v.to_col_abs(),
+            v.to_col(),
+        )),
+        ExprRef::Rows(v) => ExprRef::Rows(RowRange::new_all(
+            iri,
+            from,
+            v.row_abs(),
+            v.row(),
+            to,
+            v.to_row_abs(),
+            v.to_row(),
+        )),
+    }
+}
@@ -3,11 +3,12 @@
 //!
 
 use crate::Any;
+use std::fmt::{self, Write};
 
 pub use crate::generated::conv::*;
 
 /// Parameter for ROMAN().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RomanStyle {
     /// Only subtract powers of 10, not L or V, and only if the next
     /// number is not more than 10 times greater. A number
@@ -32,13 +33,50 @@ pub enum RomanStyle {
 }
 
 impl Any for RomanStyle {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             RomanStyle::Classic => "0",
             RomanStyle::SubLXV => "1",
             RomanStyle::SubLX => "2",
             RomanStyle::SubAllLXV => "3",
             RomanStyle::Simplified => "4",
-        });
+        })
+    }
+}
+
+/// Parameter for BASE() and DECIMAL(). A radix in the range 2..=36.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Radix(u8);
+
+impl Radix {
+    /// Radix 2.
+    pub const BINARY: Radix = Radix(2);
+    /// Radix 8.
+    pub const OCTAL: Radix = Radix(8);
+    /// Radix 10.
+    pub const DECIMAL: Radix = Radix(10);
+    /// Radix 16.
+    pub const HEXADECIMAL: Radix = Radix(16);
+
+    /// Radix, if it is in the range 2..=36.
+    pub const fn new(radix: u8) -> Option<Radix> {
+        if radix >= 2 && radix <= 36 {
+            Some(Radix(radix))
+        } else {
+            None
+        }
+    }
+
+    /// The radix.
+    pub const fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Any for Radix {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "{}", self.0)
     }
 }
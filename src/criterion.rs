@@ -0,0 +1,269 @@
+//!
+//! Builder for the criterion parameter of COUNTIF, SUMIF, AVERAGEIF and friends.
+//!
+//! LibreOffice interprets a criterion string according to a document setting.
+//! It can use wildcards, regular expressions or neither of them. Text values
+//! are escaped for the syntax given to the [CriterionBuilder].
+//!
+//! ```
+//! use spreadsheet_ods::cell;
+//! use spreadsheet_ods_formula::{formula, of};
+//! use spreadsheet_ods_formula::criterion::{CriterionBuilder, CriterionSyntax};
+//!
+//! let c = CriterionBuilder::new(CriterionSyntax::Wildcard);
+//! let f = formula(of::countif(cell!(0, 0, 9, 0), c.gt(1).unwrap()));
+//! assert_eq!(f, "of:=COUNTIF([.A1:.A10];\">1\")");
+//! let f = formula(of::countif(cell!(0, 0, 9, 0), c.text_eq("5*")));
+//! assert_eq!(f, "of:=COUNTIF([.A1:.A10];\"=5~*\")");
+//! ```
+//!
+
+use crate::{Any, Criterion, CriterionCmp, DateTime, Text, fmt_float};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Syntax used for criteria in the document.
+///
+/// This must match the settings "Enable wildcards in formulas" and
+/// "Enable regular expressions in formulas" of the document.
+/// LibreOffice uses wildcards by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CriterionSyntax {
+    /// Neither wildcards nor regular expressions.
+    Plain,
+    /// `*`, `?` and `~` as escape character.
+    #[default]
+    Wildcard,
+    /// Regular expressions.
+    Regex,
+}
+
+/// Errors for the criterion builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CriterionError {
+    /// Pattern matching needs wildcards or regular expressions.
+    PatternNotSupported(CriterionSyntax),
+    /// NaN or an infinity can't be compared with.
+    NotFinite,
+}
+
+impl Display for CriterionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CriterionError::PatternNotSupported(s) => {
+                write!(f, "pattern matching is not supported with {:?}", s)
+            }
+            CriterionError::NotFinite => write!(f, "NaN or infinite number"),
+        }
+    }
+}
+
+impl Error for CriterionError {}
+
+/// A constant criterion created with a [CriterionBuilder].
+/// The complete criterion is a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FCriterionConst(String);
+
+impl FCriterionConst {
+    /// The criterion string.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Any for FCriterionConst {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.0.as_str().write_formula(buf)
+    }
+}
+
+impl Criterion for FCriterionConst {}
+
+/// A comparison with a calculated value created with a [CriterionBuilder].
+#[derive(Debug, Clone)]
+pub struct FCriterionValue<A: Any>(CriterionCmp, A);
+
+impl<A: Any> Any for FCriterionValue<A> {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "\"{}\"", self.0)?;
+        buf.write_char('&')?;
+        self.1.write_formula(buf)
+    }
+}
+
+impl<A: Any> Criterion for FCriterionValue<A> {}
+
+/// Creates criteria for one [CriterionSyntax].
+///
+/// Constant values are folded into a single string. Text is escaped,
+/// so it matches literally.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CriterionBuilder {
+    syntax: CriterionSyntax,
+}
+
+impl CriterionBuilder {
+    /// Builder for the given syntax.
+    pub fn new(syntax: CriterionSyntax) -> Self {
+        Self { syntax }
+    }
+
+    /// Syntax.
+    pub fn syntax(&self) -> CriterionSyntax {
+        self.syntax
+    }
+
+    fn number(&self, cmp: CriterionCmp, value: f64) -> Result<FCriterionConst, CriterionError> {
+        if !value.is_finite() {
+            return Err(CriterionError::NotFinite);
+        }
+        let mut buf = cmp.to_string();
+        let _ = fmt_float(value, value, &mut buf);
+        Ok(FCriterionConst(buf))
+    }
+
+    /// Equal to a number.
+    pub fn eq<N: Into<f64>>(&self, value: N) -> Result<FCriterionConst, CriterionError> {
+        self.number(CriterionCmp::Eq, value.into())
+    }
+
+    /// Not equal to a number.
+    pub fn ne<N: Into<f64>>(&self, value: N) -> Result<FCriterionConst, CriterionError> {
+        self.number(CriterionCmp::Ne, value.into())
+    }
+
+    /// Less than a number.
+    pub fn lt<N: Into<f64>>(&self, value: N) -> Result<FCriterionConst, CriterionError> {
+        self.number(CriterionCmp::Lt, value.into())
+    }
+
+    /// Greater than a number.
+    pub fn gt<N: Into<f64>>(&self, value: N) -> Result<FCriterionConst, CriterionError> {
+        self.number(CriterionCmp::Gt, value.into())
+    }
+
+    /// Less than or equal to a number.
+    pub fn lte<N: Into<f64>>(&self, value: N) -> Result<FCriterionConst, CriterionError> {
+        self.number(CriterionCmp::LtEq, value.into())
+    }
+
+    /// Greater than or equal to a number.
+    pub fn gte<N: Into<f64>>(&self, value: N) -> Result<FCriterionConst, CriterionError> {
+        self.number(CriterionCmp::GtEq, value.into())
+    }
+
+    /// Escapes all characters that have a special meaning in the syntax.
+    pub fn escape(&self, text: &str) -> String {
+        let special: &[char] = match self.syntax {
+            CriterionSyntax::Plain => return text.to_string(),
+            CriterionSyntax::Wildcard => &['~', '*', '?'],
+            CriterionSyntax::Regex => &[
+                '\\', '.', '^', '$', '*', '+', '?', '(', ')', '[', ']', '{', '}', '|',
+            ],
+        };
+        let esc = match self.syntax {
+            CriterionSyntax::Wildcard => '~',
+            _ => '\\',
+        };
+        let mut buf = String::with_capacity(text.len());
+        for c in text.chars() {
+            if special.contains(&c) {
+                buf.push(esc);
+            }
+            buf.push(c);
+        }
+        buf
+    }
+
+    /// Equal to the text. An empty text matches empty cells, the
+    /// same as [CriterionBuilder::empty].
+    pub fn text_eq(&self, text: &str) -> FCriterionConst {
+        FCriterionConst(format!("={}", self.escape(text)))
+    }
+
+    /// Not equal to the text.
+    pub fn text_ne(&self, text: &str) -> FCriterionConst {
+        FCriterionConst(format!("<>{}", self.escape(text)))
+    }
+
+    /// Text starts with the prefix.
+    pub fn starts_with(&self, text: &str) -> Result<FCriterionConst, CriterionError> {
+        self.pattern_around(CriterionCmp::Eq, false, text, true)
+    }
+
+    /// Text ends with the suffix.
+    pub fn ends_with(&self, text: &str) -> Result<FCriterionConst, CriterionError> {
+        self.pattern_around(CriterionCmp::Eq, true, text, false)
+    }
+
+    /// Text contains the text.
+    pub fn contains(&self, text: &str) -> Result<FCriterionConst, CriterionError> {
+        self.pattern_around(CriterionCmp::Eq, true, text, true)
+    }
+
+    /// Text doesn't contain the text.
+    pub fn not_contains(&self, text: &str) -> Result<FCriterionConst, CriterionError> {
+        self.pattern_around(CriterionCmp::Ne, true, text, true)
+    }
+
+    fn pattern_around(
+        &self,
+        cmp: CriterionCmp,
+        leading: bool,
+        text: &str,
+        trailing: bool,
+    ) -> Result<FCriterionConst, CriterionError> {
+        let any = match self.syntax {
+            CriterionSyntax::Plain => {
+                return Err(CriterionError::PatternNotSupported(self.syntax));
+            }
+            CriterionSyntax::Wildcard => "*",
+            CriterionSyntax::Regex => ".*",
+        };
+        Ok(FCriterionConst(format!(
+            "{}{}{}{}",
+            cmp,
+            if leading { any } else { "" },
+            self.escape(text),
+            if trailing { any } else { "" }
+        )))
+    }
+
+    /// Match with a pattern in the syntax of the builder. The pattern is
+    /// not escaped.
+    pub fn pattern(&self, pattern: &str) -> Result<FCriterionConst, CriterionError> {
+        if self.syntax == CriterionSyntax::Plain {
+            return Err(CriterionError::PatternNotSupported(self.syntax));
+        }
+        Ok(FCriterionConst(format!("={}", pattern)))
+    }
+
+    /// Empty cells.
+    pub fn empty(&self) -> FCriterionConst {
+        FCriterionConst("=".into())
+    }
+
+    /// Cells that are not empty.
+    pub fn non_empty(&self) -> FCriterionConst {
+        FCriterionConst("<>".into())
+    }
+
+    /// Compare with a date. The date is given as a formula, e.g. DATE().
+    pub fn date<D: DateTime>(&self, cmp: CriterionCmp, date: D) -> FCriterionValue<D> {
+        FCriterionValue(cmp, date)
+    }
+
+    /// Compare with a calculated value.
+    pub fn calc<A: Any>(&self, cmp: CriterionCmp, value: A) -> FCriterionValue<A> {
+        FCriterionValue(cmp, value)
+    }
+
+    /// Compare with a calculated text. The text is used as is, there is no
+    /// escaping at runtime.
+    pub fn calc_text<A: Text>(&self, cmp: CriterionCmp, value: A) -> FCriterionValue<A> {
+        FCriterionValue(cmp, value)
+    }
+}
@@ -0,0 +1,371 @@
+//!
+//! Extraction of common subexpressions.
+//!
+//! A subexpression that occurs more than once is calculated more than once.
+//! [extract_let] binds it to a name with LET, [extract_cells] moves it to a
+//! helper cell and refers to that cell instead. Candidates are function calls
+//! and operators with a function call inside. The longest repeated
+//! subexpression is extracted first.
+//!
+//! Subexpressions with volatile functions are not extracted, two RAND()
+//! are two different numbers. Nothing inside an existing LET is extracted.
+//! [extract_cells] keeps ROW(), COLUMN() and SHEET() without arguments in
+//! the formula, in a helper cell they would give the position of the helper.
+//!
+//! ```
+//! use spreadsheet_ods_formula::cse::extract_let;
+//! use spreadsheet_ods_formula::expr::Expr;
+//! use spreadsheet_ods_formula::formula;
+//!
+//! let e = Expr::parse(
+//!     "of:=IF(ISNA(VLOOKUP([.A1];[.D1:.E9];2;0));0;VLOOKUP([.A1];[.D1:.E9];2;0))",
+//! )
+//! .expect("expr");
+//! assert_eq!(
+//!     formula(extract_let(&e)),
+//!     "of:=COM.MICROSOFT.LET(_cse1;VLOOKUP([.A1];[.D1:.E9];2;0);IF(ISNA(_cse1);0;_cse1))"
+//! );
+//! ```
+//!
+
+use crate::canon::{Canonical, canonical};
+use crate::expr::{Expr, ExprRef, InfixOp};
+use crate::simplify::is_volatile;
+use spreadsheet_ods::CellRef;
+use std::collections::HashMap;
+
+/// Functions that can return a range or an array. A helper cell would
+/// only get one value of the result.
+const ARRAY_FUNCTIONS: &[&str] = &[
+    "CHOOSE",
+    "COM.MICROSOFT.FILTER",
+    "COM.MICROSOFT.IFS",
+    "COM.MICROSOFT.SEQUENCE",
+    "COM.MICROSOFT.SORT",
+    "COM.MICROSOFT.SORTBY",
+    "COM.MICROSOFT.SWITCH",
+    "COM.MICROSOFT.UNIQUE",
+    "FREQUENCY",
+    "IF",
+    "INDEX",
+    "MINVERSE",
+    "MMULT",
+    "MUNIT",
+    "TRANSPOSE",
+];
+
+/// Formulas with their common subexpressions in helper cells.
+#[derive(Debug, Clone)]
+pub struct Extracted {
+    formulas: Vec<Expr>,
+    helpers: Vec<(CellRef, Expr)>,
+}
+
+impl Extracted {
+    /// The rewritten formulas, in the order of the input.
+    pub fn formulas(&self) -> &[Expr] {
+        &self.formulas
+    }
+
+    /// Helper cells and the formulas to write there.
+    pub fn helpers(&self) -> &[(CellRef, Expr)] {
+        &self.helpers
+    }
+
+    /// Formulas and helper cells.
+    pub fn into_parts(self) -> (Vec<Expr>, Vec<(CellRef, Expr)>) {
+        (self.formulas, self.helpers)
+    }
+}
+
+/// Binds repeated subexpressions with LET. The names are `_cse1`, `_cse2`
+/// and so on, skipping names that are already used in the formula.
+/// Returns the formula unchanged if nothing is repeated.
+pub fn extract_let(expr: &Expr) -> Expr {
+    let mut used = Vec::new();
+    names(expr, &mut used);
+    let mut n = 0;
+    let mut next = || loop {
+        n += 1;
+        let name = format!("_cse{}", n);
+        if !used.contains(&name.to_uppercase()) {
+            return Some(Expr::Name(name));
+        }
+    };
+    let (mut roots, bindings) = extract(vec![expr.clone()], None, &mut next);
+    let calc = roots.remove(0);
+    if bindings.is_empty() {
+        return calc;
+    }
+
+    // Bindings that use other bindings must come after them.
+    let mut args = Vec::new();
+    let mut open = bindings;
+    while !open.is_empty() {
+        let pos = open
+            .iter()
+            .position(|(_, v)| !open.iter().any(|(name, _)| contains(v, name)))
+            .unwrap_or(0);
+        let (name, value) = open.remove(pos);
+        args.push(name);
+        args.push(value);
+    }
+    args.push(calc);
+    Expr::func("COM.MICROSOFT.LET", args)
+}
+
+/// Moves subexpressions that are repeated in one or more formulas of the
+/// table `table` to the helper cells, in the given order. Stops when all
+/// cells are used. Helper cells that are referenced by one of the formulas
+/// are skipped. If a helper cell is on another table, the references
+/// without a table are written with `table`.
+///
+/// Subexpressions that can return a range or an array are not moved,
+/// e.g. INDEX, IF or anything with a range, an array or a named
+/// expression in it.
+pub fn extract_cells(formulas: &[Expr], table: &str, cells: &[CellRef]) -> Extracted {
+    let used: Vec<Expr> = formulas.iter().map(|f| canonical(f, Some(table))).collect();
+    let mut it = cells
+        .iter()
+        .filter(|c| !used.iter().any(|f| refers(f, table, c)));
+    let mut next = || it.next().map(|c| Expr::Ref(ExprRef::Cell(c.clone())));
+    let (formulas, helpers) = extract(formulas.to_vec(), Some(table), &mut next);
+    let helpers = helpers
+        .into_iter()
+        .filter_map(|(r, mut v)| match r {
+            Expr::Ref(ExprRef::Cell(c)) => {
+                if c.table().is_some_and(|t| t != table) {
+                    qualify(&mut v, table);
+                }
+                Some((c, v))
+            }
+            _ => None,
+        })
+        .collect();
+    Extracted { formulas, helpers }
+}
+
+/// Replaces the repeated subexpressions with the expressions returned
+/// by `next`, as long as there are some. Helper cells are used if
+/// `cells` is the table of the formulas.
+fn extract(
+    mut roots: Vec<Expr>,
+    cells: Option<&str>,
+    next: &mut dyn FnMut() -> Option<Expr>,
+) -> (Vec<Expr>, Vec<(Expr, Expr)>) {
+    let mut bindings: Vec<(Expr, Expr)> = Vec::new();
+    loop {
+        let mut found = Found::default();
+        for e in roots.iter().chain(bindings.iter().map(|(_, v)| v)) {
+            collect(e, cells, &mut found);
+        }
+        let mut best: Option<(String, usize, Expr)> = None;
+        for v in found.list {
+            if v.1 >= 2 && best.as_ref().is_none_or(|b| v.0.len() > b.0.len()) {
+                best = Some(v);
+            }
+        }
+        let Some((key, _, value)) = best else {
+            break;
+        };
+        let Some(placeholder) = next() else {
+            break;
+        };
+        for e in roots.iter_mut() {
+            replace(e, cells, &key, &placeholder);
+        }
+        for (_, v) in bindings.iter_mut() {
+            replace(v, cells, &key, &placeholder);
+        }
+        bindings.push((placeholder, value));
+    }
+    (roots, bindings)
+}
+
+/// Candidates in order of their first occurrence.
+#[derive(Default)]
+struct Found {
+    index: HashMap<String, usize>,
+    list: Vec<(String, usize, Expr)>,
+}
+
+fn is_let(e: &Expr) -> bool {
+    matches!(e, Expr::Func(name, _) if name.eq_ignore_ascii_case("COM.MICROSOFT.LET"))
+}
+
+fn has_func(e: &Expr) -> bool {
+    match e {
+        Expr::Func(..) => true,
+        Expr::Unary(_, v) | Expr::Paren(v) => has_func(v),
+        Expr::Infix(a, _, b) => has_func(a) || has_func(b),
+        _ => false,
+    }
+}
+
+/// Only constants and single cells. A range, an array or a named
+/// expression anywhere in it can make a function return an array.
+fn is_scalar(e: &Expr) -> bool {
+    match e {
+        Expr::Number(_) | Expr::Text(_) | Expr::Error(_) | Expr::Empty => true,
+        Expr::Ref(r) => matches!(r, ExprRef::Cell(_)),
+        Expr::Array(_) | Expr::Name(_) => false,
+        Expr::Func(_, args) => args.iter().all(is_scalar),
+        Expr::Unary(_, v) | Expr::Paren(v) => is_scalar(v),
+        Expr::Infix(a, op, b) => {
+            !matches!(op, InfixOp::Range | InfixOp::Union | InfixOp::Intersect)
+                && is_scalar(a)
+                && is_scalar(b)
+        }
+    }
+}
+
+/// Functions that without arguments return something about the calling
+/// cell. Moved to a helper cell they would describe the helper cell.
+const POSITION_FUNCTIONS: &[&str] = &["COLUMN", "ROW", "SHEET"];
+
+/// Depends on the cell the formula is in.
+fn is_positional(e: &Expr) -> bool {
+    match e {
+        Expr::Func(name, args) => {
+            name.eq_ignore_ascii_case("ORG.OPENOFFICE.CURRENT")
+                || args.is_empty()
+                    && POSITION_FUNCTIONS
+                        .iter()
+                        .any(|v| name.eq_ignore_ascii_case(v))
+                || args.iter().any(is_positional)
+        }
+        Expr::Array(rows) => rows.iter().flatten().any(is_positional),
+        Expr::Unary(_, v) | Expr::Paren(v) => is_positional(v),
+        Expr::Infix(a, _, b) => is_positional(a) || is_positional(b),
+        _ => false,
+    }
+}
+
+fn is_candidate(e: &Expr, cells: bool) -> bool {
+    let candidate = match e {
+        Expr::Func(_, args) if args.is_empty() => false,
+        Expr::Func(name, _) => {
+            !cells || !ARRAY_FUNCTIONS.iter().any(|v| name.eq_ignore_ascii_case(v))
+        }
+        Expr::Unary(..) | Expr::Infix(..) => has_func(e),
+        _ => false,
+    };
+    candidate && (!cells || is_scalar(e) && !is_positional(e)) && !is_volatile(e)
+}
+
+fn collect(e: &Expr, cells: Option<&str>, found: &mut Found) {
+    if is_let(e) {
+        return;
+    }
+    if is_candidate(e, cells.is_some()) {
+        let k = Canonical::key(e, cells);
+        match found.index.get(&k) {
+            Some(i) => found.list[*i].1 += 1,
+            None => {
+                found.index.insert(k.clone(), found.list.len());
+                found.list.push((k, 1, e.clone()));
+            }
+        }
+    }
+    match e {
+        Expr::Func(_, args) => args.iter().for_each(|v| collect(v, cells, found)),
+        Expr::Unary(_, v) | Expr::Paren(v) => collect(v, cells, found),
+        Expr::Infix(a, _, b) => {
+            collect(a, cells, found);
+            collect(b, cells, found);
+        }
+        _ => {}
+    }
+}
+
+fn replace(e: &mut Expr, cells: Option<&str>, k: &str, placeholder: &Expr) {
+    if is_let(e) {
+        return;
+    }
+    if matches!(e, Expr::Func(..) | Expr::Unary(..) | Expr::Infix(..))
+        && Canonical::key(e, cells) == k
+    {
+        *e = placeholder.clone();
+        return;
+    }
+    match e {
+        Expr::Func(_, args) => args
+            .iter_mut()
+            .for_each(|v| replace(v, cells, k, placeholder)),
+        Expr::Unary(_, v) | Expr::Paren(v) => replace(v, cells, k, placeholder),
+        Expr::Infix(a, _, b) => {
+            replace(a, cells, k, placeholder);
+            replace(b, cells, k, placeholder);
+        }
+        _ => {}
+    }
+}
+
+/// The canonical formula refers to the cell. A reference across tables
+/// is assumed to refer to it on every table.
+fn refers(e: &Expr, table: &str, cell: &CellRef) -> bool {
+    match e {
+        Expr::Ref(r) => {
+            let of = |t: Option<&String>| t.map_or(table.to_string(), String::clone);
+            let across = r.to_table().is_some_and(|t| Some(t) != r.table());
+            let same = r.iri().is_none() && of(r.table()) == of(cell.table());
+            (same || across)
+                && match r {
+                    ExprRef::Cell(v) => v.row() == cell.row() && v.col() == cell.col(),
+                    ExprRef::Range(v) => v.contains(cell.row(), cell.col()),
+                    ExprRef::Cols(v) => v.contains(cell.col()),
+                    ExprRef::Rows(v) => v.contains(cell.row()),
+                }
+        }
+        Expr::Func(_, args) => args.iter().any(|v| refers(v, table, cell)),
+        Expr::Array(rows) => rows.iter().flatten().any(|v| refers(v, table, cell)),
+        Expr::Unary(_, v) | Expr::Paren(v) => refers(v, table, cell),
+        Expr::Infix(a, _, b) => refers(a, table, cell) || refers(b, table, cell),
+        _ => false,
+    }
+}
+
+/// Writes references without a table with `table`.
+fn qualify(e: &mut Expr, table: &str) {
+    match e {
+        Expr::Ref(r) if r.table().is_none() => match r {
+            ExprRef::Cell(v) => v.set_table(table),
+            ExprRef::Range(v) => v.set_table(table),
+            ExprRef::Cols(v) => v.set_table(table),
+            ExprRef::Rows(v) => v.set_table(table),
+        },
+        Expr::Func(_, args) => args.iter_mut().for_each(|v| qualify(v, table)),
+        Expr::Array(rows) => rows.iter_mut().flatten().for_each(|v| qualify(v, table)),
+        Expr::Unary(_, v) | Expr::Paren(v) => qualify(v, table),
+        Expr::Infix(a, _, b) => {
+            qualify(a, table);
+            qualify(b, table);
+        }
+        _ => {}
+    }
+}
+
+/// All names in upper case.
+fn names(e: &Expr, buf: &mut Vec<String>) {
+    match e {
+        Expr::Name(v) => buf.push(v.to_uppercase()),
+        Expr::Func(_, args) => args.iter().for_each(|v| names(v, buf)),
+        Expr::Array(rows) => rows.iter().flatten().for_each(|v| names(v, buf)),
+        Expr::Unary(_, v) | Expr::Paren(v) => names(v, buf),
+        Expr::Infix(a, _, b) => {
+            names(a, buf);
+            names(b, buf);
+        }
+        _ => {}
+    }
+}
+
+fn contains(e: &Expr, part: &Expr) -> bool {
+    e == part
+        || match e {
+            Expr::Func(_, args) => args.iter().any(|v| contains(v, part)),
+            Expr::Unary(_, v) | Expr::Paren(v) => contains(v, part),
+            Expr::Infix(a, _, b) => contains(a, part) || contains(b, part),
+            _ => false,
+        }
+}
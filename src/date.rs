@@ -4,10 +4,13 @@
 
 pub use crate::generated::date::*;
 
-use crate::Any;
+use crate::sheet::FormulaType;
+use crate::{Any, Array, DateTime, Number, NumberOrArray, ReferenceOrArray, Scalar, Sequence};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::fmt::{self, Write};
 
 /// Parameter for DATEDIF()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DateDifMethod {
     /// Years
     Years,
@@ -24,20 +27,21 @@ pub enum DateDifMethod {
 }
 
 impl Any for DateDifMethod {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             DateDifMethod::Years => "y",
             DateDifMethod::Months => "m",
             DateDifMethod::Days => "d",
             DateDifMethod::DaysIgnoreMonthsYears => "md",
             DateDifMethod::MonthsIgnoreYears => "ym",
             DateDifMethod::DaysIgnoreYears => "yd",
-        });
+        })
     }
 }
 
 /// Parameter for DAYS360()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Days360Method {
     /// NASD Method
     USNasd,
@@ -46,16 +50,17 @@ pub enum Days360Method {
 }
 
 impl Any for Days360Method {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             Days360Method::USNasd => "FALSE()",
             Days360Method::Europe => "TRUE()",
-        });
+        })
     }
 }
 
 /// Parameter for WEEKDAY()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WeekdayMethod {
     /// Monday first, value 0.
     Monday0,
@@ -76,8 +81,9 @@ pub enum WeekdayMethod {
 }
 
 impl Any for WeekdayMethod {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             WeekdayMethod::Monday0 => "3",
             WeekdayMethod::Monday1 => "11",
             WeekdayMethod::Tuesday1 => "12",
@@ -86,12 +92,12 @@ impl Any for WeekdayMethod {
             WeekdayMethod::Friday1 => "15",
             WeekdayMethod::Saturday1 => "16",
             WeekdayMethod::Sunday1 => "17",
-        });
+        })
     }
 }
 
 /// Parameter for WEEKNUM()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WeeknumMethod {
     /// First week contains Jan 1, week starts on Monday.
     Jan1WeekMonday,
@@ -112,8 +118,9 @@ pub enum WeeknumMethod {
 }
 
 impl Any for WeeknumMethod {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             WeeknumMethod::Jan1WeekMonday => "11",
             WeeknumMethod::Jan1WeekTuesday => "12",
             WeeknumMethod::Jan1WeekWednesday => "13",
@@ -122,33 +129,241 @@ impl Any for WeeknumMethod {
             WeeknumMethod::Jan1WeekSaturday => "16",
             WeeknumMethod::Jan1WeekSunday => "17",
             WeeknumMethod::ISOWeeknum => "21",
-        });
+        })
     }
 }
 
 /// Parameter for YEARFRAC()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum YearFracMethod {
-    ///
     USNasd30_360,
-    ///
     ActualActual,
-    ///
     Actual360,
-    ///
     Actual365,
-    ///
     European30_360,
 }
 
 impl Any for YearFracMethod {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             YearFracMethod::USNasd30_360 => "0",
             YearFracMethod::ActualActual => "1",
             YearFracMethod::Actual360 => "2",
             YearFracMethod::Actual365 => "3",
             YearFracMethod::European30_360 => "4",
-        });
+        })
+    }
+}
+
+/// How chrono values are written to a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatePolicy {
+    /// As DATE() and TIME() expressions. This is the default.
+    ///
+    /// A Duration of less than a day becomes TIME(), any other duration
+    /// is written as serial number.
+    #[default]
+    Function,
+    /// As serial number of days since 1899-12-30, the default null date
+    /// of LibreOffice.
+    Serial,
+}
+
+/// A chrono value with an explicit [DatePolicy].
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use spreadsheet_ods_formula::{formula, of};
+/// use spreadsheet_ods_formula::date::{DatePolicy, FDate};
+///
+/// let d = NaiveDate::from_ymd_opt(2024, 3, 1).expect("date");
+/// assert_eq!(formula(of::weekday(d)), "of:=WEEKDAY(DATE(2024;3;1))");
+/// assert_eq!(formula(of::weekday(FDate(d, DatePolicy::Serial))), "of:=WEEKDAY(45352)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FDate<T: ChronoValue>(pub T, pub DatePolicy);
+
+/// Conversions for the chrono types.
+pub trait ChronoValue {
+    /// Writes the DATE()/TIME() expression.
+    fn date_fn(&self, buf: &mut dyn Write) -> fmt::Result;
+
+    /// Serial number of days.
+    fn serial(&self) -> f64;
+
+    /// Type of the DATE()/TIME() expression. The default is a number.
+    fn date_type(&self) -> FormulaType {
+        FormulaType::Number
+    }
+
+    /// Writes the value with the given policy.
+    fn date_formula(&self, policy: DatePolicy, buf: &mut dyn Write) -> fmt::Result {
+        match policy {
+            DatePolicy::Function => self.date_fn(buf),
+            DatePolicy::Serial => write!(buf, "{}", self.serial()),
+        }
+    }
+}
+
+fn null_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1899, 12, 30).expect("null date")
+}
+
+fn day_fraction(t: &NaiveTime) -> f64 {
+    (t.num_seconds_from_midnight() as f64 + t.nanosecond() as f64 / 1e9) / 86400.0
+}
+
+fn time_fn(t: &NaiveTime, buf: &mut dyn Write) -> fmt::Result {
+    let s = t.second() as f64 + t.nanosecond() as f64 / 1e9;
+    write!(buf, "TIME({};{};{})", t.hour(), t.minute(), s)
+}
+
+impl ChronoValue for NaiveDate {
+    fn date_fn(&self, buf: &mut dyn Write) -> fmt::Result {
+        use chrono::Datelike;
+        write!(buf, "DATE({};{};{})", self.year(), self.month(), self.day())
+    }
+
+    fn date_type(&self) -> FormulaType {
+        FormulaType::Date
+    }
+
+    fn serial(&self) -> f64 {
+        (*self - null_date()).num_days() as f64
+    }
+}
+
+impl ChronoValue for NaiveTime {
+    fn date_fn(&self, buf: &mut dyn Write) -> fmt::Result {
+        time_fn(self, buf)
+    }
+
+    fn date_type(&self) -> FormulaType {
+        FormulaType::Time
+    }
+
+    fn serial(&self) -> f64 {
+        day_fraction(self)
+    }
+}
+
+impl ChronoValue for NaiveDateTime {
+    /// With a time part the sum is in parentheses, so it can be
+    /// used as operand.
+    fn date_fn(&self, buf: &mut dyn Write) -> fmt::Result {
+        if self.time() == NaiveTime::MIN {
+            return self.date().date_fn(buf);
+        }
+        buf.write_char('(')?;
+        self.date().date_fn(buf)?;
+        buf.write_char('+')?;
+        time_fn(&self.time(), buf)?;
+        buf.write_char(')')
+    }
+
+    fn date_type(&self) -> FormulaType {
+        if self.time() == NaiveTime::MIN {
+            FormulaType::Date
+        } else {
+            FormulaType::DateTime
+        }
+    }
+
+    fn serial(&self) -> f64 {
+        self.date().serial() + day_fraction(&self.time())
+    }
+}
+
+impl ChronoValue for Duration {
+    fn date_fn(&self, buf: &mut dyn Write) -> fmt::Result {
+        if *self >= Duration::zero() && *self < Duration::days(1) {
+            let t = NaiveTime::MIN + *self;
+            time_fn(&t, buf)
+        } else {
+            write!(buf, "{}", self.serial())
+        }
+    }
+
+    fn date_type(&self) -> FormulaType {
+        if *self >= Duration::zero() && *self < Duration::days(1) {
+            FormulaType::Time
+        } else {
+            FormulaType::Number
+        }
+    }
+
+    fn serial(&self) -> f64 {
+        self.num_seconds() as f64 / 86400.0 + self.subsec_nanos() as f64 / 86400e9
+    }
+}
+
+macro_rules! value_chrono {
+    ($t:ty) => {
+        impl Any for $t {
+            any_formula!();
+            #[inline]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                self.date_fn(buf)
+            }
+            #[inline]
+            fn formula_type(&self) -> FormulaType {
+                self.date_type()
+            }
+        }
+        impl Number for $t {}
+        impl DateTime for $t {}
+        impl Sequence for $t {}
+        impl Scalar for $t {}
+        impl NumberOrArray for $t {}
+    };
+}
+
+value_chrono!(NaiveDate);
+value_chrono!(NaiveTime);
+value_chrono!(NaiveDateTime);
+value_chrono!(Duration);
+
+impl<T: ChronoValue> Any for FDate<T> {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.0.date_formula(self.1, buf)
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        match self.1 {
+            DatePolicy::Function => self.0.date_type(),
+            DatePolicy::Serial => FormulaType::Number,
+        }
+    }
+}
+impl<T: ChronoValue> Number for FDate<T> {}
+impl<T: ChronoValue> DateTime for FDate<T> {}
+impl<T: ChronoValue> Sequence for FDate<T> {}
+impl<T: ChronoValue> Scalar for FDate<T> {}
+impl<T: ChronoValue> NumberOrArray for FDate<T> {}
+
+/// A list of dates is written as an inline array of serial numbers.
+/// Inline arrays can only contain constants. An empty list is written
+/// as an omitted parameter, as there are no empty inline arrays.
+impl Any for Vec<NaiveDate> {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        buf.write_char('{')?;
+        for (i, v) in self.iter().enumerate() {
+            if i > 0 {
+                buf.write_char(';')?;
+            }
+            v.date_formula(DatePolicy::Serial, buf)?;
+        }
+        buf.write_char('}')
     }
 }
+impl Array for Vec<NaiveDate> {}
+impl Sequence for Vec<NaiveDate> {}
+impl ReferenceOrArray for Vec<NaiveDate> {}
+impl NumberOrArray for Vec<NaiveDate> {}
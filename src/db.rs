@@ -3,3 +3,354 @@
 //!
 
 pub use crate::generated::db::*;
+
+use crate::criterion::FCriterionConst;
+use crate::{Any, Database, Field};
+use spreadsheet_ods::{CellRange, Sheet};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Errors for the database and the criteria table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbError {
+    /// The number of headers doesn't match the width of the database.
+    HeaderCount(usize, u32),
+    /// The header cell in this column of the database is not a text.
+    InvalidHeader(u32),
+    /// The field is not one of the database headers.
+    UnknownField(String),
+    /// The field position is outside the database. Positions start with 1.
+    FieldPosition(u32),
+    /// There is no condition in the criteria table.
+    NoCondition,
+}
+
+impl Display for DbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::HeaderCount(h, w) => {
+                write!(f, "{} headers for a database with {} columns", h, w)
+            }
+            DbError::InvalidHeader(c) => write!(f, "no valid header in column {}", c),
+            DbError::UnknownField(n) => write!(f, "unknown database field {:?}", n),
+            DbError::FieldPosition(p) => write!(f, "no database field at position {}", p),
+            DbError::NoCondition => write!(f, "criteria without condition"),
+        }
+    }
+}
+
+impl Error for DbError {}
+
+/// A database range with its header row.
+///
+/// Hands out validated [DbField]s and can be used as the database
+/// parameter of all D* functions.
+///
+/// ```
+/// use spreadsheet_ods::CellRange;
+/// use spreadsheet_ods_formula::{formula, of};
+/// use spreadsheet_ods_formula::db::DbRange;
+///
+/// let db = DbRange::new(CellRange::local(0, 0, 99, 2), &["Name", "Age", "City"])
+///     .expect("headers");
+/// let f = formula(of::dmax(db.clone(), db.field("age").expect("age"), CellRange::local(0, 5, 1, 5)));
+/// assert_eq!(f, "of:=DMAX([.A1:.C100];\"Age\";[.F1:.F2])");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbRange {
+    range: CellRange,
+    headers: Vec<String>,
+}
+
+impl DbRange {
+    /// Database range with the given header names.
+    pub fn new<S: AsRef<str>>(range: CellRange, headers: &[S]) -> Result<Self, DbError> {
+        let width = range.to_col() - range.col() + 1;
+        if headers.len() != width as usize {
+            return Err(DbError::HeaderCount(headers.len(), width));
+        }
+        Ok(Self {
+            range,
+            headers: headers.iter().map(|v| v.as_ref().to_string()).collect(),
+        })
+    }
+
+    /// Database range with the header names read from the first row of the
+    /// range.
+    pub fn from_sheet(sheet: &Sheet, range: CellRange) -> Result<Self, DbError> {
+        let mut headers = Vec::new();
+        for (i, col) in (range.col()..=range.to_col()).enumerate() {
+            match sheet.value(range.row(), col).as_str_opt() {
+                Some(v) if !v.is_empty() => headers.push(v.to_string()),
+                _ => return Err(DbError::InvalidHeader(i as u32 + 1)),
+            }
+        }
+        Ok(Self { range, headers })
+    }
+
+    /// Database range.
+    pub fn range(&self) -> &CellRange {
+        &self.range
+    }
+
+    /// Database headers.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Field by header name, case-insensitive.
+    pub fn field(&self, name: &str) -> Result<DbField, DbError> {
+        match self.headers.iter().find(|v| v.eq_ignore_ascii_case(name)) {
+            Some(v) => Ok(DbField(FieldRef::Name(v.clone()))),
+            None => Err(DbError::UnknownField(name.into())),
+        }
+    }
+
+    /// Field by position. The first column is 1.
+    pub fn field_at(&self, pos: u32) -> Result<DbField, DbError> {
+        if pos == 0 || pos as usize > self.headers.len() {
+            return Err(DbError::FieldPosition(pos));
+        }
+        Ok(DbField(FieldRef::Position(pos)))
+    }
+}
+
+impl Any for DbRange {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.range.write_formula(buf)
+    }
+}
+
+impl Database for DbRange {}
+
+/// Validated field of a [DbRange].
+///
+/// Created with [DbRange::field] and [DbRange::field_at].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbField(FieldRef);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FieldRef {
+    Name(String),
+    Position(u32),
+}
+
+impl DbField {
+    /// Header name.
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            FieldRef::Name(v) => Some(v),
+            FieldRef::Position(_) => None,
+        }
+    }
+
+    /// Column position, starting with 1.
+    pub fn position(&self) -> Option<u32> {
+        match &self.0 {
+            FieldRef::Name(_) => None,
+            FieldRef::Position(v) => Some(*v),
+        }
+    }
+}
+
+impl Any for DbField {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        match &self.0 {
+            FieldRef::Name(v) => v.write_formula(buf)?,
+            FieldRef::Position(v) => v.write_formula(buf)?,
+        }
+        Ok(())
+    }
+}
+
+impl Field for DbField {}
+
+/// Condition for a criteria table.
+///
+/// Create with [CriteriaTable::cond] and combine with [DbCondition::and]
+/// and [DbCondition::or].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbCondition(Cond);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Cond {
+    /// Criterion for one field.
+    Field(String, String),
+    /// All conditions must match. Never empty.
+    And(Vec<DbCondition>),
+    /// One of the conditions must match. Never empty.
+    Or(Vec<DbCondition>),
+}
+
+impl DbCondition {
+    /// Both conditions must match.
+    pub fn and(self, other: DbCondition) -> DbCondition {
+        match self.0 {
+            Cond::And(mut v) => {
+                v.push(other);
+                DbCondition(Cond::And(v))
+            }
+            v => DbCondition(Cond::And(vec![DbCondition(v), other])),
+        }
+    }
+
+    /// One of the conditions must match.
+    pub fn or(self, other: DbCondition) -> DbCondition {
+        match self.0 {
+            Cond::Or(mut v) => {
+                v.push(other);
+                DbCondition(Cond::Or(v))
+            }
+            v => DbCondition(Cond::Or(vec![DbCondition(v), other])),
+        }
+    }
+
+    /// Expands into rows of field criteria. The rows are ORed, the
+    /// criteria in a row are ANDed.
+    fn rows(&self) -> Vec<Vec<(String, String)>> {
+        match &self.0 {
+            Cond::Field(f, c) => vec![vec![(f.clone(), c.clone())]],
+            Cond::Or(v) => v.iter().flat_map(|c| c.rows()).collect(),
+            Cond::And(v) => {
+                let mut rows = vec![Vec::new()];
+                for c in v {
+                    let mut next = Vec::new();
+                    for r in &rows {
+                        for cr in c.rows() {
+                            let mut r = r.clone();
+                            r.extend(cr);
+                            next.push(r);
+                        }
+                    }
+                    rows = next;
+                }
+                rows
+            }
+        }
+    }
+}
+
+/// Builds the criteria block for the D* functions and writes it into a sheet.
+///
+/// ```
+/// use spreadsheet_ods::{CellRange, Sheet};
+/// use spreadsheet_ods_formula::{formula, of};
+/// use spreadsheet_ods_formula::criterion::CriterionBuilder;
+/// use spreadsheet_ods_formula::db::{CriteriaTable, DbRange};
+///
+/// let c = CriterionBuilder::default();
+/// let db = DbRange::new(CellRange::local(0, 0, 99, 2), &["Name", "Age", "City"])
+///     .expect("headers");
+/// let mut t = CriteriaTable::new(&db);
+/// let cond = t.cond("Age", c.gt(30).expect("30")).expect("age")
+///     .and(t.cond("City", c.text_eq("Graz")).expect("city"));
+/// t.push(cond);
+/// t.push(t.cond("Name", c.text_eq("Anna")).expect("name"));
+///
+/// let mut sheet = Sheet::new("Data");
+/// let criteria = t.write(&mut sheet, 0, 5).expect("write");
+/// let f = formula(of::dsum(db.clone(), db.field("Age").expect("age"), criteria));
+///
+/// assert_eq!(f, "of:=DSUM([.A1:.C100];\"Age\";[Data.F1:.H3])");
+/// assert_eq!(sheet.value(0, 6).as_str_or(""), "Age");
+/// assert_eq!(sheet.value(1, 6).as_str_or(""), ">30");
+/// assert_eq!(sheet.value(1, 7).as_str_or(""), "=Graz");
+/// assert_eq!(sheet.value(2, 5).as_str_or(""), "=Anna");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CriteriaTable {
+    database: DbRange,
+    conditions: Vec<DbCondition>,
+}
+
+impl CriteriaTable {
+    /// Criteria for the database.
+    pub fn new(database: &DbRange) -> Self {
+        Self {
+            database: database.clone(),
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Database.
+    pub fn database(&self) -> &DbRange {
+        &self.database
+    }
+
+    /// Condition for one field. The field name is checked against the
+    /// headers, case-insensitive.
+    pub fn cond(&self, field: &str, criterion: FCriterionConst) -> Result<DbCondition, DbError> {
+        let Some(header) = self
+            .database
+            .headers
+            .iter()
+            .find(|v| v.eq_ignore_ascii_case(field))
+        else {
+            return Err(DbError::UnknownField(field.into()));
+        };
+        Ok(DbCondition(Cond::Field(
+            header.clone(),
+            criterion.as_str().to_string(),
+        )))
+    }
+
+    /// Adds a condition. All added conditions are ORed.
+    pub fn push(&mut self, cond: DbCondition) {
+        self.conditions.push(cond);
+    }
+
+    /// Writes the criteria block with its header row at the given position.
+    /// Returns the range of the block.
+    ///
+    /// Conditions created by the CriteriaTable of another database can name
+    /// fields that are not in this one, they are reported as
+    /// [DbError::UnknownField].
+    pub fn write(&self, sheet: &mut Sheet, row: u32, col: u32) -> Result<CellRange, DbError> {
+        let rows: Vec<_> = self.conditions.iter().flat_map(|c| c.rows()).collect();
+        if rows.iter().all(|r| r.is_empty()) {
+            return Err(DbError::NoCondition);
+        }
+        if let Some((f, _)) = rows
+            .iter()
+            .flatten()
+            .find(|(f, _)| !self.database.headers.contains(f))
+        {
+            return Err(DbError::UnknownField(f.clone()));
+        }
+
+        // a field can occur more than once in a row.
+        let mut columns: Vec<&String> = Vec::new();
+        for h in &self.database.headers {
+            let n = rows
+                .iter()
+                .map(|r| r.iter().filter(|(f, _)| f == h).count())
+                .max()
+                .unwrap_or_default();
+            columns.extend(std::iter::repeat_n(h, n));
+        }
+
+        for (i, h) in columns.iter().enumerate() {
+            sheet.set_value(row, col + i as u32, h.as_str());
+        }
+        for (j, r) in rows.iter().enumerate() {
+            let mut used = vec![false; columns.len()];
+            for (f, c) in r {
+                // the columns hold each field as often as the rows need.
+                if let Some(i) = (0..columns.len()).find(|i| !used[*i] && columns[*i] == f) {
+                    used[i] = true;
+                    sheet.set_value(row + 1 + j as u32, col + i as u32, c.as_str());
+                }
+            }
+        }
+
+        Ok(CellRange::remote(
+            sheet.name().as_str(),
+            row,
+            col,
+            row + rows.len() as u32,
+            col + columns.len() as u32 - 1,
+        ))
+    }
+}
@@ -0,0 +1,1116 @@
+//!
+//! Formula dialects other than OpenFormula.
+//!
+//! [crate::formula_with] translates a formula for Excel. References are
+//! written in A1 syntax, function names are mapped to their Excel names.
+//! Functions without an Excel equivalent are reported as an error.
+//!
+//! [crate::parse_with] reads an Excel formula. Function names are mapped
+//! back, unknown functions are reported as an error.
+//!
+//! [Dialect::R1C1] uses the same syntax with references in R1C1 notation.
+//! Relative references are written as offsets to an anchor cell, so the
+//! formula is the same for every row of a filled column.
+//!
+//! ```
+//! use spreadsheet_ods::{cell, CellRef};
+//! use spreadsheet_ods_formula::{formula_with, of};
+//! use spreadsheet_ods_formula::dialect::Dialect;
+//!
+//! let f = formula_with(
+//!     of::xlookup(cell!(0, 0), cell!("Data 1" => 0, 0, 9, 0), cell!(0, 1, 9, 1)),
+//!     Dialect::Ooxml,
+//! );
+//! assert_eq!(f.expect("ooxml"), "=_xlfn.XLOOKUP(A1,'Data 1'!A1:A10,B1:B10)");
+//! ```
+//!
+//! ```
+//! use spreadsheet_ods_formula::{formula, parse_with};
+//! use spreadsheet_ods_formula::dialect::Dialect;
+//!
+//! let f = parse_with("=SUMIFS(B:B,A:A,\">5\")", Dialect::Ooxml);
+//! assert_eq!(formula(f.expect("excel")), "of:=SUMIFS([.B:.B];[.A:.A];\">5\")");
+//! ```
+//!
+//! ```
+//! use spreadsheet_ods::{cell, CellRef};
+//! use spreadsheet_ods_formula::{formula, formula_with, num, parse_with};
+//! use spreadsheet_ods_formula::dialect::Dialect;
+//!
+//! let r1c1 = Dialect::r1c1(&CellRef::local(4, 2));
+//! let f = formula_with(num(cell!(3, 2)) * cell!(abs 0, abs 1), r1c1);
+//! assert_eq!(f.expect("r1c1"), "=R[-1]C*R1C2");
+//! let f = parse_with("=R[-1]C*R1C2", r1c1);
+//! assert_eq!(formula(f.expect("r1c1")), "of:=[.C4]*[.$B$1]");
+//! ```
+//!
+
+use crate::expr::{Expr, ExprRef, InfixOp, ParseError, UnaryOp};
+use crate::{Any, fmt_float};
+use spreadsheet_ods::{CellRange, CellRef, ColRange, RowRange};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Formula syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// OpenFormula as used in ODS files. Written with the prefix "of:=".
+    #[default]
+    OpenFormula,
+    /// Excel A1 syntax as used in OOXML files. Written with the prefix "=".
+    Ooxml,
+    /// Excel syntax with R1C1 references. Relative references are
+    /// offsets to the anchor cell at the zero based row and column.
+    R1C1 {
+        /// Row of the anchor cell.
+        row: u32,
+        /// Column of the anchor cell.
+        col: u32,
+    },
+}
+
+impl Dialect {
+    /// R1C1 syntax for a formula in the anchor cell.
+    pub fn r1c1(anchor: &CellRef) -> Self {
+        Dialect::R1C1 {
+            row: anchor.row(),
+            col: anchor.col(),
+        }
+    }
+}
+
+/// Errors when translating a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialectError {
+    /// The formula could not be parsed.
+    Parse(ParseError),
+    /// The function has no equivalent in the dialect.
+    NoEquivalent(String),
+    /// The reference can't be written in the dialect.
+    Reference(String),
+}
+
+impl Display for DialectError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DialectError::Parse(e) => write!(f, "{}", e),
+            DialectError::NoEquivalent(n) => write!(f, "function {} has no equivalent", n),
+            DialectError::Reference(r) => write!(f, "reference {} can't be translated", r),
+        }
+    }
+}
+
+impl Error for DialectError {}
+
+impl From<ParseError> for DialectError {
+    fn from(value: ParseError) -> Self {
+        DialectError::Parse(value)
+    }
+}
+
+/// Functions that Excel writes with the prefix `_xlfn.`.
+const XLFN: &[&str] = &[
+    "ACOT",
+    "ACOTH",
+    "AGGREGATE",
+    "ARABIC",
+    "BASE",
+    "BETA.DIST",
+    "BETA.INV",
+    "BINOM.DIST",
+    "BINOM.DIST.RANGE",
+    "BINOM.INV",
+    "BITAND",
+    "BITLSHIFT",
+    "BITOR",
+    "BITRSHIFT",
+    "BITXOR",
+    "CEILING.MATH",
+    "CEILING.PRECISE",
+    "CHISQ.DIST",
+    "CHISQ.DIST.RT",
+    "CHISQ.INV",
+    "CHISQ.INV.RT",
+    "CHISQ.TEST",
+    "COMBINA",
+    "CONCAT",
+    "CONFIDENCE.NORM",
+    "CONFIDENCE.T",
+    "COT",
+    "COTH",
+    "COVARIANCE.P",
+    "COVARIANCE.S",
+    "CSC",
+    "CSCH",
+    "DAYS",
+    "DECIMAL",
+    "ENCODEURL",
+    "ERF.PRECISE",
+    "ERFC.PRECISE",
+    "EXPON.DIST",
+    "F.DIST",
+    "F.DIST.RT",
+    "F.INV",
+    "F.INV.RT",
+    "F.TEST",
+    "FILTERXML",
+    "FLOOR.MATH",
+    "FLOOR.PRECISE",
+    "FORECAST.ETS",
+    "FORECAST.LINEAR",
+    "FORMULATEXT",
+    "GAMMA",
+    "GAMMA.DIST",
+    "GAMMA.INV",
+    "GAMMALN.PRECISE",
+    "GAUSS",
+    "HYPGEOM.DIST",
+    "IFNA",
+    "IFS",
+    "IMCOSH",
+    "IMCOT",
+    "IMCSC",
+    "IMCSCH",
+    "IMSEC",
+    "IMSECH",
+    "IMSINH",
+    "IMTAN",
+    "ISFORMULA",
+    "ISOWEEKNUM",
+    "LET",
+    "LOGNORM.DIST",
+    "LOGNORM.INV",
+    "MAXIFS",
+    "MINIFS",
+    "MODE.MULT",
+    "MODE.SNGL",
+    "NEGBINOM.DIST",
+    "NETWORKDAYS.INTL",
+    "NORM.DIST",
+    "NORM.INV",
+    "NORM.S.DIST",
+    "NORM.S.INV",
+    "NUMBERVALUE",
+    "PDURATION",
+    "PERCENTILE.EXC",
+    "PERCENTILE.INC",
+    "PERCENTRANK.EXC",
+    "PERCENTRANK.INC",
+    "PERMUTATIONA",
+    "PHI",
+    "POISSON.DIST",
+    "QUARTILE.EXC",
+    "QUARTILE.INC",
+    "RANDARRAY",
+    "RANK.AVG",
+    "RANK.EQ",
+    "RRI",
+    "SEC",
+    "SECH",
+    "SEQUENCE",
+    "SHEET",
+    "SHEETS",
+    "SKEW.P",
+    "SORTBY",
+    "STDEV.P",
+    "STDEV.S",
+    "SWITCH",
+    "T.DIST",
+    "T.DIST.2T",
+    "T.DIST.RT",
+    "T.INV",
+    "T.INV.2T",
+    "T.TEST",
+    "TEXTJOIN",
+    "UNICHAR",
+    "UNICODE",
+    "UNIQUE",
+    "VAR.P",
+    "VAR.S",
+    "WEBSERVICE",
+    "WEIBULL.DIST",
+    "WORKDAY.INTL",
+    "XLOOKUP",
+    "XMATCH",
+    "XOR",
+    "Z.TEST",
+];
+
+/// Functions that Excel writes with the prefix `_xlfn._xlws.`.
+const XLWS: &[&str] = &["FILTER", "SORT"];
+
+/// Functions with a different name in Excel.
+const RENAMED: &[(&str, &str)] = &[
+    ("CEILING.XCL", "CEILING"),
+    ("FLOOR.XCL", "FLOOR"),
+    ("FORMULA", "FORMULATEXT"),
+    ("JIS", "DBCS"),
+    ("SKEWP", "SKEW.P"),
+];
+
+/// Functions that Excel has with other semantics. They are written as
+/// the Excel function that computes the same. OpenFormula FDIST and FINV
+/// are left-tailed, Excel's are right-tailed. OpenFormula CEILING and
+/// FLOOR take a mode, Excel's take the sign of the significance.
+const REPLACED: &[(&str, &str)] = &[
+    ("CEILING", "CEILING.MATH"),
+    ("FDIST", "F.DIST"),
+    ("FINV", "F.INV"),
+    ("FLOOR", "FLOOR.MATH"),
+];
+
+/// Functions without an Excel equivalent. Functions with the
+/// prefixes ORG.OPENOFFICE. and ORG.LIBREOFFICE. have none either.
+const NO_EXCEL: &[&str] = &[
+    "B",
+    "CHISQDIST",
+    "CHISQINV",
+    "DDE",
+    "EUROCONVERT",
+    "MULTIPLE.OPERATIONS",
+];
+
+/// Functions with the same name in OpenFormula and Excel.
+const SAME: &[&str] = &[
+    "ABS",
+    "ACCRINT",
+    "ACCRINTM",
+    "ACOS",
+    "ACOSH",
+    "ACOT",
+    "ACOTH",
+    "ADDRESS",
+    "AGGREGATE",
+    "AMORDEGRC",
+    "AMORLINC",
+    "AND",
+    "ARABIC",
+    "AREAS",
+    "ASC",
+    "ASIN",
+    "ASINH",
+    "ATAN",
+    "ATAN2",
+    "ATANH",
+    "AVEDEV",
+    "AVERAGE",
+    "AVERAGEA",
+    "AVERAGEIF",
+    "AVERAGEIFS",
+    "BASE",
+    "BESSELI",
+    "BESSELJ",
+    "BESSELK",
+    "BESSELY",
+    "BETA.DIST",
+    "BETA.INV",
+    "BETADIST",
+    "BETAINV",
+    "BIN2DEC",
+    "BIN2HEX",
+    "BIN2OCT",
+    "BINOM.DIST",
+    "BINOM.DIST.RANGE",
+    "BINOM.INV",
+    "BINOMDIST",
+    "BITAND",
+    "BITLSHIFT",
+    "BITOR",
+    "BITRSHIFT",
+    "BITXOR",
+    "CEILING.MATH",
+    "CEILING.PRECISE",
+    "CELL",
+    "CHAR",
+    "CHOOSE",
+    "CLEAN",
+    "CODE",
+    "COLUMN",
+    "COLUMNS",
+    "COMBIN",
+    "COMBINA",
+    "COMPLEX",
+    "CONCATENATE",
+    "CONFIDENCE",
+    "CONVERT",
+    "CORREL",
+    "COS",
+    "COSH",
+    "COT",
+    "COTH",
+    "COUNT",
+    "COUNTA",
+    "COUNTBLANK",
+    "COUNTIF",
+    "COUNTIFS",
+    "COUPDAYBS",
+    "COUPDAYS",
+    "COUPDAYSNC",
+    "COUPNCD",
+    "COUPNUM",
+    "COUPPCD",
+    "COVAR",
+    "CRITBINOM",
+    "CSC",
+    "CSCH",
+    "CUMIPMT",
+    "CUMPRINC",
+    "DATE",
+    "DATEDIF",
+    "DATEVALUE",
+    "DAVERAGE",
+    "DAY",
+    "DAYS",
+    "DAYS360",
+    "DB",
+    "DCOUNT",
+    "DCOUNTA",
+    "DDB",
+    "DEC2BIN",
+    "DEC2HEX",
+    "DEC2OCT",
+    "DECIMAL",
+    "DEGREES",
+    "DELTA",
+    "DEVSQ",
+    "DGET",
+    "DISC",
+    "DMAX",
+    "DMIN",
+    "DOLLAR",
+    "DOLLARDE",
+    "DOLLARFR",
+    "DPRODUCT",
+    "DSTDEV",
+    "DSTDEVP",
+    "DSUM",
+    "DURATION",
+    "DVAR",
+    "DVARP",
+    "EDATE",
+    "EFFECT",
+    "EOMONTH",
+    "ERF",
+    "ERFC",
+    "ERROR.TYPE",
+    "EVEN",
+    "EXACT",
+    "EXP",
+    "EXPONDIST",
+    "FACT",
+    "FACTDOUBLE",
+    "FALSE",
+    "FIND",
+    "FINDB",
+    "FISHER",
+    "FISHERINV",
+    "FIXED",
+    "FORECAST",
+    "FREQUENCY",
+    "FTEST",
+    "FV",
+    "FVSCHEDULE",
+    "GAMMA",
+    "GAMMADIST",
+    "GAMMAINV",
+    "GAMMALN",
+    "GAUSS",
+    "GCD",
+    "GEOMEAN",
+    "GESTEP",
+    "GETPIVOTDATA",
+    "GROWTH",
+    "HARMEAN",
+    "HEX2BIN",
+    "HEX2DEC",
+    "HEX2OCT",
+    "HLOOKUP",
+    "HOUR",
+    "HYPERLINK",
+    "HYPGEOMDIST",
+    "IF",
+    "IFERROR",
+    "IFNA",
+    "IMABS",
+    "IMAGINARY",
+    "IMARGUMENT",
+    "IMCONJUGATE",
+    "IMCOS",
+    "IMCOSH",
+    "IMCOT",
+    "IMCSC",
+    "IMCSCH",
+    "IMDIV",
+    "IMEXP",
+    "IMLN",
+    "IMLOG10",
+    "IMLOG2",
+    "IMPOWER",
+    "IMPRODUCT",
+    "IMREAL",
+    "IMSEC",
+    "IMSECH",
+    "IMSIN",
+    "IMSINH",
+    "IMSQRT",
+    "IMSUB",
+    "IMSUM",
+    "IMTAN",
+    "INDEX",
+    "INDIRECT",
+    "INFO",
+    "INT",
+    "INTERCEPT",
+    "INTRATE",
+    "IPMT",
+    "IRR",
+    "ISBLANK",
+    "ISERR",
+    "ISERROR",
+    "ISEVEN",
+    "ISFORMULA",
+    "ISLOGICAL",
+    "ISNA",
+    "ISNONTEXT",
+    "ISNUMBER",
+    "ISODD",
+    "ISOWEEKNUM",
+    "ISPMT",
+    "ISREF",
+    "ISTEXT",
+    "KURT",
+    "LARGE",
+    "LCM",
+    "LEFT",
+    "LEFTB",
+    "LEN",
+    "LENB",
+    "LINEST",
+    "LN",
+    "LOG",
+    "LOG10",
+    "LOGEST",
+    "LOGINV",
+    "LOGNORMDIST",
+    "LOOKUP",
+    "LOWER",
+    "MATCH",
+    "MAX",
+    "MAXA",
+    "MDETERM",
+    "MDURATION",
+    "MEDIAN",
+    "MID",
+    "MIDB",
+    "MIN",
+    "MINA",
+    "MINUTE",
+    "MINVERSE",
+    "MIRR",
+    "MMULT",
+    "MOD",
+    "MODE",
+    "MONTH",
+    "MROUND",
+    "MULTINOMIAL",
+    "MUNIT",
+    "N",
+    "NA",
+    "NEGBINOMDIST",
+    "NETWORKDAYS",
+    "NOMINAL",
+    "NORMDIST",
+    "NORMINV",
+    "NOT",
+    "NOW",
+    "NPER",
+    "NPV",
+    "NUMBERVALUE",
+    "OCT2BIN",
+    "OCT2DEC",
+    "OCT2HEX",
+    "ODD",
+    "ODDFPRICE",
+    "ODDFYIELD",
+    "ODDLPRICE",
+    "ODDLYIELD",
+    "OFFSET",
+    "OR",
+    "PDURATION",
+    "PEARSON",
+    "PERCENTILE",
+    "PERCENTRANK",
+    "PERMUT",
+    "PERMUTATIONA",
+    "PHI",
+    "PI",
+    "PMT",
+    "POISSON",
+    "POWER",
+    "PPMT",
+    "PRICE",
+    "PRICEDISC",
+    "PRICEMAT",
+    "PROB",
+    "PRODUCT",
+    "PROPER",
+    "PV",
+    "QUARTILE",
+    "QUOTIENT",
+    "RADIANS",
+    "RAND",
+    "RANDBETWEEN",
+    "RANK",
+    "RATE",
+    "RECEIVED",
+    "REPLACE",
+    "REPLACEB",
+    "REPT",
+    "RIGHT",
+    "RIGHTB",
+    "ROMAN",
+    "ROUND",
+    "ROUNDDOWN",
+    "ROUNDUP",
+    "ROW",
+    "ROWS",
+    "RRI",
+    "RSQ",
+    "SEARCH",
+    "SEARCHB",
+    "SEC",
+    "SECH",
+    "SECOND",
+    "SERIESSUM",
+    "SHEET",
+    "SHEETS",
+    "SIGN",
+    "SIN",
+    "SINH",
+    "SKEW",
+    "SLN",
+    "SLOPE",
+    "SMALL",
+    "SQRT",
+    "SQRTPI",
+    "STANDARDIZE",
+    "STDEV",
+    "STDEVA",
+    "STDEVP",
+    "STDEVPA",
+    "STEYX",
+    "SUBSTITUTE",
+    "SUBTOTAL",
+    "SUM",
+    "SUMIF",
+    "SUMIFS",
+    "SUMPRODUCT",
+    "SUMSQ",
+    "SUMX2MY2",
+    "SUMX2PY2",
+    "SUMXMY2",
+    "SYD",
+    "T",
+    "TAN",
+    "TANH",
+    "TBILLEQ",
+    "TBILLPRICE",
+    "TBILLYIELD",
+    "TEXT",
+    "TIME",
+    "TIMEVALUE",
+    "TINV",
+    "TODAY",
+    "TRANSPOSE",
+    "TREND",
+    "TRIM",
+    "TRIMMEAN",
+    "TRUE",
+    "TRUNC",
+    "TTEST",
+    "TYPE",
+    "UNICHAR",
+    "UNICODE",
+    "UPPER",
+    "VALUE",
+    "VAR",
+    "VARA",
+    "VARP",
+    "VARPA",
+    "VDB",
+    "VLOOKUP",
+    "WEEKDAY",
+    "WEEKNUM",
+    "WEIBULL",
+    "WORKDAY",
+    "XIRR",
+    "XNPV",
+    "XOR",
+    "YEAR",
+    "YEARFRAC",
+    "YIELD",
+    "YIELDDISC",
+    "YIELDMAT",
+    "ZTEST",
+];
+
+/// Excel functions that OpenFormula writes with the prefix `COM.MICROSOFT.`.
+const MICROSOFT: &[&str] = &[
+    "CONCAT",
+    "FILTER",
+    "FILTERXML",
+    "IFS",
+    "LET",
+    "MAXIFS",
+    "MINIFS",
+    "RANDARRAY",
+    "SEQUENCE",
+    "SORT",
+    "SORTBY",
+    "SWITCH",
+    "TEXTJOIN",
+    "UNIQUE",
+    "WEBSERVICE",
+    "XLOOKUP",
+    "XMATCH",
+];
+
+/// Excel functions that OpenFormula writes with the prefix `LEGACY.`.
+const LEGACY: &[&str] = &[
+    "CHIDIST",
+    "CHIINV",
+    "CHITEST",
+    "FDIST",
+    "FINV",
+    "NORMSDIST",
+    "NORMSINV",
+    "TDIST",
+];
+
+/// Maps an Excel function name to the OpenFormula name.
+pub fn of_name(name: &str) -> Result<String, DialectError> {
+    let upper = name.to_ascii_uppercase();
+    let base = upper.strip_prefix("_XLFN.").unwrap_or(&upper);
+    let base = base.strip_prefix("_XLWS.").unwrap_or(base);
+    if let Some((of, _)) = RENAMED.iter().find(|(_, xl)| *xl == base) {
+        Ok(of.to_string())
+    } else if LEGACY.contains(&base) {
+        Ok(format!("LEGACY.{}", base))
+    } else if MICROSOFT.contains(&base) {
+        Ok(format!("COM.MICROSOFT.{}", base))
+    } else if SAME.contains(&base) {
+        Ok(base.to_string())
+    } else if XLFN.contains(&base) {
+        // Newer Excel functions are known to LibreOffice with this prefix.
+        Ok(format!("COM.MICROSOFT.{}", base))
+    } else {
+        Err(DialectError::NoEquivalent(name.to_string()))
+    }
+}
+
+/// Parses a formula in the dialect and maps the function names.
+pub(crate) fn read(formula: &str, dialect: Dialect) -> Result<Expr, DialectError> {
+    let mut expr = Expr::parse_dialect(formula, dialect)?;
+    if dialect != Dialect::OpenFormula {
+        of_names(&mut expr)?;
+    }
+    Ok(expr)
+}
+
+fn of_names(expr: &mut Expr) -> Result<(), DialectError> {
+    match expr {
+        Expr::Func(name, args) => {
+            *name = of_name(name)?;
+            for v in args {
+                of_names(v)?;
+            }
+        }
+        Expr::Array(rows) => {
+            for v in rows.iter_mut().flatten() {
+                of_names(v)?;
+            }
+        }
+        Expr::Unary(_, v) | Expr::Paren(v) => of_names(v)?,
+        Expr::Infix(a, _, b) => {
+            of_names(a)?;
+            of_names(b)?;
+        }
+        Expr::Name(name) => {
+            if name
+                .get(..XLPM.len())
+                .is_some_and(|v| v.eq_ignore_ascii_case(XLPM))
+            {
+                name.replace_range(..XLPM.len(), "");
+            }
+        }
+        Expr::Number(_) | Expr::Text(_) | Expr::Error(_) | Expr::Ref(_) | Expr::Empty => {}
+    }
+    Ok(())
+}
+
+/// Maps an OpenFormula function name to the name written in OOXML.
+/// Functions that are not known to have an Excel equivalent are
+/// reported as DialectError::NoEquivalent.
+pub fn ooxml_name(name: &str) -> Result<String, DialectError> {
+    let upper = name.to_ascii_uppercase();
+    if upper.starts_with("ORG.OPENOFFICE.")
+        || upper.starts_with("ORG.LIBREOFFICE.")
+        || NO_EXCEL.contains(&upper.as_str())
+    {
+        return Err(DialectError::NoEquivalent(name.to_string()));
+    }
+    let base = if let Some((_, xl)) = REPLACED.iter().find(|(of, _)| *of == upper) {
+        *xl
+    } else if let Some(base) = upper.strip_prefix("COM.MICROSOFT.") {
+        MICROSOFT
+            .iter()
+            .chain(XLFN)
+            .find(|v| **v == base)
+            .copied()
+            .ok_or_else(|| DialectError::NoEquivalent(name.to_string()))?
+    } else if let Some(base) = upper.strip_prefix("LEGACY.") {
+        LEGACY
+            .iter()
+            .find(|v| **v == base)
+            .copied()
+            .ok_or_else(|| DialectError::NoEquivalent(name.to_string()))?
+    } else if let Some((_, xl)) = RENAMED.iter().find(|(of, _)| *of == upper) {
+        *xl
+    } else if SAME.contains(&upper.as_str()) {
+        upper.as_str()
+    } else {
+        return Err(DialectError::NoEquivalent(name.to_string()));
+    };
+    Ok(if XLWS.contains(&base) {
+        format!("_xlfn._xlws.{}", base)
+    } else if XLFN.contains(&base) {
+        format!("_xlfn.{}", base)
+    } else {
+        base.to_string()
+    })
+}
+
+/// Translates the formula into the dialect.
+pub(crate) fn write(expr: &Expr, dialect: Dialect, buf: &mut String) -> Result<(), DialectError> {
+    match dialect {
+        Dialect::OpenFormula => {
+            buf.push_str("of:=");
+            expr.formula(buf);
+            Ok(())
+        }
+        Dialect::Ooxml | Dialect::R1C1 { .. } => {
+            buf.push('=');
+            ooxml(&xlpm(expr, &[]), dialect, buf)
+        }
+    }
+}
+
+/// Prefix of LET names in OOXML.
+const XLPM: &str = "_xlpm.";
+
+fn is_let(name: &str) -> bool {
+    name.eq_ignore_ascii_case("COM.MICROSOFT.LET") || name.eq_ignore_ascii_case("LET")
+}
+
+/// Adds the prefix `_xlpm.` to the names bound by LET.
+fn xlpm(expr: &Expr, bound: &[&str]) -> Expr {
+    match expr {
+        Expr::Name(name) if bound.iter().any(|v| v.eq_ignore_ascii_case(name)) => {
+            Expr::Name(format!("{}{}", XLPM, name))
+        }
+        Expr::Func(name, args) if is_let(name) => {
+            let mut bound = bound.to_vec();
+            let mut res = Vec::with_capacity(args.len());
+            for (i, v) in args.iter().enumerate() {
+                match v {
+                    // Every other parameter except the last is a new name.
+                    Expr::Name(n) if i % 2 == 0 && i + 1 < args.len() => {
+                        res.push(Expr::Name(format!("{}{}", XLPM, n)));
+                        bound.push(n);
+                    }
+                    v => res.push(xlpm(v, &bound)),
+                }
+            }
+            Expr::Func(name.clone(), res)
+        }
+        Expr::Func(name, args) => {
+            Expr::Func(name.clone(), args.iter().map(|v| xlpm(v, bound)).collect())
+        }
+        Expr::Array(rows) => Expr::Array(
+            rows.iter()
+                .map(|r| r.iter().map(|v| xlpm(v, bound)).collect())
+                .collect(),
+        ),
+        Expr::Unary(op, v) => Expr::Unary(*op, Box::new(xlpm(v, bound))),
+        Expr::Infix(a, op, b) => Expr::infix(xlpm(a, bound), *op, xlpm(b, bound)),
+        Expr::Paren(v) => Expr::Paren(Box::new(xlpm(v, bound))),
+        _ => expr.clone(),
+    }
+}
+
+fn ooxml_operand(
+    e: &Expr,
+    min: u8,
+    dialect: Dialect,
+    buf: &mut String,
+) -> Result<(), DialectError> {
+    if e.precedence() < min {
+        buf.push('(');
+        ooxml(e, dialect, buf)?;
+        buf.push(')');
+        Ok(())
+    } else {
+        ooxml(e, dialect, buf)
+    }
+}
+
+fn ooxml(expr: &Expr, dialect: Dialect, buf: &mut String) -> Result<(), DialectError> {
+    match expr {
+        Expr::Number(v) => {
+            let _ = fmt_float(*v, *v, buf);
+        }
+        Expr::Text(_) | Expr::Error(_) | Expr::Name(_) | Expr::Empty => expr.formula(buf),
+        Expr::Ref(r) => match dialect {
+            Dialect::R1C1 { row, col } => r1c1_ref(r, row, col, buf)?,
+            _ => ooxml_ref(r, buf)?,
+        },
+        Expr::Array(rows) => {
+            buf.push('{');
+            for (i, r) in rows.iter().enumerate() {
+                if i > 0 {
+                    buf.push(';');
+                }
+                for (j, v) in r.iter().enumerate() {
+                    if j > 0 {
+                        buf.push(',');
+                    }
+                    ooxml(v, dialect, buf)?;
+                }
+            }
+            buf.push('}');
+        }
+        Expr::Func(name, args) => {
+            if let Some(v) = expr.as_bool() {
+                buf.push_str(if v { "TRUE" } else { "FALSE" });
+                return Ok(());
+            }
+            buf.push_str(&ooxml_name(name)?);
+            buf.push('(');
+            for (i, v) in args.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                ooxml(v, dialect, buf)?;
+            }
+            // Cumulative is optional for FDIST, but not for F.DIST.
+            if name.eq_ignore_ascii_case("FDIST") && args.len() == 3 {
+                buf.push_str(",TRUE");
+            }
+            buf.push(')');
+        }
+        Expr::Unary(op, v) => {
+            if *op == UnaryOp::Percent {
+                ooxml_operand(v, op.precedence(), dialect, buf)?;
+                buf.push_str(op.symbol());
+            } else {
+                buf.push_str(op.symbol());
+                ooxml_operand(v, op.precedence(), dialect, buf)?;
+            }
+        }
+        Expr::Infix(a, op, b) => {
+            // The union operator is a comma in Excel and always
+            // needs parentheses to separate it from the arguments.
+            if *op == InfixOp::Union {
+                buf.push('(');
+            }
+            ooxml_operand(a, op.precedence(), dialect, buf)?;
+            buf.push_str(match op {
+                InfixOp::Union => ",",
+                InfixOp::Intersect => " ",
+                op => op.symbol(),
+            });
+            ooxml_operand(b, op.precedence() + 1, dialect, buf)?;
+            if *op == InfixOp::Union {
+                buf.push(')');
+            }
+        }
+        Expr::Paren(v) => {
+            if matches!(**v, Expr::Infix(_, InfixOp::Union, _)) {
+                ooxml(v, dialect, buf)?;
+            } else {
+                buf.push('(');
+                ooxml(v, dialect, buf)?;
+                buf.push(')');
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sheet names are quoted unless they are simple identifiers that can't
+/// be mistaken for a cell reference.
+fn ooxml_sheet(name: &str, buf: &mut String) {
+    let simple = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        && !looks_like_ref(name);
+    if simple {
+        buf.push_str(name);
+    } else {
+        buf.push('\'');
+        buf.push_str(&name.replace('\'', "''"));
+        buf.push('\'');
+    }
+}
+
+/// Names like A1 or R1C1 would be read as references.
+fn looks_like_ref(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    let letters = upper.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let a1 = letters.len() < upper.len()
+        && !letters.is_empty()
+        && letters.chars().all(|c| c.is_ascii_digit());
+    let r1c1 = upper.starts_with('R')
+        && upper[1..]
+            .split_once('C')
+            .is_some_and(|(r, c)| r.chars().chain(c.chars()).all(|c| c.is_ascii_digit()));
+    a1 || r1c1 || upper == "R" || upper == "C"
+}
+
+fn ooxml_tables(
+    r: &ExprRef,
+    table: Option<&String>,
+    to_table: Option<&String>,
+    buf: &mut String,
+) -> Result<(), DialectError> {
+    if r.iri().is_some() {
+        return Err(DialectError::Reference(source(r)));
+    }
+    match (table, to_table) {
+        (Some(t), Some(tt)) if t != tt => {
+            ooxml_sheet(t, buf);
+            buf.push(':');
+            ooxml_sheet(tt, buf);
+            buf.push('!');
+        }
+        (Some(t), _) => {
+            ooxml_sheet(t, buf);
+            buf.push('!');
+        }
+        (None, Some(_)) => return Err(DialectError::Reference(source(r))),
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+pub(crate) fn a1_col(abs: bool, col: u32, buf: &mut String) {
+    if abs {
+        buf.push('$');
+    }
+    let mut name = Vec::new();
+    let mut col = col as u64 + 1;
+    while col > 0 {
+        name.push(b'A' + ((col - 1) % 26) as u8);
+        col = (col - 1) / 26;
+    }
+    name.reverse();
+    buf.extend(name.iter().map(|&c| c as char));
+}
+
+pub(crate) fn a1_row(abs: bool, row: u32, buf: &mut String) {
+    if abs {
+        buf.push('$');
+    }
+    buf.push_str(&(row as u64 + 1).to_string());
+}
+
+fn a1_cell(c: &CellRef, buf: &mut String) {
+    a1_col(c.col_abs(), c.col(), buf);
+    a1_row(c.row_abs(), c.row(), buf);
+}
+
+fn a1_range(c: &CellRange, buf: &mut String) {
+    a1_col(c.col_abs(), c.col(), buf);
+    a1_row(c.row_abs(), c.row(), buf);
+    buf.push(':');
+    a1_col(c.to_col_abs(), c.to_col(), buf);
+    a1_row(c.to_row_abs(), c.to_row(), buf);
+}
+
+fn a1_cols(c: &ColRange, buf: &mut String) {
+    a1_col(c.col_abs(), c.col(), buf);
+    buf.push(':');
+    a1_col(c.to_col_abs(), c.to_col(), buf);
+}
+
+fn a1_rows(c: &RowRange, buf: &mut String) {
+    a1_row(c.row_abs(), c.row(), buf);
+    buf.push(':');
+    a1_row(c.to_row_abs(), c.to_row(), buf);
+}
+
+fn ooxml_ref(r: &ExprRef, buf: &mut String) -> Result<(), DialectError> {
+    ooxml_tables(r, r.table(), r.to_table(), buf)?;
+    match r {
+        ExprRef::Cell(c) => a1_cell(c, buf),
+        ExprRef::Range(c) => a1_range(c, buf),
+        ExprRef::Cols(c) => a1_cols(c, buf),
+        ExprRef::Rows(c) => a1_rows(c, buf),
+    }
+    Ok(())
+}
+
+/// Absolute row or column as `R1`, relative as offset `R[-1]` to the
+/// anchor. No offset is just `R`.
+fn r1c1_part(tag: char, abs: bool, v: u32, anchor: u32, buf: &mut String) {
+    buf.push(tag);
+    if abs {
+        buf.push_str(&(v as u64 + 1).to_string());
+    } else if v != anchor {
+        buf.push('[');
+        buf.push_str(&(v as i64 - anchor as i64).to_string());
+        buf.push(']');
+    }
+}
+
+fn r1c1_ref(r: &ExprRef, row: u32, col: u32, buf: &mut String) -> Result<(), DialectError> {
+    ooxml_tables(r, r.table(), r.to_table(), buf)?;
+    match r {
+        ExprRef::Cell(c) => {
+            r1c1_part('R', c.row_abs(), c.row(), row, buf);
+            r1c1_part('C', c.col_abs(), c.col(), col, buf);
+        }
+        ExprRef::Range(c) => {
+            r1c1_part('R', c.row_abs(), c.row(), row, buf);
+            r1c1_part('C', c.col_abs(), c.col(), col, buf);
+            buf.push(':');
+            r1c1_part('R', c.to_row_abs(), c.to_row(), row, buf);
+            r1c1_part('C', c.to_col_abs(), c.to_col(), col, buf);
+        }
+        ExprRef::Cols(c) => {
+            r1c1_part('C', c.col_abs(), c.col(), col, buf);
+            if (c.col(), c.col_abs()) != (c.to_col(), c.to_col_abs()) {
+                buf.push(':');
+                r1c1_part('C', c.to_col_abs(), c.to_col(), col, buf);
+            }
+        }
+        ExprRef::Rows(c) => {
+            r1c1_part('R', c.row_abs(), c.row(), row, buf);
+            if (c.row(), c.row_abs()) != (c.to_row(), c.to_row_abs()) {
+                buf.push(':');
+                r1c1_part('R', c.to_row_abs(), c.to_row(), row, buf);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn source(r: &ExprRef) -> String {
+    let mut buf = String::new();
+    r.formula(&mut buf);
+    buf
+}
@@ -0,0 +1,1099 @@
+//!
+//! Formula expression tree.
+//!
+//! The builder functions of this crate write OpenFormula directly. [Expr] is
+//! the parsed form of such a formula. It is used to translate formulas into
+//! other dialects and to rewrite them.
+//!
+//! ```
+//! use spreadsheet_ods::cell;
+//! use spreadsheet_ods_formula::{formula, of};
+//! use spreadsheet_ods_formula::expr::Expr;
+//!
+//! let e = Expr::from_formula(&of::sum(cell!(0, 0, 9, 0))).expect("expr");
+//! assert_eq!(formula(e), "of:=SUM([.A1:.A10])");
+//! ```
+//!
+
+use crate::dialect::Dialect;
+use crate::sheet::{FormulaType, expr_type};
+use crate::{
+    Any, Array, Criteria, Criterion, Database, DateTime, Field, FormulaError, Logical, Matrix,
+    Number, NumberOrArray, Reference, ReferenceOrArray, Scalar, Sequence, Text, TextOrNumber,
+    TextOrReference, fmt_float,
+};
+use spreadsheet_ods::refs::{parse_cellrange, parse_cellref, parse_colrange, parse_rowrange};
+use spreadsheet_ods::{CellRange, CellRef, ColRange, RowRange};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Reference in a formula.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprRef {
+    /// Single cell.
+    Cell(CellRef),
+    /// Cell range.
+    Range(CellRange),
+    /// Whole columns.
+    Cols(ColRange),
+    /// Whole rows.
+    Rows(RowRange),
+}
+
+impl ExprRef {
+    /// Table of the reference, if any.
+    pub fn table(&self) -> Option<&String> {
+        match self {
+            ExprRef::Cell(v) => v.table(),
+            ExprRef::Range(v) => v.table(),
+            ExprRef::Cols(v) => v.table(),
+            ExprRef::Rows(v) => v.table(),
+        }
+    }
+
+    /// Table at the end of the reference, if it differs from the start.
+    pub fn to_table(&self) -> Option<&String> {
+        match self {
+            ExprRef::Cell(_) => None,
+            ExprRef::Range(v) => v.to_table(),
+            ExprRef::Cols(v) => v.to_table(),
+            ExprRef::Rows(v) => v.to_table(),
+        }
+    }
+
+    /// External document, if any.
+    pub fn iri(&self) -> Option<&String> {
+        match self {
+            ExprRef::Cell(v) => v.iri(),
+            ExprRef::Range(v) => v.iri(),
+            ExprRef::Cols(v) => v.iri(),
+            ExprRef::Rows(v) => v.iri(),
+        }
+    }
+}
+
+impl Any for ExprRef {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        match self {
+            ExprRef::Cell(v) => write!(buf, "[{}]", v),
+            ExprRef::Range(v) => write!(buf, "[{}]", v),
+            ExprRef::Cols(v) => write!(buf, "[{}]", v),
+            ExprRef::Rows(v) => write!(buf, "[{}]", v),
+        }
+    }
+}
+
+/// Prefix and postfix operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    /// Prefix +
+    Plus,
+    /// Prefix -
+    Minus,
+    /// Postfix %
+    Percent,
+}
+
+impl UnaryOp {
+    /// Operator symbol.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::Plus => "+",
+            UnaryOp::Minus => "-",
+            UnaryOp::Percent => "%",
+        }
+    }
+
+    /// Binding strength, higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            UnaryOp::Plus | UnaryOp::Minus => 7,
+            UnaryOp::Percent => 6,
+        }
+    }
+}
+
+/// Infix operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InfixOp {
+    /// =
+    Eq,
+    /// <>
+    Ne,
+    /// <
+    Lt,
+    /// >
+    Gt,
+    /// <=
+    Le,
+    /// >=
+    Ge,
+    /// &
+    Concat,
+    /// +
+    Add,
+    /// -
+    Sub,
+    /// *
+    Mul,
+    /// /
+    Div,
+    /// ^
+    Pow,
+    /// ~ reference concatenation
+    Union,
+    /// ! reference intersection
+    Intersect,
+    /// : range
+    Range,
+}
+
+impl InfixOp {
+    /// Operator symbol in OpenFormula.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            InfixOp::Eq => "=",
+            InfixOp::Ne => "<>",
+            InfixOp::Lt => "<",
+            InfixOp::Gt => ">",
+            InfixOp::Le => "<=",
+            InfixOp::Ge => ">=",
+            InfixOp::Concat => "&",
+            InfixOp::Add => "+",
+            InfixOp::Sub => "-",
+            InfixOp::Mul => "*",
+            InfixOp::Div => "/",
+            InfixOp::Pow => "^",
+            InfixOp::Union => "~",
+            InfixOp::Intersect => "!",
+            InfixOp::Range => ":",
+        }
+    }
+
+    /// Binding strength, higher binds tighter. All infix operators
+    /// are left associative.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            InfixOp::Eq | InfixOp::Ne | InfixOp::Lt | InfixOp::Gt | InfixOp::Le | InfixOp::Ge => 1,
+            InfixOp::Concat => 2,
+            InfixOp::Add | InfixOp::Sub => 3,
+            InfixOp::Mul | InfixOp::Div => 4,
+            InfixOp::Pow => 5,
+            InfixOp::Union => 8,
+            InfixOp::Intersect => 9,
+            InfixOp::Range => 10,
+        }
+    }
+
+    /// Comparison operator.
+    pub fn is_comparison(&self) -> bool {
+        self.precedence() == 1
+    }
+}
+
+/// Parsed formula.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Number literal.
+    Number(f64),
+    /// Text literal.
+    Text(String),
+    /// Error literal.
+    Error(FormulaError),
+    /// Reference.
+    Ref(ExprRef),
+    /// Inline array, a list of rows.
+    Array(Vec<Vec<Expr>>),
+    /// Function call. TRUE() and FALSE() are functions too.
+    Func(String, Vec<Expr>),
+    /// Named expression or LET name.
+    Name(String),
+    /// Omitted parameter.
+    Empty,
+    /// Prefix or postfix operator.
+    Unary(UnaryOp, Box<Expr>),
+    /// Infix operator.
+    Infix(Box<Expr>, InfixOp, Box<Expr>),
+    /// Parentheses.
+    Paren(Box<Expr>),
+}
+
+impl Expr {
+    /// Parses an OpenFormula formula. The prefix "of:=" is optional.
+    pub fn parse(formula: &str) -> Result<Expr, ParseError> {
+        let body = formula.strip_prefix("of:").unwrap_or(formula);
+        let body = body.strip_prefix('=').unwrap_or(body);
+        let offset = formula.len() - body.len();
+        Parser::new(body, offset, Dialect::OpenFormula).parse()
+    }
+
+    /// Parses a formula in the syntax of the dialect. Function names are
+    /// not translated.
+    pub(crate) fn parse_dialect(formula: &str, dialect: Dialect) -> Result<Expr, ParseError> {
+        match dialect {
+            Dialect::OpenFormula => Expr::parse(formula),
+            Dialect::Ooxml | Dialect::R1C1 { .. } => {
+                let body = formula.strip_prefix('=').unwrap_or(formula);
+                let offset = formula.len() - body.len();
+                Parser::new(body, offset, dialect).parse()
+            }
+        }
+    }
+
+    /// Parses the output of any formula expression of this crate.
+    pub fn from_formula<T: Any + ?Sized>(f: &T) -> Result<Expr, ParseError> {
+        let mut buf = String::new();
+        f.formula(&mut buf);
+        Parser::new(&buf, 0, Dialect::OpenFormula).parse()
+    }
+
+    /// Function call.
+    pub fn func<S: Into<String>>(name: S, args: Vec<Expr>) -> Expr {
+        Expr::Func(name.into(), args)
+    }
+
+    /// Infix operation.
+    pub fn infix(left: Expr, op: InfixOp, right: Expr) -> Expr {
+        Expr::Infix(Box::new(left), op, Box::new(right))
+    }
+
+    /// Binding strength of the expression, higher binds tighter.
+    /// Operands with a lower precedence than their operator need parentheses.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Expr::Unary(op, _) => op.precedence(),
+            Expr::Infix(_, op, _) => op.precedence(),
+            _ => u8::MAX,
+        }
+    }
+
+    /// Boolean constant TRUE() or FALSE().
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Expr::Func(name, args) if args.is_empty() => {
+                if name.eq_ignore_ascii_case("TRUE") {
+                    Some(true)
+                } else if name.eq_ignore_ascii_case("FALSE") {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Writes the operand and adds parentheses where the operator
+/// would bind it differently.
+fn operand(e: &Expr, min: u8, buf: &mut dyn Write) -> fmt::Result {
+    if e.precedence() < min {
+        buf.write_char('(')?;
+        e.write_formula(buf)?;
+        buf.write_char(')')
+    } else {
+        e.write_formula(buf)
+    }
+}
+
+impl Any for Expr {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        match self {
+            Expr::Number(v) => fmt_float(*v, *v, buf)?,
+            Expr::Text(v) => v.as_str().write_formula(buf)?,
+            Expr::Error(v) => v.write_formula(buf)?,
+            Expr::Ref(v) => v.write_formula(buf)?,
+            Expr::Array(rows) => {
+                buf.write_char('{')?;
+                for (i, r) in rows.iter().enumerate() {
+                    if i > 0 {
+                        buf.write_char('|')?;
+                    }
+                    for (j, v) in r.iter().enumerate() {
+                        if j > 0 {
+                            buf.write_char(';')?;
+                        }
+                        v.write_formula(buf)?;
+                    }
+                }
+                buf.write_char('}')?;
+            }
+            Expr::Func(name, args) => {
+                buf.write_str(name)?;
+                buf.write_char('(')?;
+                for (i, v) in args.iter().enumerate() {
+                    if i > 0 {
+                        buf.write_char(';')?;
+                    }
+                    v.write_formula(buf)?;
+                }
+                buf.write_char(')')?;
+            }
+            Expr::Name(v) => buf.write_str(v)?,
+            Expr::Empty => {}
+            Expr::Unary(op, v) => {
+                if *op == UnaryOp::Percent {
+                    operand(v, op.precedence(), buf)?;
+                    buf.write_str(op.symbol())?;
+                } else {
+                    buf.write_str(op.symbol())?;
+                    operand(v, op.precedence(), buf)?;
+                }
+            }
+            Expr::Infix(a, op, b) => {
+                operand(a, op.precedence(), buf)?;
+                buf.write_str(op.symbol())?;
+                operand(b, op.precedence() + 1, buf)?;
+            }
+            Expr::Paren(v) => {
+                buf.write_char('(')?;
+                v.write_formula(buf)?;
+                buf.write_char(')')?;
+            }
+        }
+        Ok(())
+    }
+
+    fn formula_type(&self) -> FormulaType {
+        expr_type(self)
+    }
+}
+impl Number for Expr {}
+impl Text for Expr {}
+impl Logical for Expr {}
+impl Reference for Expr {}
+impl Matrix for Expr {}
+impl Array for Expr {}
+impl Database for Expr {}
+impl Criterion for Expr {}
+impl Criteria for Expr {}
+impl Sequence for Expr {}
+impl Scalar for Expr {}
+impl Field for Expr {}
+impl DateTime for Expr {}
+impl TextOrNumber for Expr {}
+impl ReferenceOrArray for Expr {}
+impl TextOrReference for Expr {}
+impl NumberOrArray for Expr {}
+
+/// Errors when parsing a formula. Positions are byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Unexpected character.
+    Unexpected(usize, char),
+    /// The formula ends too early.
+    UnexpectedEnd,
+    /// Invalid reference.
+    Reference(usize, String),
+    /// Unknown error literal.
+    ErrorLiteral(usize, String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Unexpected(p, c) => write!(f, "unexpected '{}' at {}", c, p),
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of formula"),
+            ParseError::Reference(p, r) => write!(f, "invalid reference '{}' at {}", r, p),
+            ParseError::ErrorLiteral(p, e) => write!(f, "unknown error '{}' at {}", e, p),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+const ERRORS: [FormulaError; 7] = [
+    FormulaError::Null,
+    FormulaError::Div0,
+    FormulaError::Value,
+    FormulaError::Ref,
+    FormulaError::Name,
+    FormulaError::Num,
+    FormulaError::NA,
+];
+
+/// Largest column and row in Excel.
+const A1_MAX_COL: u32 = 16384;
+const A1_MAX_ROW: u32 = 1048576;
+
+/// Part of an A1 reference.
+enum A1Part {
+    Cell(u32, bool, u32, bool),
+    Col(u32, bool),
+    Row(u32, bool),
+}
+
+/// Orders the coordinates of two corners, the absolute flag stays
+/// with its coordinate.
+fn ordered(a: (u32, bool), b: (u32, bool)) -> ((u32, bool), (u32, bool)) {
+    if a.0 > b.0 { (b, a) } else { (a, b) }
+}
+
+/// Recursive descent parser for OpenFormula and the Excel A1 and R1C1 syntax.
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    offset: usize,
+    dialect: Dialect,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str, offset: usize, dialect: Dialect) -> Self {
+        Self {
+            src,
+            pos: 0,
+            offset,
+            dialect,
+        }
+    }
+
+    fn excel(&self) -> bool {
+        matches!(self.dialect, Dialect::Ooxml | Dialect::R1C1 { .. })
+    }
+
+    /// Separator for function arguments and array columns.
+    fn sep(&self) -> &'static str {
+        if self.excel() { "," } else { ";" }
+    }
+
+    /// Separator for array rows.
+    fn row_sep(&self) -> &'static str {
+        if self.excel() { ";" } else { "|" }
+    }
+
+    fn parse(mut self) -> Result<Expr, ParseError> {
+        self.skip_ws();
+        let e = if self.peek().is_none() {
+            Expr::Empty
+        } else {
+            self.expr()?
+        };
+        self.skip_ws();
+        match self.peek() {
+            None => Ok(e),
+            Some(c) => Err(self.unexpected(c)),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes the token if it comes next.
+    fn eat(&mut self, tok: &str) -> bool {
+        self.skip_ws();
+        if self.rest().starts_with(tok) {
+            self.pos += tok.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, tok: char) -> Result<(), ParseError> {
+        self.skip_ws();
+        match self.peek() {
+            Some(c) if c == tok => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.unexpected(c)),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    fn unexpected(&self, c: char) -> ParseError {
+        ParseError::Unexpected(self.offset + self.pos, c)
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        self.infix(1)
+    }
+
+    fn infix_op(&mut self, level: u8) -> Option<InfixOp> {
+        const OPS: [(&str, InfixOp); 15] = [
+            ("<>", InfixOp::Ne),
+            ("<=", InfixOp::Le),
+            (">=", InfixOp::Ge),
+            ("=", InfixOp::Eq),
+            ("<", InfixOp::Lt),
+            (">", InfixOp::Gt),
+            ("&", InfixOp::Concat),
+            ("+", InfixOp::Add),
+            ("-", InfixOp::Sub),
+            ("*", InfixOp::Mul),
+            ("/", InfixOp::Div),
+            ("^", InfixOp::Pow),
+            ("~", InfixOp::Union),
+            ("!", InfixOp::Intersect),
+            (":", InfixOp::Range),
+        ];
+        if self.excel() && level == 9 {
+            // Excel writes the intersection as a space between two references.
+            self.skip_ws();
+            let ws = self.src[..self.pos].ends_with(char::is_whitespace);
+            let next = self
+                .peek()
+                .is_some_and(|c| c.is_alphabetic() || "$'(_".contains(c));
+            return (ws && next).then_some(InfixOp::Intersect);
+        }
+        self.skip_ws();
+        for (tok, op) in OPS {
+            if self.excel() && op == InfixOp::Union {
+                continue;
+            }
+            if op.precedence() == level && self.eat(tok) {
+                return Some(op);
+            }
+        }
+        None
+    }
+
+    /// Left associative infix operators of the given precedence.
+    fn infix(&mut self, level: u8) -> Result<Expr, ParseError> {
+        let mut left = self.operand(level)?;
+        while let Some(op) = self.infix_op(level) {
+            let right = self.operand(level)?;
+            left = Expr::infix(left, op, right);
+        }
+        Ok(left)
+    }
+
+    fn operand(&mut self, level: u8) -> Result<Expr, ParseError> {
+        match level {
+            1..=4 => self.infix(level + 1),
+            5 => self.postfix(),
+            8..=9 => self.infix(level + 1),
+            _ => self.primary(),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ParseError> {
+        let mut e = self.prefix()?;
+        while self.eat("%") {
+            e = Expr::Unary(UnaryOp::Percent, Box::new(e));
+        }
+        Ok(e)
+    }
+
+    fn prefix(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("-") {
+            Ok(Expr::Unary(UnaryOp::Minus, Box::new(self.prefix()?)))
+        } else if self.eat("+") {
+            Ok(Expr::Unary(UnaryOp::Plus, Box::new(self.prefix()?)))
+        } else {
+            self.infix(8)
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        self.skip_ws();
+        let Some(c) = self.peek() else {
+            return Err(ParseError::UnexpectedEnd);
+        };
+        match c {
+            '(' => {
+                self.pos += 1;
+                let mut e = self.expr()?;
+                // The union of references is a comma in Excel.
+                while self.excel() && self.eat(",") {
+                    e = Expr::infix(e, InfixOp::Union, self.expr()?);
+                }
+                self.expect(')')?;
+                Ok(Expr::Paren(Box::new(e)))
+            }
+            '"' => self.text(),
+            '[' if !self.excel() => self.reference(),
+            '\'' | '$' if self.excel() => self.a1_reference(),
+            c if self.excel() && (c.is_alphanumeric() || c == '_') => {
+                let start = self.pos;
+                match self.try_reference()? {
+                    Some(e) => Ok(e),
+                    None => {
+                        self.pos = start;
+                        if c.is_ascii_digit() {
+                            self.number()
+                        } else {
+                            self.identifier()
+                        }
+                    }
+                }
+            }
+            '{' => self.array(),
+            '#' => self.error(),
+            '0'..='9' | '.' => self.number(),
+            c if c.is_alphabetic() || c == '_' || c == '\\' => self.identifier(),
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    fn text(&mut self) -> Result<Expr, ParseError> {
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            let Some(end) = rest.find('"') else {
+                return Err(ParseError::UnexpectedEnd);
+            };
+            text.push_str(&rest[..end]);
+            self.pos += end + 1;
+            if self.rest().starts_with('"') {
+                text.push('"');
+                self.pos += 1;
+            } else {
+                return Ok(Expr::Text(text));
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+        let bytes = self.src.as_bytes();
+        let digits = |p: &mut usize| {
+            let s = *p;
+            while *p < bytes.len() && bytes[*p].is_ascii_digit() {
+                *p += 1;
+            }
+            *p > s
+        };
+        let mut p = self.pos;
+        let int = digits(&mut p);
+        let mut frac = false;
+        if p < bytes.len() && bytes[p] == b'.' {
+            p += 1;
+            frac = digits(&mut p);
+        }
+        if !int && !frac {
+            return Err(ParseError::Unexpected(self.offset + start, '.'));
+        }
+        if p < bytes.len() && (bytes[p] == b'e' || bytes[p] == b'E') {
+            let mut q = p + 1;
+            if q < bytes.len() && (bytes[q] == b'+' || bytes[q] == b'-') {
+                q += 1;
+            }
+            if digits(&mut q) {
+                p = q;
+            }
+        }
+        self.pos = p;
+        match self.src[start..p].parse::<f64>() {
+            Ok(v) => Ok(Expr::Number(v)),
+            Err(_) => Err(ParseError::Unexpected(self.offset + start, '.')),
+        }
+    }
+
+    fn error(&mut self) -> Result<Expr, ParseError> {
+        for e in ERRORS {
+            if self.rest().starts_with(e.as_str()) {
+                self.pos += e.as_str().len();
+                return Ok(Expr::Error(e));
+            }
+        }
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || "#/!?_".contains(c)))
+            .unwrap_or(rest.len());
+        Err(ParseError::ErrorLiteral(
+            self.offset + self.pos,
+            rest[..end].to_string(),
+        ))
+    }
+
+    fn reference(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut quoted = false;
+        let mut end = None;
+        for (i, c) in self.rest().char_indices() {
+            match c {
+                '\'' => quoted = !quoted,
+                ']' if !quoted => {
+                    end = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let Some(end) = end else {
+            return Err(ParseError::UnexpectedEnd);
+        };
+        let inner = &self.rest()[..end];
+        self.pos += end + 1;
+        let r = if let Ok(v) = parse_cellref(inner) {
+            ExprRef::Cell(v)
+        } else if let Ok(v) = parse_cellrange(inner) {
+            ExprRef::Range(v)
+        } else if let Ok(v) = parse_colrange(inner) {
+            ExprRef::Cols(v)
+        } else if let Ok(v) = parse_rowrange(inner) {
+            ExprRef::Rows(v)
+        } else {
+            return Err(ParseError::Reference(
+                self.offset + start,
+                inner.to_string(),
+            ));
+        };
+        Ok(Expr::Ref(r))
+    }
+
+    fn array(&mut self) -> Result<Expr, ParseError> {
+        self.pos += 1;
+        let mut rows = Vec::new();
+        if self.eat("}") {
+            return Ok(Expr::Array(rows));
+        }
+        let mut row = Vec::new();
+        loop {
+            row.push(self.expr()?);
+            if self.eat(self.sep()) {
+                continue;
+            } else if self.eat(self.row_sep()) {
+                rows.push(std::mem::take(&mut row));
+            } else {
+                self.expect('}')?;
+                rows.push(row);
+                return Ok(Expr::Array(rows));
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<Expr, ParseError> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '\\'))
+            .unwrap_or(rest.len());
+        let name = rest[..end].to_string();
+        self.pos += end;
+        if !self.eat("(") {
+            // Excel writes the logical constants without parentheses.
+            if self.excel()
+                && (name.eq_ignore_ascii_case("TRUE") || name.eq_ignore_ascii_case("FALSE"))
+            {
+                return Ok(Expr::Func(name.to_ascii_uppercase(), Vec::new()));
+            }
+            return Ok(Expr::Name(name));
+        }
+        let mut args = Vec::new();
+        if self.eat(")") {
+            return Ok(Expr::Func(name, args));
+        }
+        loop {
+            self.skip_ws();
+            if self.rest().starts_with(self.sep()) || self.rest().starts_with(')') {
+                args.push(Expr::Empty);
+            } else {
+                args.push(self.expr()?);
+            }
+            if !self.eat(self.sep()) {
+                self.expect(')')?;
+                return Ok(Expr::Func(name, args));
+            }
+        }
+    }
+
+    /// Excel reference with an optional sheet prefix.
+    fn a1_reference(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+        match self.try_reference()? {
+            Some(e) => Ok(e),
+            None => Err(self.a1_error(start)),
+        }
+    }
+
+    /// Excel reference or None if the text is no reference.
+    fn try_reference(&mut self) -> Result<Option<Expr>, ParseError> {
+        let sheets = self.a1_sheets()?;
+        let Some(first) = self.ref_part() else {
+            return Ok(None);
+        };
+        let before_colon = self.pos;
+        let second = if self.rest().starts_with(':') {
+            self.pos += 1;
+            let second = self.ref_part();
+            if second.is_none() {
+                self.pos = before_colon;
+            }
+            second
+        } else {
+            None
+        };
+        // Function names like LOG10 look like a cell.
+        if sheets.is_none() && self.rest().trim_start().starts_with('(') {
+            return Ok(None);
+        }
+        // Excel accepts the corners in any order, B2:A1 is A1:B2.
+        let (first, second) = match (first, second) {
+            (A1Part::Cell(c, ca, r, ra), Some(A1Part::Cell(tc, tca, tr, tra))) => {
+                let ((c, ca), (tc, tca)) = ordered((c, ca), (tc, tca));
+                let ((r, ra), (tr, tra)) = ordered((r, ra), (tr, tra));
+                (
+                    A1Part::Cell(c, ca, r, ra),
+                    Some(A1Part::Cell(tc, tca, tr, tra)),
+                )
+            }
+            (A1Part::Col(c, ca), Some(A1Part::Col(tc, tca))) => {
+                let ((c, ca), (tc, tca)) = ordered((c, ca), (tc, tca));
+                (A1Part::Col(c, ca), Some(A1Part::Col(tc, tca)))
+            }
+            (A1Part::Row(r, ra), Some(A1Part::Row(tr, tra))) => {
+                let ((r, ra), (tr, tra)) = ordered((r, ra), (tr, tra));
+                (A1Part::Row(r, ra), Some(A1Part::Row(tr, tra)))
+            }
+            v => v,
+        };
+        let mut r = match (first, second) {
+            (A1Part::Cell(c, ca, r, ra), None) => {
+                let mut v = CellRef::local(r, c);
+                v.set_col_abs(ca);
+                v.set_row_abs(ra);
+                ExprRef::Cell(v)
+            }
+            (A1Part::Cell(c, ca, r, ra), Some(A1Part::Cell(tc, tca, tr, tra))) => {
+                let mut v = CellRange::local(r, c, tr, tc);
+                v.set_col_abs(ca);
+                v.set_row_abs(ra);
+                v.set_to_col_abs(tca);
+                v.set_to_row_abs(tra);
+                ExprRef::Range(v)
+            }
+            (A1Part::Col(c, ca), Some(A1Part::Col(tc, tca))) => {
+                let mut v = ColRange::new(c, tc);
+                v.set_col_abs(ca);
+                v.set_to_col_abs(tca);
+                ExprRef::Cols(v)
+            }
+            (A1Part::Row(r, ra), Some(A1Part::Row(tr, tra))) => {
+                let mut v = RowRange::new(r, tr);
+                v.set_row_abs(ra);
+                v.set_to_row_abs(tra);
+                ExprRef::Rows(v)
+            }
+            // R1C1 allows a single column "C2" or row "R[1]".
+            (A1Part::Col(c, ca), None) if self.r1c1_anchor().is_some() => {
+                let mut v = ColRange::new(c, c);
+                v.set_col_abs(ca);
+                v.set_to_col_abs(ca);
+                ExprRef::Cols(v)
+            }
+            (A1Part::Row(r, ra), None) if self.r1c1_anchor().is_some() => {
+                let mut v = RowRange::new(r, r);
+                v.set_row_abs(ra);
+                v.set_to_row_abs(ra);
+                ExprRef::Rows(v)
+            }
+            _ => return Ok(None),
+        };
+        if let Some((table, to_table)) = sheets {
+            if let (ExprRef::Cell(c), Some(_)) = (&r, &to_table) {
+                let mut v = CellRange::local(c.row(), c.col(), c.row(), c.col());
+                v.set_col_abs(c.col_abs());
+                v.set_row_abs(c.row_abs());
+                v.set_to_col_abs(c.col_abs());
+                v.set_to_row_abs(c.row_abs());
+                r = ExprRef::Range(v);
+            }
+            match &mut r {
+                ExprRef::Cell(v) => v.set_table(table),
+                ExprRef::Range(v) => {
+                    v.set_table(table);
+                    if let Some(t) = to_table {
+                        v.set_to_table(t);
+                    }
+                }
+                ExprRef::Cols(v) => {
+                    v.set_table(table);
+                    if let Some(t) = to_table {
+                        v.set_to_table(t);
+                    }
+                }
+                ExprRef::Rows(v) => {
+                    v.set_table(table);
+                    if let Some(t) = to_table {
+                        v.set_to_table(t);
+                    }
+                }
+            }
+        }
+        Ok(Some(Expr::Ref(r)))
+    }
+
+    fn a1_error(&self, start: usize) -> ParseError {
+        let rest = &self.src[start..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || ",;()".contains(c))
+            .unwrap_or(rest.len());
+        ParseError::Reference(self.offset + start, rest[..end].to_string())
+    }
+
+    /// Sheet prefix "Sheet1!", "'My sheet'!" or "Sheet1:Sheet3!".
+    fn a1_sheets(&mut self) -> Result<Option<(String, Option<String>)>, ParseError> {
+        let start = self.pos;
+        let names = if self.rest().starts_with('\'') {
+            self.pos += 1;
+            let mut name = String::new();
+            loop {
+                let rest = self.rest();
+                let Some(end) = rest.find('\'') else {
+                    return Err(ParseError::UnexpectedEnd);
+                };
+                name.push_str(&rest[..end]);
+                self.pos += end + 1;
+                if self.rest().starts_with('\'') {
+                    name.push('\'');
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+            if !self.rest().starts_with('!') {
+                return Err(self.a1_error(start));
+            }
+            name
+        } else {
+            let rest = self.rest();
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == ':'))
+                .unwrap_or(rest.len());
+            if !rest[end..].starts_with('!') {
+                return Ok(None);
+            }
+            self.pos += end;
+            rest[..end].to_string()
+        };
+        self.pos += 1;
+        Ok(Some(match names.split_once(':') {
+            Some((a, b)) => (a.to_string(), Some(b.to_string())),
+            None => (names, None),
+        }))
+    }
+
+    /// Anchor cell for R1C1 references.
+    fn r1c1_anchor(&self) -> Option<(u32, u32)> {
+        match self.dialect {
+            Dialect::R1C1 { row, col } => Some((row, col)),
+            _ => None,
+        }
+    }
+
+    fn ref_part(&mut self) -> Option<A1Part> {
+        match self.r1c1_anchor() {
+            Some((row, col)) => self.r1c1_part(row, col),
+            None => self.a1_part(),
+        }
+    }
+
+    /// A cell "R1C[-1]", a column "C1" or a row "R[2]". Relative parts
+    /// are resolved against the anchor.
+    fn r1c1_part(&mut self, row: u32, col: u32) -> Option<A1Part> {
+        let mut p = self.pos;
+        let r = self.r1c1_axis(&mut p, b'R', row, A1_MAX_ROW)?;
+        let c = self.r1c1_axis(&mut p, b'C', col, A1_MAX_COL)?;
+        if self.src[p..].starts_with(|c: char| c.is_alphanumeric() || "_.$(!".contains(c)) {
+            return None;
+        }
+        let part = match (r, c) {
+            (Some((r, ra)), Some((c, ca))) => A1Part::Cell(c, ca, r, ra),
+            (None, Some((c, ca))) => A1Part::Col(c, ca),
+            (Some((r, ra)), None) => A1Part::Row(r, ra),
+            (None, None) => return None,
+        };
+        self.pos = p;
+        Some(part)
+    }
+
+    /// One axis of an R1C1 reference. Returns Some(None) if the tag is
+    /// missing and None if the axis is invalid.
+    fn r1c1_axis(
+        &self,
+        p: &mut usize,
+        tag: u8,
+        anchor: u32,
+        max: u32,
+    ) -> Option<Option<(u32, bool)>> {
+        let b = self.src.as_bytes();
+        if b.get(*p).map(|c| c.to_ascii_uppercase()) != Some(tag) {
+            return Some(None);
+        }
+        let mut q = *p + 1;
+        let v = if b.get(q) == Some(&b'[') {
+            let end = self.src[q..].find(']')? + q;
+            let offset = self.src[q + 1..end].parse::<i64>().ok()?;
+            q = end + 1;
+            let v = anchor as i64 + offset;
+            ((0..max as i64).contains(&v)).then_some((v as u32, false))?
+        } else {
+            let s = q;
+            while q < b.len() && b[q].is_ascii_digit() {
+                q += 1;
+            }
+            if q == s {
+                (anchor, false)
+            } else {
+                match self.src[s..q].parse::<u32>() {
+                    Ok(v) if (1..=max).contains(&v) => (v - 1, true),
+                    _ => return None,
+                }
+            }
+        };
+        *p = q;
+        Some(Some(v))
+    }
+
+    /// A cell "$A$1", a column "$A" or a row "$1".
+    fn a1_part(&mut self) -> Option<A1Part> {
+        let b = self.src.as_bytes();
+        let mut p = self.pos;
+        let col_abs = b.get(p) == Some(&b'$');
+        if col_abs {
+            p += 1;
+        }
+        let cs = p;
+        while p < b.len() && b[p].is_ascii_alphabetic() {
+            p += 1;
+        }
+        let letters = &self.src[cs..p];
+        let row_abs = !letters.is_empty() && b.get(p) == Some(&b'$');
+        if row_abs {
+            p += 1;
+        }
+        let rs = p;
+        while p < b.len() && b[p].is_ascii_digit() {
+            p += 1;
+        }
+        let digits = &self.src[rs..p];
+        if self.src[p..].starts_with(|c: char| c.is_alphanumeric() || "_.$(!".contains(c)) {
+            return None;
+        }
+        let col = if letters.is_empty() || letters.len() > 3 {
+            None
+        } else {
+            let c = letters.bytes().fold(0u32, |c, l| {
+                c * 26 + (l.to_ascii_uppercase() - b'A') as u32 + 1
+            });
+            (c <= A1_MAX_COL).then(|| c - 1)
+        };
+        let row = match digits.parse::<u32>() {
+            Ok(r) if (1..=A1_MAX_ROW).contains(&r) => Some(r - 1),
+            _ => None,
+        };
+        let part = match (letters.is_empty(), digits.is_empty(), col, row) {
+            (false, false, Some(c), Some(r)) => A1Part::Cell(c, col_abs, r, row_abs),
+            (false, true, Some(c), None) if !row_abs => A1Part::Col(c, col_abs),
+            (true, false, None, Some(r)) => A1Part::Row(r, col_abs),
+            _ => return None,
+        };
+        self.pos = p;
+        Some(part)
+    }
+}
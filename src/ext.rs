@@ -2,24 +2,28 @@
 //! External access
 //!
 
-use crate::{Any, FnNumber4, Text};
+use crate::{Any, FnAny2, FnNumber4, FnText1, Text};
+use std::fmt::{self, Write};
 
 pub use crate::generated::ext::*;
 
 /// Parameter for DDE()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DDEConversion {
     NumberLocalized,
     NumberEnUS,
+    Text,
 }
 
 impl Any for DDEConversion {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             DDEConversion::NumberLocalized => "0",
             DDEConversion::NumberEnUS => "1",
-        });
+            DDEConversion::Text => "2",
+        })
     }
 }
 
@@ -43,3 +47,40 @@ pub fn dde_text<A: Text, B: Text, C: Text>(
 ) -> FnNumber4<A, B, C, &'static str> {
     FnNumber4("DDE", server, topic, item, "2")
 }
+
+/// Applies an XPath expression to an XML document.
+///
+/// [documentfoundation->FILTERXML](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FILTERXML)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.FILTERXML( Xml: Text; XPath: Text )
+/// ```
+///
+/// __Semantics__:
+/// Returns the result of the XPath query. Multiple results are returned as
+/// an array.
+///
+/// __See also__: [crate::of::webservice()]
+#[inline]
+pub fn filterxml<A: Text, B: Text>(xml: A, xpath: B) -> FnAny2<A, B> {
+    FnAny2("COM.MICROSOFT.FILTERXML", xml, xpath)
+}
+
+/// Gets web content from a URI.
+///
+/// [documentfoundation->WEBSERVICE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/WEBSERVICE)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.WEBSERVICE( Uri: Text )
+/// ```
+///
+/// __Semantics__:
+/// Returns the content of the URI as text.
+///
+/// __See also__: [crate::of::filterxml()]
+#[inline]
+pub fn webservice<A: Text>(uri: A) -> FnText1<A> {
+    FnText1("COM.MICROSOFT.WEBSERVICE", uri)
+}
@@ -3,7 +3,7 @@
 //!
 
 use crate::Any;
-use std::fmt::Write;
+use std::fmt::{self, Write};
 
 pub use crate::generated::fin::*;
 
@@ -11,7 +11,7 @@ pub use crate::generated::fin::*;
 pub use crate::date::YearFracMethod;
 
 /// Parameter for many fin functions.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Frequency {
     Annual,
     Semiannual,
@@ -20,8 +20,9 @@ pub enum Frequency {
 }
 
 impl Any for Frequency {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -30,42 +31,40 @@ impl Any for Frequency {
                 Frequency::Quarterly => 4,
                 Frequency::Monthly => 12,
             }
-        );
+        )
     }
 }
 
 /// Parameter for CUMIPMT()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MaturityDate {
-    ///
     DueAtEnd,
-    ///
     DueAtBeginning,
 }
 
 impl Any for MaturityDate {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             MaturityDate::DueAtEnd => "0",
             MaturityDate::DueAtBeginning => "1",
-        });
+        })
     }
 }
 
 /// Parameter for CUMIPMT()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PayType {
-    ///
     DueAtEnd,
-    ///
     DueAtBeginning,
 }
 
 impl Any for PayType {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             PayType::DueAtEnd => "0",
             PayType::DueAtBeginning => "1",
-        });
+        })
     }
 }
@@ -1,6 +1,8 @@
 pub(crate) mod bit;
 pub(crate) mod complex;
 pub(crate) mod conv;
+// The docs are copied verbatim from the OpenFormula specification.
+#[allow(clippy::doc_lazy_continuation)]
 pub(crate) mod date;
 pub(crate) mod db;
 pub(crate) mod ext;
@@ -10,7 +12,9 @@ pub(crate) mod logic;
 pub(crate) mod lookup;
 pub(crate) mod math;
 pub(crate) mod matrix;
+pub(crate) mod returns;
 pub(crate) mod round;
 pub(crate) mod stat;
+#[allow(clippy::doc_lazy_continuation)]
 pub(crate) mod text;
 pub(crate) mod textb;
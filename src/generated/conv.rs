@@ -83,7 +83,7 @@ pub fn arabic<A: Text>(x: A) -> FnNumber1<A> {
 ///
 /// __See also__: [crate::of::decimal()], [crate::of::base_()], 
 #[inline]
-pub fn base<A: Number, B: Number>(x: A, radix: B) -> FnText2<A, B> {
+pub fn base<A: Number>(x: A, radix: Radix) -> FnText2<A, Radix> {
     FnText2("BASE", x, radix)
 }
 
@@ -113,7 +113,7 @@ pub fn base<A: Number, B: Number>(x: A, radix: B) -> FnText2<A, B> {
 ///
 /// __See also__: [crate::of::decimal()], [crate::of::base()], 
 #[inline]
-pub fn base_<A: Number, B: Number, C: Number>(x: A, radix: B, minimum_length: C) -> FnText3<A, B, C> {
+pub fn base_<A: Number, B: Number>(x: A, radix: Radix, minimum_length: B) -> FnText3<A, Radix, B> {
     FnText3("BASE", x, radix, minimum_length)
 }
 
@@ -573,7 +573,7 @@ pub fn dec2oct_<A: TextOrNumber, B: Number>(x: A, digits: B) -> FnText2<A, B> {
 ///
 /// __See also__: [crate::of::base()], 
 #[inline]
-pub fn decimal<A: Text, B: Number>(x: A, radix: B) -> FnNumber2<A, B> {
+pub fn decimal<A: Text>(x: A, radix: Radix) -> FnNumber2<A, Radix> {
     FnNumber2("DECIMAL", x, radix)
 }
 
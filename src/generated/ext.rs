@@ -84,7 +84,7 @@ pub fn dde<A: Text, B: Text, C: Text>(server: A, topic: B, item: C) -> FnText3<A
 ///
 /// __See also__: [crate::of::dde()], 
 #[inline]
-pub fn dde_<A: Text, B: Text, C: Text, D: Number>(server: A, topic: B, item: C, mode: D) -> FnText4<A, B, C, D> {
+pub fn dde_<A: Text, B: Text, C: Text>(server: A, topic: B, item: C, mode: DDEConversion) -> FnText4<A, B, C, DDEConversion> {
     FnText4("DDE", server, topic, item, mode)
 }
 
@@ -1301,7 +1301,7 @@ pub fn fv_<A: Number, B: Number, C: Number, D: Number>(rate: A, nper: B, payment
 ///
 /// __See also__: [crate::of::pv()], [crate::of::nper()], [crate::of::pmt()], [crate::of::rate()], [crate::of::fv()], [crate::of::fv_()], 
 #[inline]
-pub fn fv__<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, nper: B, payment: C, pv: D, pay_type: E) -> FnNumber5<A, B, C, D, E> {
+pub fn fv__<A: Number, B: Number, C: Number, D: Number>(rate: A, nper: B, payment: C, pv: D, pay_type: PayType) -> FnNumber5<A, B, C, D, PayType> {
     FnNumber5("FV", rate, nper, payment, pv, pay_type)
 }
 
@@ -1513,7 +1513,7 @@ pub fn ipmt_<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, per
 ///
 /// __See also__: [crate::of::ppmt()], [crate::of::pmt()], [crate::of::ipmt()], [crate::of::ipmt_()], 
 #[inline]
-pub fn ipmt__<A: Number, B: Number, C: Number, D: Number, E: Number, F: Number>(rate: A, period: B, nper: C, p_v: D, f_v: E, type_: F) -> FnNumber6<A, B, C, D, E, F> {
+pub fn ipmt__<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, period: B, nper: C, p_v: D, f_v: E, type_: PayType) -> FnNumber6<A, B, C, D, E, PayType> {
     FnNumber6("IPMT", rate, period, nper, p_v, f_v, type_)
 }
 
@@ -2374,7 +2374,7 @@ pub fn pmt_<A: Number, B: Number, C: Number, D: Number>(rate: A, nper: B, pv: C,
 ///
 /// __See also__: [crate::of::fv()], [crate::of::nper()], [crate::of::pv()], [crate::of::rate()], [crate::of::pmt()], [crate::of::pmt_()], 
 #[inline]
-pub fn pmt__<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, nper: B, pv: C, fv: D, pay_type: E) -> FnNumber5<A, B, C, D, E> {
+pub fn pmt__<A: Number, B: Number, C: Number, D: Number>(rate: A, nper: B, pv: C, fv: D, pay_type: PayType) -> FnNumber5<A, B, C, D, PayType> {
     FnNumber5("PMT", rate, nper, pv, fv, pay_type)
 }
 
@@ -2482,7 +2482,7 @@ pub fn ppmt_<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, per
 ///
 /// __See also__: [crate::of::pmt()], [crate::of::ppmt()], [crate::of::ppmt_()], 
 #[inline]
-pub fn ppmt__<A: Number, B: Number, C: Number, D: Number, E: Number, F: Number>(rate: A, period: B, nper: C, present: D, future: E, type_: F) -> FnNumber6<A, B, C, D, E, F> {
+pub fn ppmt__<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, period: B, nper: C, present: D, future: E, type_: PayType) -> FnNumber6<A, B, C, D, E, PayType> {
     FnNumber6("PPMT", rate, period, nper, present, future, type_)
 }
 
@@ -2818,7 +2818,7 @@ pub fn pv_<A: Number, B: Number, C: Number, D: Number>(rate: A, nper: B, payment
 ///
 /// __See also__: [crate::of::fv()], [crate::of::nper()], [crate::of::pmt()], [crate::of::rate()], [crate::of::pv()], [crate::of::pv_()], 
 #[inline]
-pub fn pv__<A: Number, B: Number, C: Number, D: Number, E: Number>(rate: A, nper: B, payment: C, fv: D, pay_type: E) -> FnNumber5<A, B, C, D, E> {
+pub fn pv__<A: Number, B: Number, C: Number, D: Number>(rate: A, nper: B, payment: C, fv: D, pay_type: PayType) -> FnNumber5<A, B, C, D, PayType> {
     FnNumber5("PV", rate, nper, payment, fv, pay_type)
 }
 
@@ -2929,7 +2929,7 @@ pub fn rate_<A: Number, B: Number, C: Number, D: Number>(nper: A, payment: B, pv
 ///
 /// __See also__: [crate::of::fv()], [crate::of::nper()], [crate::of::pmt()], [crate::of::pv()], [crate::of::rate()], [crate::of::rate_()], [crate::of::rate___()], 
 #[inline]
-pub fn rate__<A: Number, B: Number, C: Number, D: Number, E: Number>(nper: A, payment: B, pv: C, fv: D, pay_type: E) -> FnNumber5<A, B, C, D, E> {
+pub fn rate__<A: Number, B: Number, C: Number, D: Number>(nper: A, payment: B, pv: C, fv: D, pay_type: PayType) -> FnNumber5<A, B, C, D, PayType> {
     FnNumber5("RATE", nper, payment, pv, fv, pay_type)
 }
 
@@ -2966,7 +2966,7 @@ pub fn rate__<A: Number, B: Number, C: Number, D: Number, E: Number>(nper: A, pa
 ///
 /// __See also__: [crate::of::fv()], [crate::of::nper()], [crate::of::pmt()], [crate::of::pv()], [crate::of::rate()], [crate::of::rate_()], [crate::of::rate__()], 
 #[inline]
-pub fn rate___<A: Number, B: Number, C: Number, D: Number, E: Number, F: Number>(nper: A, payment: B, pv: C, fv: D, pay_type: E, guess: F) -> FnNumber6<A, B, C, D, E, F> {
+pub fn rate___<A: Number, B: Number, C: Number, D: Number, E: Number>(nper: A, payment: B, pv: C, fv: D, pay_type: PayType, guess: E) -> FnNumber6<A, B, C, D, PayType, E> {
     FnNumber6("RATE", nper, payment, pv, fv, pay_type, guess)
 }
 
@@ -764,7 +764,7 @@ pub fn numbervalue<A: Text>(x: A) -> FnNumber1<A> {
 ///
 /// __See also__: [crate::of::n()], [crate::of::t()], [crate::of::datevalue()], [crate::of::timevalue()], [crate::of::value()], [crate::of::numbervalue()], [crate::of::numbervalue__()], 
 #[inline]
-pub fn numbervalue_<A: Text, B: Text>(x: A, decimal_separator: B) -> FnNumber2<A, B> {
+pub fn numbervalue_<A: Text>(x: A, decimal_separator: NumberSeparator) -> FnNumber2<A, NumberSeparator> {
     FnNumber2("NUMBERVALUE", x, decimal_separator)
 }
 
@@ -810,7 +810,7 @@ pub fn numbervalue_<A: Text, B: Text>(x: A, decimal_separator: B) -> FnNumber2<A
 ///
 /// __See also__: [crate::of::n()], [crate::of::t()], [crate::of::datevalue()], [crate::of::timevalue()], [crate::of::value()], [crate::of::numbervalue()], [crate::of::numbervalue_()], 
 #[inline]
-pub fn numbervalue__<A: Text, B: Text, C: Text>(x: A, decimal_separator: B, group_separator: C) -> FnNumber3<A, B, C> {
+pub fn numbervalue__<A: Text>(x: A, decimal_separator: NumberSeparator, group_separator: NumberSeparator) -> FnNumber3<A, NumberSeparator, NumberSeparator> {
     FnNumber3("NUMBERVALUE", x, decimal_separator, group_separator)
 }
 
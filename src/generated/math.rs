@@ -381,76 +381,6 @@ pub fn combina<A: Number, B: Number>(n: A, m: B) -> FnNumber2<A, B> {
     FnNumber2("COMBINA", n, m)
 }
 
-/// Returns a number converted from one unit system into another.
-///
-/// [documentfoundation->CONVERT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/CONVERT)
-///
-/// __Syntax__: 
-/// ```ods
-///     CONVERT( N: Number; From: Text; Into: Text )
-/// ```
-///
-/// __Constraints__:
-/// From and Into shall be legal units, and shall be in the same unit group.
-///
-/// __Semantics__:
-/// Returns the number converted from the unit identified by From into the unit 
-/// identified by Into. A unit is a unit symbol , optionally preceded by a unit 
-/// prefix (either a decimal prefix or a binary prefix, as specified in Table 
-/// 25 - Decimal Prefixes for use in CONVERT and Table 26 - Binary prefixes for 
-/// use in CONVERT respectively). Units (including both the unit symbol and the 
-/// optional unit prefix) are case-sensitive.
-/// 
-/// Evaluators claiming to implement this function shall support at least the 
-/// following unit symbols (with conversions between them and other units in 
-/// the same group):
-/// 
-/// If a conversion factor (as listed above) is not exact, an implementation 
-/// may use a more accurate conversion factor instead.
-/// 
-/// Implementation-defined unit names should contain a 'FULL STOP' (U+002E) 
-/// character.
-/// 
-/// Evaluators shall support decimal prefixes for unit symbols marked with * 
-/// and binary prefixes for unit symbols marked with †. Evaluators should not 
-/// support prefixes for other unit symbols.
-/// 
-/// The unit symbols in parentheses are deprecated unit symbols; evaluators 
-/// shall support these unit symbols.
-/// 
-/// Evaluators should use internationally-standardized unit name abbreviations 
-/// for such additions where possible. Evaluators may support the obsolete 
-/// symbols “p” and “P” as unit names for Pascals.
-/// 
-/// For purposes of this function, a year is exactly 365.25 days long.
-/// 
-/// Evaluators claiming to support this function shall permit the unit decimal 
-/// prefixes specified in Table 25 - Decimal Prefixes for use in CONVERT to be 
-/// prepended to any unit symbol marked with * in Table 24 - Unit names. Adding 
-/// a unit prefix indicates multiplication of the (scalar) unit by the given 
-/// prefix value; for example km indicates kilometres, and km2 or km^2 indicate 
-/// square kilometres.
-///
-/// __Note__:
-/// The prefix “e” for 10 1 is nonstandard and included for backward 
-/// compatibility with legacy applications and documents.
-/// 
-/// The unit names marked with † in Table 24 - Unit names (see the 
-/// Information Unit group) shall also support the following binary prefixes 
-/// per IEC 60027-2:
-/// 
-/// In the case where there is a naming conflict (a unit name with a prefix is 
-/// the same as an unprefixed name), the unprefixed name shall take precedence.
-/// 
-/// Evaluators may implement this conversion by first converting to some SI 
-/// unit (e.g., meter and kilogram), and then convert again to the final unit.
-///
-/// __See also__: [crate::of::euroconvert()], 
-#[inline]
-pub fn convert<A: Number>(n: A, from: ConvertUnit, into: ConvertUnit) -> FnNumber3<A, ConvertUnit, ConvertUnit> {
-    FnNumber3("CONVERT", n, from, into)
-}
-
 /// Return the cosine of an angle specified in radians.
 ///
 /// [documentfoundation->COS](https://wiki.documentfoundation.org/Documentation/Calc_Functions/COS)
@@ -772,7 +702,7 @@ pub fn erfc<A: Number>(z: A) -> FnNumber1<A> {
 ///
 /// __See also__: [crate::of::convert()], [crate::of::euroconvert_()], [crate::of::euroconvert__()], 
 #[inline]
-pub fn euroconvert<A: Number, B: Text, C: Text>(n: A, from: B, to: C) -> FnNumber3<A, B, C> {
+pub fn euroconvert<A: Number>(n: A, from: EuroCurrency, to: EuroCurrency) -> FnNumber3<A, EuroCurrency, EuroCurrency> {
     FnNumber3("EUROCONVERT", n, from, to)
 }
 
@@ -828,7 +758,7 @@ pub fn euroconvert<A: Number, B: Text, C: Text>(n: A, from: B, to: C) -> FnNumbe
 ///
 /// __See also__: [crate::of::convert()], [crate::of::euroconvert()], [crate::of::euroconvert__()], 
 #[inline]
-pub fn euroconvert_<A: Number, B: Text, C: Text, D: Logical>(n: A, from: B, to: C, full_precision: D) -> FnNumber4<A, B, C, D> {
+pub fn euroconvert_<A: Number, B: Logical>(n: A, from: EuroCurrency, to: EuroCurrency, full_precision: B) -> FnNumber4<A, EuroCurrency, EuroCurrency, B> {
     FnNumber4("EUROCONVERT", n, from, to, full_precision)
 }
 
@@ -884,7 +814,7 @@ pub fn euroconvert_<A: Number, B: Text, C: Text, D: Logical>(n: A, from: B, to:
 ///
 /// __See also__: [crate::of::convert()], [crate::of::euroconvert()], [crate::of::euroconvert_()], 
 #[inline]
-pub fn euroconvert__<A: Number, B: Text, C: Text, D: Logical, E: Number>(n: A, from: B, to: C, full_precision: D, triangulation_precision: E) -> FnNumber5<A, B, C, D, E> {
+pub fn euroconvert__<A: Number, B: Logical, C: Number>(n: A, from: EuroCurrency, to: EuroCurrency, full_precision: B, triangulation_precision: C) -> FnNumber5<A, EuroCurrency, EuroCurrency, B, C> {
     FnNumber5("EUROCONVERT", n, from, to, full_precision, triangulation_precision)
 }
 
@@ -2157,7 +2087,7 @@ pub fn ceiling_math_<A: Number, B: Number>(number: A, significance: B) -> FnNumb
 ///
 /// __See also__: [crate::of::ceiling_math()], [crate::of::ceiling_math_()], 
 #[inline]
-pub fn ceiling_math__<A: Number, B: Number, C: Number>(number: A, significance: B, mode: C) -> FnNumber3<A, B, C> {
+pub fn ceiling_math__<A: Number, B: Number>(number: A, significance: B, mode: RoundingMode) -> FnNumber3<A, B, RoundingMode> {
     FnNumber3("CEILING.MATH", number, significance, mode)
 }
 
@@ -0,0 +1,405 @@
+//! Return types of the functions as given in the OpenFormula specification.
+
+/// Function names and their return types, sorted by name.
+pub(crate) const RETURNS: &[(&str, &str)] = &[
+    ("ABS", "Number"),
+    ("ACCRINT", "Currency"),
+    ("ACCRINTM", "Currency"),
+    ("ACOS", "Number"),
+    ("ACOSH", "Number"),
+    ("ACOT", "Number"),
+    ("ACOTH", "Number"),
+    ("ADDRESS", "Text"),
+    ("AGGREGATE", "Number"),
+    ("AMORDEGRC", "Number"),
+    ("AMORLINC", "Currency"),
+    ("AND", "Logical"),
+    ("ARABIC", "Number"),
+    ("AREAS", "Number"),
+    ("ASC", "Text"),
+    ("ASIN", "Number"),
+    ("ASINH", "Number"),
+    ("ATAN", "Number"),
+    ("ATAN2", "Number"),
+    ("ATANH", "Number"),
+    ("AVEDEV", "Number"),
+    ("AVERAGE", "Number"),
+    ("AVERAGEA", "Number"),
+    ("AVERAGEIF", "Number"),
+    ("AVERAGEIFS", "Number"),
+    ("B", "Number"),
+    ("BASE", "Text"),
+    ("BESSELI", "Number"),
+    ("BESSELJ", "Number"),
+    ("BESSELK", "Number"),
+    ("BESSELY", "Number"),
+    ("BETA.DIST", "Number"),
+    ("BETA.INV", "Number"),
+    ("BETADIST", "Number"),
+    ("BETAINV", "Number"),
+    ("BIN2DEC", "Number"),
+    ("BIN2HEX", "Text"),
+    ("BIN2OCT", "Text"),
+    ("BINOM.DIST", "Number"),
+    ("BINOM.DIST.RANGE", "Number"),
+    ("BINOM.INV", "Integer"),
+    ("BINOMDIST", "Number"),
+    ("BITAND", "Number"),
+    ("BITLSHIFT", "Number"),
+    ("BITOR", "Number"),
+    ("BITRSHIFT", "Number"),
+    ("BITXOR", "Number"),
+    ("CEILING", "Number"),
+    ("CEILING.MATH", "Number"),
+    ("CEILING.PRECISE", "Number"),
+    ("CEILING.XCL", "Number"),
+    ("CELL", "Information about position, formatting properties or content"),
+    ("CHAR", "Text"),
+    ("CHISQDIST", "Number"),
+    ("CHISQINV", "Number"),
+    ("CHOOSE", "Any"),
+    ("CLEAN", "Text"),
+    ("CODE", "Number"),
+    ("COLUMN", "Number"),
+    ("COLUMNS", "Number"),
+    ("COMBIN", "Number"),
+    ("COMBINA", "Number"),
+    ("COMPLEX", "Complex"),
+    ("CONCATENATE", "Text"),
+    ("CONFIDENCE", "Number"),
+    ("CONVERT", "Number"),
+    ("CORREL", "Number"),
+    ("COS", "Number"),
+    ("COSH", "Number"),
+    ("COT", "Number"),
+    ("COTH", "Number"),
+    ("COUNT", "Number"),
+    ("COUNTA", "Number"),
+    ("COUNTBLANK", "Number"),
+    ("COUNTIF", "Number"),
+    ("COUNTIFS", "Number"),
+    ("COUPDAYBS", "Number"),
+    ("COUPDAYS", "Number"),
+    ("COUPDAYSNC", "Number"),
+    ("COUPNCD", "Date"),
+    ("COUPNUM", "Number"),
+    ("COUPPCD", "Date"),
+    ("COVAR", "Number"),
+    ("CRITBINOM", "Number"),
+    ("CSC", "Number"),
+    ("CSCH", "Number"),
+    ("CUMIPMT", "Currency"),
+    ("CUMPRINC", "Currency"),
+    ("DATE", "Date"),
+    ("DATEDIF", "Number"),
+    ("DATEVALUE", "Date"),
+    ("DAVERAGE", "Number"),
+    ("DAY", "Number"),
+    ("DAYS", "Number"),
+    ("DAYS360", "Number"),
+    ("DB", "Currency"),
+    ("DCOUNT", "Number"),
+    ("DCOUNTA", "Number"),
+    ("DDB", "Currency"),
+    ("DDE", "Number|Text"),
+    ("DEC2BIN", "Text"),
+    ("DEC2HEX", "Text"),
+    ("DEC2OCT", "Text"),
+    ("DECIMAL", "Number"),
+    ("DEGREES", "Number"),
+    ("DELTA", "Number"),
+    ("DEVSQ", "Number"),
+    ("DGET", "Number"),
+    ("DISC", "Percentage"),
+    ("DMAX", "Number"),
+    ("DMIN", "Number"),
+    ("DOLLAR", "Text"),
+    ("DOLLARDE", "Number"),
+    ("DOLLARFR", "Number"),
+    ("DPRODUCT", "Number"),
+    ("DSTDEV", "Number"),
+    ("DSTDEVP", "Number"),
+    ("DSUM", "Number"),
+    ("DURATION", "Number"),
+    ("DVAR", "Number"),
+    ("DVARP", "Number"),
+    ("EDATE", "Number"),
+    ("EFFECT", "Number"),
+    ("EOMONTH", "Number"),
+    ("ERF", "Number"),
+    ("ERFC", "Number"),
+    ("ERROR.TYPE", "Number"),
+    ("EUROCONVERT", "Currency"),
+    ("EVEN", "Number"),
+    ("EXACT", "Logical"),
+    ("EXP", "Number"),
+    ("EXPONDIST", "Number"),
+    ("FACT", "Number"),
+    ("FACTDOUBLE", "Number"),
+    ("FALSE", "Logical"),
+    ("FDIST", "Number"),
+    ("FIND", "Number"),
+    ("FINDB", "BytePosition"),
+    ("FINV", "Number"),
+    ("FISHER", "Number"),
+    ("FISHERINV", "Number"),
+    ("FIXED", "Text"),
+    ("FLOOR", "Number"),
+    ("FORECAST", "Number"),
+    ("FORMULA", "String"),
+    ("FREQUENCY", "Array"),
+    ("FTEST", "Number"),
+    ("FV", "Currency"),
+    ("FVSCHEDULE", "Currency"),
+    ("GAMMA", "Number"),
+    ("GAMMADIST", "Number"),
+    ("GAMMAINV", "Number"),
+    ("GAMMALN", "Number"),
+    ("GAUSS", "Number"),
+    ("GCD", "Number"),
+    ("GEOMEAN", "Number"),
+    ("GESTEP", "Number"),
+    ("GROWTH", "Array"),
+    ("HARMEAN", "Number"),
+    ("HEX2BIN", "Text"),
+    ("HEX2DEC", "Number"),
+    ("HEX2OCT", "Text"),
+    ("HLOOKUP", "Any"),
+    ("HOUR", "Number"),
+    ("HYPERLINK", "Text or Number"),
+    ("HYPGEOMDIST", "Number"),
+    ("IF", "Any"),
+    ("IFERROR", "Any"),
+    ("IFNA", "Any"),
+    ("IMABS", "Number"),
+    ("IMAGINARY", "Number"),
+    ("IMARGUMENT", "Number"),
+    ("IMCONJUGATE", "Complex"),
+    ("IMCOS", "Complex"),
+    ("IMCOSH", "Complex"),
+    ("IMCOT", "Complex"),
+    ("IMCSC", "Complex"),
+    ("IMCSCH", "Complex"),
+    ("IMDIV", "Complex"),
+    ("IMEXP", "Complex"),
+    ("IMLN", "Complex"),
+    ("IMLOG10", "Complex"),
+    ("IMLOG2", "Complex"),
+    ("IMPOWER", "Complex"),
+    ("IMPRODUCT", "Complex"),
+    ("IMREAL", "Number"),
+    ("IMSEC", "Complex"),
+    ("IMSECH", "Number"),
+    ("IMSIN", "Complex"),
+    ("IMSINH", "Complex"),
+    ("IMSQRT", "Complex"),
+    ("IMSUB", "Complex"),
+    ("IMSUM", "Complex"),
+    ("IMTAN", "Complex"),
+    ("INDEX", "Any"),
+    ("INDIRECT", "Reference"),
+    ("INFO", "Any (see below)"),
+    ("INT", "Number"),
+    ("INTERCEPT", "Number"),
+    ("INTRATE", "Number"),
+    ("IPMT", "Currency"),
+    ("IRR", "Percentage"),
+    ("ISBLANK", "Logical"),
+    ("ISERR", "Logical"),
+    ("ISERROR", "Logical"),
+    ("ISEVEN", "Logical"),
+    ("ISFORMULA", "Logical"),
+    ("ISLOGICAL", "Logical"),
+    ("ISNA", "Logical"),
+    ("ISNONTEXT", "Logical"),
+    ("ISNUMBER", "Logical"),
+    ("ISODD", "Logical"),
+    ("ISOWEEKNUM", "Number"),
+    ("ISPMT", "Currency"),
+    ("ISREF", "Logical"),
+    ("ISTEXT", "Logical"),
+    ("JIS", "Text"),
+    ("KURT", "Number"),
+    ("LARGE", "Number or Array"),
+    ("LCM", "Number"),
+    ("LEFT", "Text"),
+    ("LEFTB", "Text"),
+    ("LEGACY.CHIDIST", "Number"),
+    ("LEGACY.CHIINV", "Number"),
+    ("LEGACY.CHITEST", "Number"),
+    ("LEGACY.FDIST", "Number"),
+    ("LEGACY.FINV", "Number"),
+    ("LEGACY.NORMSDIST", "Number"),
+    ("LEGACY.NORMSINV", "Number"),
+    ("LEGACY.TDIST", "Number"),
+    ("LEN", "Integer"),
+    ("LENB", "ByteLength"),
+    ("LINEST", "Array"),
+    ("LN", "Number"),
+    ("LOG", "Number"),
+    ("LOG10", "Number"),
+    ("LOGEST", "Array"),
+    ("LOGINV", "Number"),
+    ("LOGNORMDIST", "Number"),
+    ("LOOKUP", "Any"),
+    ("LOWER", "Text"),
+    ("MATCH", "Any"),
+    ("MAX", "Number"),
+    ("MAXA", "Number"),
+    ("MDETERM", "Number"),
+    ("MDURATION", "Number"),
+    ("MEDIAN", "Number"),
+    ("MID", "Text"),
+    ("MIDB", "Text"),
+    ("MIN", "Number"),
+    ("MINA", "Number"),
+    ("MINUTE", "Number"),
+    ("MINVERSE", "Array"),
+    ("MIRR", "Percentage"),
+    ("MMULT", "Array"),
+    ("MOD", "Number"),
+    ("MONTH", "Number"),
+    ("MROUND", "Number"),
+    ("MULTINOMIAL", "Number"),
+    ("MULTIPLE.OPERATIONS", "Any"),
+    ("MUNIT", "Array"),
+    ("N", "Number"),
+    ("NA", "Error"),
+    ("NEGBINOMDIST", "Number"),
+    ("NETWORKDAYS", "Number"),
+    ("NOMINAL", "Number"),
+    ("NORMDIST", "Number"),
+    ("NORMINV", "Number"),
+    ("NOT", "Logical"),
+    ("NOW", "DateTime"),
+    ("NPER", "Number"),
+    ("NPV", "Currency"),
+    ("NUMBERVALUE", "Number"),
+    ("OCT2BIN", "Text"),
+    ("OCT2DEC", "Number"),
+    ("OCT2HEX", "Text"),
+    ("ODD", "Number"),
+    ("ODDFPRICE", "Number"),
+    ("ODDFYIELD", "Number"),
+    ("ODDLPRICE", "Number"),
+    ("ODDLYIELD", "Number"),
+    ("OFFSET", "Reference"),
+    ("OR", "Logical"),
+    ("PDURATION", "Number"),
+    ("PEARSON", "Number"),
+    ("PERCENTILE", "Number"),
+    ("PERCENTRANK", "Number"),
+    ("PERMUT", "Number"),
+    ("PERMUTATIONA", "Number"),
+    ("PHI", "Number"),
+    ("PI", "Number"),
+    ("PMT", "Currency"),
+    ("POISSON", "Number"),
+    ("POWER", "Number"),
+    ("PPMT", "Number"),
+    ("PRICE", "Number"),
+    ("PRICEDISC", "Number"),
+    ("PRICEMAT", "Number"),
+    ("PROB", "Number"),
+    ("PRODUCT", "Number"),
+    ("PROPER", "Text"),
+    ("PV", "Currency"),
+    ("QUARTILE", "Number"),
+    ("QUOTIENT", "Number"),
+    ("RADIANS", "Number"),
+    ("RAND", "Number"),
+    ("RANDBETWEEN", "Integer"),
+    ("RANK", "Number"),
+    ("RATE", "Percentage"),
+    ("RECEIVED", "Number"),
+    ("REPLACE", "Text"),
+    ("REPLACEB", "Text"),
+    ("REPT", "Text"),
+    ("RIGHT", "Text"),
+    ("RIGHTB", "Text"),
+    ("ROMAN", "Text"),
+    ("ROUND", "Number"),
+    ("ROUNDDOWN", "Number"),
+    ("ROUNDUP", "Number"),
+    ("ROW", "Number"),
+    ("ROWS", "Number"),
+    ("RRI", "Percentage"),
+    ("RSQ", "Number"),
+    ("SEARCH", "Integer"),
+    ("SEARCHB", "BytePosition"),
+    ("SEC", "Number"),
+    ("SECH", "Number"),
+    ("SECOND", "Number"),
+    ("SERIESSUM", "Number"),
+    ("SHEET", "Number ≥ 1"),
+    ("SHEETS", "Number ≥ 1"),
+    ("SIGN", "Number"),
+    ("SIN", "Number"),
+    ("SINH", "Number"),
+    ("SKEW", "Number"),
+    ("SKEWP", "Number"),
+    ("SLN", "Currency"),
+    ("SLOPE", "Number"),
+    ("SMALL", "Number or Array"),
+    ("SQRT", "Number"),
+    ("SQRTPI", "Number"),
+    ("STANDARDIZE", "Number"),
+    ("STDEV", "Number"),
+    ("STDEVA", "Number"),
+    ("STDEVP", "Number"),
+    ("STDEVPA", "Number"),
+    ("STEYX", "Number"),
+    ("SUBSTITUTE", "Text"),
+    ("SUBTOTAL", "Number"),
+    ("SUM", "Number"),
+    ("SUMIF", "Number"),
+    ("SUMIFS", "Number"),
+    ("SUMPRODUCT", "Number"),
+    ("SUMSQ", "Number"),
+    ("SUMX2MY2", "Number"),
+    ("SUMX2PY2", "Number"),
+    ("SUMXMY2", "Number"),
+    ("SYD", "Currency"),
+    ("T", "Text"),
+    ("TAN", "Number"),
+    ("TANH", "Number"),
+    ("TBILLEQ", "Number"),
+    ("TBILLPRICE", "Number"),
+    ("TBILLYIELD", "Number"),
+    ("TEXT", "Text"),
+    ("TIME", "Time"),
+    ("TIMEVALUE", "Time"),
+    ("TINV", "Number"),
+    ("TODAY", "Date"),
+    ("TRANSPOSE", "Array"),
+    ("TREND", "Array"),
+    ("TRIM", "Text"),
+    ("TRIMMEAN", "Number"),
+    ("TRUE", "Logical"),
+    ("TRUNC", "Number"),
+    ("TTEST", "Number"),
+    ("TYPE", "Number"),
+    ("UNICHAR", "Text"),
+    ("UNICODE", "Number"),
+    ("UPPER", "Text"),
+    ("VALUE", "Number"),
+    ("VAR", "Number"),
+    ("VARA", "Number"),
+    ("VARP", "Number"),
+    ("VARPA", "Number"),
+    ("VDB", "Number"),
+    ("VLOOKUP", "Any"),
+    ("WEEKDAY", "Number"),
+    ("WEEKNUM", "Number"),
+    ("WEIBULL", "Number"),
+    ("WORKDAY", "DateTime"),
+    ("XIRR", "Number"),
+    ("XNPV", "Number"),
+    ("XOR", "Logical"),
+    ("YEAR", "Number"),
+    ("YEARFRAC", "Number"),
+    ("YIELD", "Number"),
+    ("YIELDDISC", "Number"),
+    ("YIELDMAT", "Number"),
+    ("ZTEST", "Number"),
+];
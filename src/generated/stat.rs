@@ -2879,7 +2879,7 @@ pub fn rank<A: Number, B: Sequence>(value: A, data: B) -> FnNumber2<A, B> {
 ///
 /// __See also__: [crate::of::rank()], 
 #[inline]
-pub fn rank_<A: Number, B: Sequence, C: Number>(value: A, data: B, order: C) -> FnNumber3<A, B, C> {
+pub fn rank_<A: Number, B: Sequence>(value: A, data: B, order: RankOrder) -> FnNumber3<A, B, RankOrder> {
     FnNumber3("RANK", value, data, order)
 }
 
@@ -3205,7 +3205,7 @@ pub fn steyx<A: Array, B: Array>(measured_y: A, x: B) -> FnNumber2<A, B> {
 ///
 /// __See also__: [crate::of::gamma()], [crate::of::betadist()], [crate::of::binomdist()], [crate::of::chisqdist()], [crate::of::expondist()], [crate::of::fdist()], [crate::of::gammadist()], [crate::of::gauss()], [crate::of::hypgeomdist()], [crate::of::lognormdist()], [crate::of::negbinomdist()], [crate::of::normdist()], [crate::of::poisson()], [crate::of::weibull()], 
 #[inline]
-pub fn legacy_tdist<A: Number, B: Number, C: Number>(x: A, df: B, tails: C) -> FnNumber3<A, B, C> {
+pub fn legacy_tdist<A: Number, B: Number>(x: A, df: B, tails: TTestTails) -> FnNumber3<A, B, TTestTails> {
     FnNumber3("LEGACY.TDIST", x, df, tails)
 }
 
@@ -3530,7 +3530,7 @@ pub fn trimmean<A: Sequence, B: Number>(data_set: A, cut_off_fraction: B) -> FnN
 ///
 /// __See also__: [crate::of::columns()], [crate::of::count()], [crate::of::rows()], [crate::of::ftest()], [crate::of::legacy_tdist()], [crate::of::ztest()], 
 #[inline]
-pub fn ttest<A: Array, B: Array, C: Number, D: Number>(x: A, y: B, tails: C, type_: D) -> FnNumber4<A, B, C, D> {
+pub fn ttest<A: Array, B: Array>(x: A, y: B, tails: TTestTails, type_: TTestType) -> FnNumber4<A, B, TTestTails, TTestType> {
     FnNumber4("TTEST", x, y, tails, type_)
 }
 
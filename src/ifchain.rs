@@ -0,0 +1,214 @@
+//!
+//! Flattening of nested IF chains.
+//!
+//! `IF(c1;v1;IF(c2;v2;d))` grows one level for every case. [flatten_if]
+//! rewrites such chains:
+//!
+//! * If every condition compares the same expression with a constant,
+//!   the chain becomes SWITCH.
+//! * Any other chain becomes IFS.
+//!
+//! IFS and SWITCH are not part of ODF 1.2. With [IfTarget::Odf12] a chain
+//! that compares the same expression with the numbers 1..n, each one once,
+//! becomes CHOOSE. `OR(x={1;2;…})` checks that the expression is one of the
+//! numbers, otherwise the default is used. An error in the expression is
+//! returned, as in the IF chain. Other chains stay nested IF.
+//!
+//! A missing else of the last IF is FALSE(), as for IF. Chains with an
+//! omitted value are not changed. Expressions with volatile functions are
+//! never used as the compared expression, because they are evaluated only
+//! once by SWITCH.
+//!
+//! ```
+//! use spreadsheet_ods_formula::expr::Expr;
+//! use spreadsheet_ods_formula::formula;
+//! use spreadsheet_ods_formula::ifchain::{flatten_if, IfTarget};
+//!
+//! let e = Expr::parse("of:=IF([.A1]=\"a\";1;IF([.A1]=\"b\";2;0))").expect("expr");
+//! assert_eq!(
+//!     formula(flatten_if(&e, IfTarget::default())),
+//!     "of:=COM.MICROSOFT.SWITCH([.A1];\"a\";1;\"b\";2;0)"
+//! );
+//! assert_eq!(
+//!     formula(flatten_if(&e, IfTarget::Odf12)),
+//!     "of:=IF([.A1]=\"a\";1;IF([.A1]=\"b\";2;0))"
+//! );
+//! ```
+//!
+
+use crate::canon::Canonical;
+use crate::expr::{Expr, InfixOp};
+use crate::simplify::is_volatile;
+
+/// Functions that can be used by [flatten_if].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IfTarget {
+    /// Only ODF 1.2 functions. Chains over 1..n become CHOOSE, others
+    /// stay nested IF.
+    Odf12,
+    /// IFS and SWITCH as supported by LibreOffice and Excel.
+    #[default]
+    Extended,
+}
+
+/// Rewrites all chains of nested IF with at least two conditions.
+pub fn flatten_if(expr: &Expr, target: IfTarget) -> Expr {
+    let flatten = |v: &Expr| flatten_if(v, target);
+    match expr {
+        Expr::Array(rows) => Expr::Array(
+            rows.iter()
+                .map(|r| r.iter().map(flatten).collect())
+                .collect(),
+        ),
+        Expr::Func(name, args) => {
+            if name.eq_ignore_ascii_case("IF")
+                && let Some((cases, default)) = chain(expr)
+                && cases.len() >= 2
+                && let Some(v) = rewrite(&cases, &default, target)
+            {
+                v
+            } else {
+                Expr::Func(name.clone(), args.iter().map(flatten).collect())
+            }
+        }
+        Expr::Unary(op, v) => Expr::Unary(*op, Box::new(flatten(v))),
+        Expr::Infix(a, op, b) => Expr::infix(flatten(a), *op, flatten(b)),
+        Expr::Paren(v) => Expr::Paren(Box::new(flatten(v))),
+        _ => expr.clone(),
+    }
+}
+
+/// Conditions with their values, and the default.
+fn chain(expr: &Expr) -> Option<(Vec<(&Expr, &Expr)>, Expr)> {
+    let mut cases = Vec::new();
+    let mut e = expr;
+    while let Expr::Func(name, args) = e {
+        if !name.eq_ignore_ascii_case("IF")
+            || !(args.len() == 2 || args.len() == 3)
+            || args[..2].contains(&Expr::Empty)
+            || args.get(2) == Some(&Expr::Empty)
+        {
+            break;
+        }
+        cases.push((&args[0], &args[1]));
+        match args.get(2) {
+            None => return Some((cases, Expr::func("FALSE", Vec::new()))),
+            Some(v) => e = unparen(v),
+        }
+    }
+    if cases.is_empty() {
+        None
+    } else {
+        Some((cases, e.clone()))
+    }
+}
+
+fn unparen(e: &Expr) -> &Expr {
+    match e {
+        Expr::Paren(v) => unparen(v),
+        e => e,
+    }
+}
+
+/// Splits `x=k` into the compared expression and the constant.
+fn compare(cond: &Expr) -> Option<(&Expr, &Expr)> {
+    let Expr::Infix(a, InfixOp::Eq, b) = unparen(cond) else {
+        return None;
+    };
+    let is_const = |e: &Expr| matches!(e, Expr::Number(_) | Expr::Text(_));
+    let (subject, k) = match (is_const(b), is_const(a)) {
+        (true, false) => (a, b),
+        (false, true) => (b, a),
+        _ => return None,
+    };
+    (!is_volatile(subject)).then_some((subject.as_ref(), k.as_ref()))
+}
+
+/// Compared expression and the constants, if all conditions compare
+/// the same expression.
+fn subject(cases: &[(Expr, Expr)]) -> Option<(Expr, Vec<Expr>)> {
+    let mut subject: Option<(String, Expr)> = None;
+    let mut consts = Vec::new();
+    for (c, _) in cases {
+        let (s, k) = compare(c)?;
+        let s = unparen(s);
+        let sk = Canonical::key(s, None);
+        match &subject {
+            Some((first, _)) if *first != sk => return None,
+            Some(_) => {}
+            None => subject = Some((sk, s.clone())),
+        }
+        consts.push(k.clone());
+    }
+    subject.map(|(_, s)| (s, consts))
+}
+
+/// The constants are 1..n, each one once.
+fn dense(consts: &[Expr]) -> bool {
+    let mut seen = vec![false; consts.len()];
+    for k in consts {
+        let Expr::Number(v) = k else {
+            return false;
+        };
+        if v.fract() != 0.0 || *v < 1.0 || *v > consts.len() as f64 {
+            return false;
+        }
+        let i = *v as usize - 1;
+        if seen[i] {
+            return false;
+        }
+        seen[i] = true;
+    }
+    true
+}
+
+fn rewrite(cases: &[(&Expr, &Expr)], default: &Expr, target: IfTarget) -> Option<Expr> {
+    let flatten = |v: &Expr| flatten_if(v, target);
+    let cases: Vec<(Expr, Expr)> = cases
+        .iter()
+        .map(|(c, v)| (flatten(c), flatten(v)))
+        .collect();
+    let default = flatten(default);
+    if let Some((s, consts)) = subject(&cases) {
+        if target == IfTarget::Odf12 && dense(&consts) {
+            let mut values = vec![Expr::Empty; consts.len()];
+            for (k, (_, v)) in consts.iter().zip(&cases) {
+                if let Expr::Number(k) = k {
+                    values[*k as usize - 1] = v.clone();
+                }
+            }
+            // An error in s is an error of OR too.
+            let list = Expr::Array(vec![
+                (1..=consts.len()).map(|v| Expr::Number(v as f64)).collect(),
+            ]);
+            let found = Expr::func("OR", vec![Expr::infix(s.clone(), InfixOp::Eq, list)]);
+            let mut args = vec![s];
+            args.extend(values);
+            return Some(Expr::func(
+                "IF",
+                vec![found, Expr::func("CHOOSE", args), default],
+            ));
+        }
+        if target == IfTarget::Extended {
+            let mut args = vec![s];
+            for (k, (_, v)) in consts.into_iter().zip(cases) {
+                args.push(k);
+                args.push(v);
+            }
+            args.push(default);
+            return Some(Expr::func("COM.MICROSOFT.SWITCH", args));
+        }
+    }
+    if target == IfTarget::Extended {
+        let mut args = Vec::new();
+        for (c, v) in cases {
+            args.push(c);
+            args.push(v);
+        }
+        args.push(Expr::func("TRUE", Vec::new()));
+        args.push(default);
+        Some(Expr::func("COM.MICROSOFT.IFS", args))
+    } else {
+        None
+    }
+}
@@ -4,11 +4,16 @@
 
 pub use crate::generated::info::*;
 
-use crate::{Any, Criterion, FnNumberVar, Reference};
-use std::fmt::Write;
+use crate::{
+    Any, Criterion, FnAny0, FnNumber1, FnNumber2, FnNumber3, FnNumber4, FnNumberVar, Number,
+    Reference, Text,
+};
+use spreadsheet_ods::color::{Rgb, Rgba};
+use std::fmt::{self, Write};
+use std::sync::Arc;
 
 /// Parameter for CELL()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CellInfo {
     Address,
     Col,
@@ -27,8 +32,9 @@ pub enum CellInfo {
 }
 
 impl Any for CellInfo {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -47,12 +53,12 @@ impl Any for CellInfo {
                 CellInfo::Type => "TYPE",
                 CellInfo::Width => "WIDTH",
             }
-        );
+        )
     }
 }
 
 /// Parameter for INFO()
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InfoInfo {
     Directory,
     MemAvail,
@@ -67,8 +73,9 @@ pub enum InfoInfo {
 }
 
 impl Any for InfoInfo {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -83,21 +90,251 @@ impl Any for InfoInfo {
                 InfoInfo::System => "system",
                 InfoInfo::TotMem => "totmem",
             }
-        );
+        )
+    }
+}
+
+/// Parameter for NUMBERVALUE()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberSeparator {
+    /// "."
+    Period,
+    /// ","
+    Comma,
+    /// " "
+    Space,
+    /// No-break space U+00A0
+    NoBreakSpace,
+    /// Narrow no-break space U+202F
+    NarrowNoBreakSpace,
+    /// "'"
+    Apostrophe,
+    /// Right single quotation mark U+2019
+    RightSingleQuotation,
+}
+
+impl Any for NumberSeparator {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            NumberSeparator::Period => "\".\"",
+            NumberSeparator::Comma => "\",\"",
+            NumberSeparator::Space => "\" \"",
+            NumberSeparator::NoBreakSpace => "\"\u{a0}\"",
+            NumberSeparator::NarrowNoBreakSpace => "\"\u{202f}\"",
+            NumberSeparator::Apostrophe => "\"'\"",
+            NumberSeparator::RightSingleQuotation => "\"\u{2019}\"",
+        })
+    }
+}
+
+/// Result of TYPE()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeCode {
+    /// 1
+    Number,
+    /// 2
+    Text,
+    /// 4
+    Logical,
+    /// 8, LibreOffice only.
+    Formula,
+    /// 16
+    Error,
+    /// 64
+    Array,
+}
+
+impl TypeCode {
+    /// The numeric code.
+    pub fn code(&self) -> u8 {
+        match self {
+            TypeCode::Number => 1,
+            TypeCode::Text => 2,
+            TypeCode::Logical => 4,
+            TypeCode::Formula => 8,
+            TypeCode::Error => 16,
+            TypeCode::Array => 64,
+        }
+    }
+}
+
+impl Any for TypeCode {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "{}", self.code())
     }
 }
 
 /// Count the number of cells that meet multiple criteria in multiple ranges.
 #[inline]
-pub fn countifs<R: Reference + 'static, C: Criterion + 'static, const N: usize>(
+pub fn countifs<
+    R: Reference + Send + Sync + 'static,
+    C: Criterion + Send + Sync + 'static,
+    const N: usize,
+>(
     list: [(R, C); N],
 ) -> FnNumberVar {
-    let mut param: Vec<Box<dyn Any>> = Vec::new();
+    let mut param: Vec<Arc<dyn Any + Send + Sync>> = Vec::new();
 
     for (r, c) in list {
-        param.push(Box::new(r));
-        param.push(Box::new(c));
+        param.push(Arc::new(r));
+        param.push(Arc::new(c));
     }
 
     FnNumberVar("COUNTIFS", param)
 }
+
+/// Applies a cell style to the cell containing the formula.
+///
+/// [documentfoundation->STYLE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/STYLE)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.OPENOFFICE.STYLE( Style: Text [; Time: Number [; Style2: Text ]] )
+/// ```
+///
+/// __Semantics__:
+/// Applies the named cell style to the formula cell and returns 0. The style
+/// can be given as a [spreadsheet_ods::CellStyleRef]. This function is
+/// specific to LibreOffice.
+///
+/// __See also__: [crate::of::style_()], [crate::of::style__()], [crate::of::current()]
+#[inline]
+pub fn style<A: Text>(style: A) -> FnNumber1<A> {
+    FnNumber1("ORG.OPENOFFICE.STYLE", style)
+}
+
+/// Applies a cell style to the cell containing the formula, and applies
+/// the default style after the given time in seconds.
+///
+/// [documentfoundation->STYLE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/STYLE)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.OPENOFFICE.STYLE( Style: Text [; Time: Number [; Style2: Text ]] )
+/// ```
+///
+/// __See also__: [crate::of::style()], [crate::of::style__()]
+#[inline]
+pub fn style_<A: Text, B: Number>(style: A, time: B) -> FnNumber2<A, B> {
+    FnNumber2("ORG.OPENOFFICE.STYLE", style, time)
+}
+
+/// Applies a cell style to the cell containing the formula, and applies
+/// style2 after the given time in seconds.
+///
+/// [documentfoundation->STYLE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/STYLE)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.OPENOFFICE.STYLE( Style: Text [; Time: Number [; Style2: Text ]] )
+/// ```
+///
+/// __See also__: [crate::of::style()], [crate::of::style_()]
+#[inline]
+pub fn style__<A: Text, B: Number, C: Text>(style: A, time: B, style2: C) -> FnNumber3<A, B, C> {
+    FnNumber3("ORG.OPENOFFICE.STYLE", style, time, style2)
+}
+
+/// Returns the result of the formula evaluation up to this point.
+///
+/// [documentfoundation->CURRENT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/CURRENT)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.OPENOFFICE.CURRENT()
+/// ```
+///
+/// __Semantics__:
+/// Returns the intermediate result of the formula that is currently
+/// evaluated. Often used together with STYLE(). This function is specific
+/// to LibreOffice.
+///
+/// __See also__: [crate::of::style()]
+#[inline]
+pub fn current() -> FnAny0 {
+    FnAny0("ORG.OPENOFFICE.CURRENT")
+}
+
+/// Returns a numeric value calculated from the red, green and blue components.
+///
+/// [documentfoundation->COLOR](https://wiki.documentfoundation.org/Documentation/Calc_Functions/COLOR)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.LIBREOFFICE.COLOR( Red: Integer; Green: Integer; Blue: Integer [; Alpha: Integer ] )
+/// ```
+///
+/// __Constraints__:
+/// All components are in the range 0..=255.
+///
+/// __Semantics__:
+/// Returns the color as a number. This function is specific to LibreOffice.
+///
+/// __See also__: [crate::of::color_()], [crate::of::color_rgb()], [crate::of::color_rgba()]
+#[inline]
+pub fn color<A: Number, B: Number, C: Number>(red: A, green: B, blue: C) -> FnNumber3<A, B, C> {
+    FnNumber3("ORG.LIBREOFFICE.COLOR", red, green, blue)
+}
+
+/// Returns a numeric value calculated from the red, green, blue and alpha components.
+///
+/// [documentfoundation->COLOR](https://wiki.documentfoundation.org/Documentation/Calc_Functions/COLOR)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.LIBREOFFICE.COLOR( Red: Integer; Green: Integer; Blue: Integer [; Alpha: Integer ] )
+/// ```
+///
+/// __See also__: [crate::of::color()], [crate::of::color_rgba()]
+#[inline]
+pub fn color_<A: Number, B: Number, C: Number, D: Number>(
+    red: A,
+    green: B,
+    blue: C,
+    alpha: D,
+) -> FnNumber4<A, B, C, D> {
+    FnNumber4("ORG.LIBREOFFICE.COLOR", red, green, blue, alpha)
+}
+
+/// COLOR() from a spreadsheet-ods color.
+///
+/// __See also__: [crate::of::color()]
+#[inline]
+pub fn color_rgb(color: Rgb<u8>) -> FnNumber3<u8, u8, u8> {
+    FnNumber3("ORG.LIBREOFFICE.COLOR", color.r, color.g, color.b)
+}
+
+/// COLOR() with alpha channel from a spreadsheet-ods color.
+///
+/// __See also__: [crate::of::color_()]
+#[inline]
+pub fn color_rgba(color: Rgba<u8>) -> FnNumber4<u8, u8, u8, u8> {
+    FnNumber4(
+        "ORG.LIBREOFFICE.COLOR",
+        color.c.r,
+        color.c.g,
+        color.c.b,
+        color.a,
+    )
+}
+
+/// Returns the LibreOffice error number of an error value.
+///
+/// [documentfoundation->ERRORTYPE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/ERRORTYPE)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.OPENOFFICE.ERRORTYPE( E: Reference )
+/// ```
+///
+/// __Semantics__:
+/// Returns the internal LibreOffice error number, e.g. 502 for an invalid
+/// argument. Use ERROR.TYPE() for the portable error codes.
+///
+/// __See also__: [crate::of::error_type()]
+#[inline]
+pub fn errortype<A: Any>(e: A) -> FnNumber1<A> {
+    FnNumber1("ORG.OPENOFFICE.ERRORTYPE", e)
+}
@@ -18,6 +18,11 @@
 //!   The common operators are overloaded too. With the caveat that the first parameter
 //!   must be created via the num() function.
 //!
+//! * rust_decimal::Decimal and spreadsheet_ods::Value can be used as literals.
+//!
+//! * chrono NaiveDate, NaiveTime, NaiveDateTime and Duration are written as DATE()/TIME().
+//!   Use date::FDate to write them as serial numbers instead.
+//!
 //! * Expressions in parentheses must use the p()-function.
 //!
 //! ```
@@ -110,11 +115,29 @@
 // NO #![warn(unused_results)]
 #![warn(variant_size_differences)]
 
-use spreadsheet_ods::{CellRange, CellRef};
+use crate::date::ChronoValue;
+use crate::dialect::{Dialect, DialectError};
+use crate::expr::Expr;
+use crate::logic::{FLet, LetName};
+use crate::sheet::{FormulaType, NodeKind, node_type, op_type};
+use rust_decimal::Decimal;
+use spreadsheet_ods::{CellRange, CellRef, CellStyleRef, Value};
 use std::borrow::Borrow;
 use std::borrow::Cow;
-use std::fmt::{Display, Formatter, Write};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, LowerExp, Write};
 use std::ops::{Add, BitAnd, BitXor, Div, Mul, Neg, Sub};
+use std::sync::Arc;
+
+/// Implements [Any::formula] with [Any::write_formula].
+macro_rules! any_formula {
+    () => {
+        #[inline]
+        fn formula(&self, buf: &mut String) {
+            let _ = self.write_formula(buf);
+        }
+    };
+}
 
 mod generated;
 
@@ -122,21 +145,32 @@ pub mod cmp;
 pub mod op;
 
 pub mod bit;
+pub mod canon;
 pub mod complex;
 pub mod conv;
+pub mod criterion;
+pub mod cse;
 pub mod date;
 pub mod db;
+pub mod dialect;
+pub mod expr;
 pub mod ext;
 pub mod fin;
+pub mod ifchain;
 pub mod info;
 pub mod logic;
 pub mod lookup;
 pub mod math;
 pub mod matrix;
+pub mod minimize;
 pub mod round;
+pub mod sheet;
+pub mod simplify;
 pub mod stat;
 pub mod text;
 pub mod textb;
+pub mod ui;
+pub mod validate;
 
 /// The traits for this crate.
 /// And the function p() for parentheses.
@@ -199,9 +233,25 @@ pub mod of {
 /// Trait for any part of the formula.
 ///
 /// This trait is used to create the string-repr of the formula.
+/// Implementations must provide [Any::formula]. The types of this crate
+/// also implement [Any::write_formula], which doesn't allocate.
 pub trait Any {
-    /// Output to a formula.
+    /// Output to a formula. Appends to the buffer.
     fn formula(&self, buf: &mut String);
+
+    /// Output to any fmt::Write.
+    ///
+    /// The default implementation renders into a temporary String.
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        let mut tmp = String::new();
+        self.formula(&mut tmp);
+        buf.write_str(&tmp)
+    }
+
+    /// Type of the result, see [sheet]. The default is a number.
+    fn formula_type(&self) -> FormulaType {
+        FormulaType::Number
+    }
 }
 
 /// Numeric parameter.
@@ -404,27 +454,32 @@ impl<T: Reference> ReferenceOp<T> for T {
 // -----------------------------------------------------------------------
 
 macro_rules! any_struct {
-    (VAL $t:ident) => {
+    ($kind:ident VAL $t:ident) => {
 
         /// A newtype wrapper for a simple value.
         /// Useful in combination with overloaded operators.
         #[doc(hidden)]
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         pub struct $t<A:Any>(pub A);
 
         impl<A:Any> Any for $t<A> {
+            any_formula!();
              #[inline]
-             fn formula(&self, buf: &mut String) {
-                self.0.formula(buf);
+             fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                self.0.write_formula(buf)
+            }
+            #[inline]
+            fn formula_type(&self) -> FormulaType {
+                self.0.formula_type()
             }
         }
 
     };
-    (OP $t:ident) => {
+    ($kind:ident OP $t:ident) => {
 
         /// Operator definition.
         #[doc(hidden)]
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         pub struct $t<A:Any, B:Any>(
             pub A,
             pub &'static str,
@@ -432,63 +487,79 @@ macro_rules! any_struct {
         );
 
         impl<A:Any, B: Any> Any for $t<A,B> {
+            any_formula!();
+            #[inline]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                self.0.write_formula(buf)?;
+                buf.write_str(self.1.as_ref())?;
+                self.2.write_formula(buf)
+            }
             #[inline]
-            fn formula(&self, buf: &mut String) {
-                self.0.formula(buf);
-                buf.push_str(self.1.as_ref());
-                self.2.formula(buf);
+            fn formula_type(&self) -> FormulaType {
+                op_type(NodeKind::$kind, self.0.formula_type(), self.1, self.2.formula_type())
             }
         }
 
     };
-    (VAR $t:ident) => {
+    ($kind:ident VAR $t:ident) => {
 
         /// Function with variable number of parameters.
         #[doc(hidden)]
+        #[derive(Clone)]
         pub struct $t(
             pub &'static str,
-            pub Vec<Box<dyn Any>>
+            pub Vec<Arc<dyn Any + Send + Sync>>
         );
 
         impl Any for $t {
+            any_formula!();
             #[inline]
-            fn formula(&self, buf: &mut String) {
-                buf.push_str(self.0);
-                buf.push('(');
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                buf.write_str(self.0)?;
+                buf.write_char('(')?;
                 for (i, v) in self.1.iter().enumerate() {
                     if i > 0 {
-                        buf.push(';');
+                        buf.write_char(';')?;
                     }
-                    let _ = v.formula(buf);
+                    v.write_formula(buf)?;
                 }
-                buf.push(')');
+                buf.write_char(')')
+            }
+            fn formula_type(&self) -> FormulaType {
+                let args: Vec<_> = self.1.iter().map(|v| Some(v.formula_type())).collect();
+                node_type(NodeKind::$kind, self.0, &args)
             }
         }
 
     };
-    ($t:ident) => {
+    ($kind:ident $t:ident) => {
 
         /// Parameterless function.
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         #[doc(hidden)]
         pub struct $t(
             pub &'static str
         );
 
         impl Any for $t {
+            any_formula!();
+            #[inline]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                buf.write_str(self.0.as_ref())?;
+                buf.write_char('(')?;
+                buf.write_char(')')
+            }
             #[inline]
-            fn formula(&self, buf: &mut String) {
-                buf.push_str(self.0.as_ref());
-                buf.push('(');
-                buf.push(')');
+            fn formula_type(&self) -> FormulaType {
+                node_type(NodeKind::$kind, self.0, &[])
             }
         }
 
     };
-    ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
+    ($kind:ident $t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
 
         /// Function with parameters.
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         #[doc(hidden)]
         pub struct $t<$tname0: Any+'static $(,$tname: Any+'static)*>(
             pub &'static str,
@@ -497,16 +568,21 @@ macro_rules! any_struct {
         );
 
         impl <$tname0: Any+'static $(,$tname: Any+'static)*> Any for $t<$tname0 $(,$tname)*> {
+            any_formula!();
             #[inline]
-            fn formula(&self, buf: &mut String) {
-                buf.push_str(self.0.as_ref());
-                buf.push('(');
-                self.1.formula(buf);
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                buf.write_str(self.0.as_ref())?;
+                buf.write_char('(')?;
+                self.1.write_formula(buf)?;
                 $(
-                    buf.push(';');
-                    self.$tidx.formula(buf);
+                    buf.write_char(';')?;
+                    self.$tidx.write_formula(buf)?;
                 )*
-                buf.push(')');
+                buf.write_char(')')
+            }
+            fn formula_type(&self) -> FormulaType {
+                let args = [Some(self.1.formula_type()) $(, Some(self.$tidx.formula_type()))*];
+                node_type(NodeKind::$kind, self.0, &args)
             }
         }
 
@@ -515,23 +591,23 @@ macro_rules! any_struct {
 
 macro_rules! fn_any {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Any VAL $t);
         fn_any!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Any OP $t);
         fn_any!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Any VAR $t);
         fn_any!(__IMPL $t:);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Any $t);
         fn_any!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Any $t: $tname0 $($tname $tidx)*);
         fn_any!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
@@ -569,30 +645,31 @@ fn_any!(FnAny2: A B 2);
 fn_any!(FnAny3: A B 2 C 3);
 fn_any!(FnAny4: A B 2 C 3 D 4);
 fn_any!(FnAny5: A B 2 C 3 D 4 E 5);
+fn_any!(FnAny6: A B 2 C 3 D 4 E 5 F 6);
 
 macro_rules! fn_number {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Number VAL $t);
         fn_number!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Number OP $t);
         fn_number!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Number VAR $t);
         fn_number!(__IMPL $t:);
     };
     (REFVAR $t:ident) => {
-        any_struct!(REFVAR $t);
+        any_struct!(Number REFVAR $t);
         fn_number!(__IMPL $t: 'a);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Number $t);
         fn_number!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Number $t: $tname0 $($tname $tidx)*);
         fn_number!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
@@ -633,27 +710,27 @@ fn_number!(FnNumber9: A B 2 C 3 D 4 E 5 F 6 G 7 H 8 I 9);
 
 macro_rules! fn_text {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Text VAL $t);
         fn_text!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Text OP $t);
         fn_text!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Text VAR $t);
         fn_text!(__IMPL $t:);
     };
     (REFVAR $t:ident) => {
-        any_struct!(REFVAR $t);
+        any_struct!(Text REFVAR $t);
         fn_text!(__IMPL $t: 'a);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Text $t);
         fn_text!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Text $t: $tname0 $($tname $tidx)*);
         fn_text!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
@@ -688,27 +765,27 @@ fn_text!(FnText5: A B 2 C 3 D 4 E 5);
 
 macro_rules! fn_logical {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Logical VAL $t);
         fn_logical!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Logical OP $t);
         fn_logical!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Logical VAR $t);
         fn_logical!(__IMPL $t:);
     };
     (REFVAR $t:ident) => {
-        any_struct!(REFVAR $t);
+        any_struct!(Logical REFVAR $t);
         fn_logical!(__IMPL $t: 'a);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Logical $t);
         fn_logical!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Logical $t: $tname0 $($tname $tidx)*);
         fn_logical!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
@@ -738,27 +815,27 @@ fn_logical!(FnLogical2: A B 2);
 
 macro_rules! fn_matrix {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Matrix VAL $t);
         fn_matrix!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Matrix OP $t);
         fn_matrix!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Matrix VAR $t);
         fn_matrix!(__IMPL $t:);
     };
     (REFVAR $t:ident) => {
-        any_struct!(REFVAR $t);
+        any_struct!(Matrix REFVAR $t);
         fn_matrix!(__IMPL $t: 'a);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Matrix $t);
         fn_matrix!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Matrix $t: $tname0 $($tname $tidx)*);
         fn_matrix!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
@@ -775,30 +852,33 @@ macro_rules! fn_matrix {
 // fn_matrix!(FnMatrix0);
 fn_matrix!(FnMatrix1: A);
 fn_matrix!(FnMatrix2: A B 2);
+fn_matrix!(FnMatrix3: A B 2 C 3);
+fn_matrix!(FnMatrix4: A B 2 C 3 D 4);
+fn_matrix!(FnMatrix5: A B 2 C 3 D 4 E 5);
 
 macro_rules! fn_reference {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Reference VAL $t);
         fn_reference!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Reference OP $t);
         fn_reference!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Reference VAR $t);
         fn_reference!(__IMPL $t:);
     };
     (REFVAR $t:ident) => {
-        any_struct!(REFVAR $t);
+        any_struct!(Reference REFVAR $t);
         fn_reference!(__IMPL $t: 'a);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Reference $t);
         fn_reference!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Reference $t: $tname0 $($tname $tidx)*);
         fn_reference!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
@@ -851,36 +931,40 @@ fn_reference!(FnReference5: A B 2 C 3 D 4 E 5);
 
 macro_rules! fn_array {
     (VAL $t:ident) => {
-        any_struct!(VAL $t);
+        any_struct!(Array VAL $t);
         fn_array!(__IMPL $t: A);
     };
     (OP $t:ident) => {
-        any_struct!(OP $t);
+        any_struct!(Array OP $t);
         fn_array!(__IMPL $t: A B);
     };
     (VAR $t:ident) => {
-        any_struct!(VAR $t);
+        any_struct!(Array VAR $t);
         fn_array!(__IMPL $t:);
     };
     (REFVAR $t:ident) => {
-        any_struct!(REFVAR $t);
+        any_struct!(Array REFVAR $t);
         fn_array!(__IMPL $t: 'a);
     };
     ($t:ident) => {
-        any_struct!($t);
+        any_struct!(Array $t);
         fn_array!(__IMPL $t:);
     };
     ($t:ident : $tname0:tt $($tname:tt $tidx:tt)*) => {
-        any_struct!($t: $tname0 $($tname $tidx)*);
+        any_struct!(Array $t: $tname0 $($tname $tidx)*);
         fn_array!(__IMPL $t: $tname0 $($tname)*);
     };
     (__IMPL $t:ident : $($l:lifetime)?) => {
         impl $(<$l>)? Array for $t$(<$l>)?  {}
+        impl $(<$l>)? Matrix for $t$(<$l>)?  {}
+        impl $(<$l>)? Sequence for $t$(<$l>)?  {}
         impl $(<$l>)? ReferenceOrArray for $t$(<$l>)?  {}
         impl $(<$l>)? NumberOrArray for $t$(<$l>)?  {}
     };
     (__IMPL $t:ident : $($l:lifetime)? $tname0:tt $($tname:tt)*) => {
         impl <$($l, )?$tname0: Any $(,$tname: Any)*> Array for $t<$($l, )?$tname0 $(,$tname)*> {}
+        impl <$($l, )?$tname0: Any $(,$tname: Any)*> Matrix for $t<$($l, )?$tname0 $(,$tname)*> {}
+        impl <$($l, )?$tname0: Any $(,$tname: Any)*> Sequence for $t<$($l, )?$tname0 $(,$tname)*> {}
         impl <$($l, )?$tname0: Any $(,$tname: Any)*> ReferenceOrArray for $t<$($l, )?$tname0 $(,$tname)*> {}
         impl <$($l, )?$tname0: Any $(,$tname: Any)*> NumberOrArray for $t<$($l, )?$tname0 $(,$tname)*> {}
     };
@@ -888,12 +972,13 @@ macro_rules! fn_array {
 
 // fn_array!(VAL ValArray);
 // fn_array!(OP OpArray);
-// fn_array!(VAR FnArrayVar);
-// fn_array!(FnArray0);
+fn_array!(VAR FnArrayVar);
+fn_array!(FnArray0);
 fn_array!(FnArray1: A);
 fn_array!(FnArray2: A B 2);
 fn_array!(FnArray3: A B 2 C 3);
 fn_array!(FnArray4: A B 2 C 3 D 4);
+fn_array!(FnArray5: A B 2 C 3 D 4 E 5);
 
 // -----------------------------------------------------------------------
 
@@ -901,15 +986,20 @@ macro_rules! tup {
     ( $tname0:ident $($tname:tt $tnum:tt)* ) => {
 
         impl<$tname0: Any, $($tname: Any,)*> Any for ($tname0, $($tname,)*) {
+            any_formula!();
             #[inline]
-            fn formula(&self, buf: &mut String) {
-                buf.push('{');
-                self.0.formula(buf);
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                buf.write_char('{')?;
+                self.0.write_formula(buf)?;
                 $(
-                    buf.push(';');
-                    self.$tnum.formula(buf);
+                    buf.write_char(';')?;
+                    self.$tnum.write_formula(buf)?;
                 )*
-                buf.push('}');
+                buf.write_char('}')
+            }
+            #[inline]
+            fn formula_type(&self) -> FormulaType {
+                self.0.formula_type()
             }
         }
 
@@ -919,10 +1009,11 @@ macro_rules! tup {
 }
 
 impl Any for () {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push('{');
-        buf.push('}');
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_char('{')?;
+        buf.write_char('}')
     }
 }
 impl Sequence for () {}
@@ -952,7 +1043,7 @@ tup!(A B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9 K 10 L 11 M 12 N 13 O 14 P 15 Q 16 R
 // -----------------------------------------------------------------------
 
 /// Filter criteria.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CriterionCmp {
     Cmp,
     Eq,
@@ -965,7 +1056,7 @@ pub enum CriterionCmp {
 
 impl Display for CriterionCmp {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             CriterionCmp::Cmp => write!(f, ""),
             CriterionCmp::Eq => write!(f, "="),
@@ -979,7 +1070,7 @@ impl Display for CriterionCmp {
 }
 
 /// Filter/search
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FCriterion<A: Any>(CriterionCmp, A);
 impl<A: Any> FCriterion<A> {
     #[inline]
@@ -1019,21 +1110,23 @@ impl<A: Any> FCriterion<A> {
 }
 
 impl<A: Any> Any for FCriterion<A> {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(buf, "\"{}\"", self.0);
-        buf.push('&');
-        self.1.formula(buf);
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "\"{}\"", self.0)?;
+        buf.write_char('&')?;
+        self.1.write_formula(buf)
     }
 }
 impl<A: Any> Criterion for FCriterion<A> {}
 
 impl<A: Any> Any for (CriterionCmp, A) {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(buf, "\"{}\"", self.0);
-        buf.push('&');
-        self.1.formula(buf);
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "\"{}\"", self.0)?;
+        buf.write_char('&')?;
+        self.1.write_formula(buf)
     }
 }
 impl<A: Any> Criterion for (CriterionCmp, A) {}
@@ -1041,45 +1134,47 @@ impl<A: Any> Criterion for (CriterionCmp, A) {}
 // -----------------------------------------------------------------------
 
 /// Matrix value.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FMatrix<T: Any, const N: usize, const M: usize>(pub [[T; M]; N]);
 
 impl<T: Any, const N: usize, const M: usize> Any for FMatrix<T, N, M> {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push('{');
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_char('{')?;
         for (i, r) in self.0.iter().enumerate() {
             if i > 0 {
-                buf.push('|');
+                buf.write_char('|')?;
             }
             for (j, v) in r.iter().enumerate() {
                 if j > 0 {
-                    buf.push(';');
+                    buf.write_char(';')?;
                 }
-                v.formula(buf);
+                v.write_formula(buf)?;
             }
         }
-        buf.push('}');
+        buf.write_char('}')
     }
 }
 
 impl<T: Any, const N: usize, const M: usize> Matrix for FMatrix<T, N, M> {}
 
 /// Array.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FArray<T: Any, const N: usize>(pub [T; N]);
 
 impl<T: Any, const N: usize> Any for FArray<T, N> {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push('{');
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_char('{')?;
         for (i, v) in self.0.iter().enumerate() {
             if i > 0 {
-                buf.push(';');
+                buf.write_char(';')?;
             }
-            v.formula(buf);
+            v.write_formula(buf)?;
         }
-        buf.push('}');
+        buf.write_char('}')
     }
 }
 
@@ -1091,11 +1186,17 @@ impl<T: Any, const N: usize> NumberOrArray for FArray<T, N> {}
 // -----------------------------------------------------------------------
 
 impl<T: Any + Sized> Any for Option<T> {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
         if let Some(v) = self {
-            v.formula(buf);
+            v.write_formula(buf)?;
         }
+        Ok(())
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        self.as_ref().map(|v| v.formula_type()).unwrap_or_default()
     }
 }
 
@@ -1120,14 +1221,19 @@ impl<T: NumberOrArray + Any + Sized> NumberOrArray for Option<T> {}
 // -----------------------------------------------------------------------
 
 /// An expression in parentheses. Use p() to create one.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FParentheses<A>(A);
 impl<A: Any> Any for FParentheses<A> {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push('(');
-        self.0.formula(buf);
-        buf.push(')');
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_char('(')?;
+        self.0.write_formula(buf)?;
+        buf.write_char(')')
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        self.0.formula_type()
     }
 }
 impl<A: Number> Number for FParentheses<A> {}
@@ -1155,14 +1261,106 @@ pub fn p<A: Any>(a: A) -> FParentheses<A> {
 
 // -----------------------------------------------------------------------
 
+/// A subexpression that is used in several places. Use shared() to
+/// create one. Clones point to the same node and render identically.
+#[derive(Debug)]
+pub struct FShared<A>(Arc<A>);
+impl<A> Clone for FShared<A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        FShared(Arc::clone(&self.0))
+    }
+}
+impl<A: Any> Any for FShared<A> {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.0.write_formula(buf)
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        self.0.formula_type()
+    }
+}
+impl<A: Number> Number for FShared<A> {}
+impl<A: Text> Text for FShared<A> {}
+impl<A: Logical> Logical for FShared<A> {}
+impl<A: Reference> Reference for FShared<A> {}
+impl<A: Matrix> Matrix for FShared<A> {}
+impl<A: Array> Array for FShared<A> {}
+impl<A: Database> Database for FShared<A> {}
+impl<A: Criteria> Criteria for FShared<A> {}
+impl<A: Criterion> Criterion for FShared<A> {}
+impl<A: Sequence> Sequence for FShared<A> {}
+impl<A: Scalar> Scalar for FShared<A> {}
+impl<A: Field> Field for FShared<A> {}
+impl<A: DateTime> DateTime for FShared<A> {}
+impl<A: TextOrNumber> TextOrNumber for FShared<A> {}
+impl<A: ReferenceOrArray> ReferenceOrArray for FShared<A> {}
+impl<A: TextOrReference> TextOrReference for FShared<A> {}
+impl<A: NumberOrArray> NumberOrArray for FShared<A> {}
+
+/// Creates a shared subexpression.
+///
+/// ```
+/// use spreadsheet_ods::cell;
+/// use spreadsheet_ods_formula::{formula, of, shared};
+///
+/// let v = shared(of::vlookup(cell!(0, 0), cell!(0, 1, 9, 2), 2));
+/// let f = formula(of::if_then_else(of::isna(v.clone()), 0, v));
+/// assert_eq!(f, "of:=IF(ISNA(VLOOKUP([.A1];[.B1:.C10];2));0;VLOOKUP([.A1];[.B1:.C10];2))");
+/// ```
+#[inline]
+pub fn shared<A: Any>(a: A) -> FShared<A> {
+    FShared(Arc::new(a))
+}
+
+// -----------------------------------------------------------------------
+
 macro_rules! value_number {
     ($t:ty) => {
         impl Any for $t {
+            any_formula!();
+            #[inline]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                write!(buf, "{}", self)
+            }
+        }
+        value_number!(__TRAITS $t);
+    };
+    (INT $t:ty) => {
+        impl Any for $t {
+            any_formula!();
             #[inline]
-            fn formula(&self, buf: &mut String) {
-                let _ = write!(buf, "{}", self);
+            #[allow(trivial_numeric_casts)]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                fmt_int(*self, self.unsigned_abs() as u128, *self as f64, buf)
             }
         }
+        value_number!(__TRAITS $t);
+    };
+    (UINT $t:ty) => {
+        impl Any for $t {
+            any_formula!();
+            #[inline]
+            #[allow(trivial_numeric_casts)]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                fmt_int(*self, *self as u128, *self as f64, buf)
+            }
+        }
+        value_number!(__TRAITS $t);
+    };
+    (FLOAT $t:ty) => {
+        impl Any for $t {
+            any_formula!();
+            #[inline]
+            fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+                fmt_float(*self, f64::from(*self), buf)
+            }
+        }
+        value_number!(__TRAITS $t);
+    };
+    (__TRAITS $t:ty) => {
         impl Number for $t {}
         impl Logical for $t {}
         impl Sequence for $t {}
@@ -1174,20 +1372,233 @@ macro_rules! value_number {
     };
 }
 
-value_number!(i8);
-value_number!(i16);
-value_number!(i32);
-value_number!(i64);
-value_number!(i128);
-value_number!(isize);
-value_number!(u8);
-value_number!(u16);
-value_number!(u32);
-value_number!(u64);
-value_number!(u128);
-value_number!(usize);
-value_number!(f32);
-value_number!(f64);
+value_number!(INT i8);
+value_number!(INT i16);
+value_number!(INT i32);
+value_number!(INT i64);
+value_number!(INT i128);
+value_number!(INT isize);
+value_number!(UINT u8);
+value_number!(UINT u16);
+value_number!(UINT u32);
+value_number!(UINT u64);
+value_number!(UINT u128);
+value_number!(UINT usize);
+value_number!(FLOAT f32);
+value_number!(FLOAT f64);
+value_number!(Decimal);
+
+/// Writes an integer. Integers beyond 2^53 that a spreadsheet can't
+/// represent exactly are written as the double it would use instead.
+/// Use [FNumber::from_i128] to detect the precision loss.
+fn fmt_int<T: Display>(v: T, abs: u128, f: f64, buf: &mut dyn Write) -> fmt::Result {
+    if exact_f64(abs) {
+        write!(buf, "{}", v)
+    } else {
+        fmt_float(f, f, buf)
+    }
+}
+
+/// Writes a float as shortest round-trip representation. Very large and
+/// very small numbers use the exponent syntax 1.5e-7. NaN and the
+/// infinities can't be written as number and become #NUM!.
+pub(crate) fn fmt_float<T: Display + LowerExp>(v: T, f: f64, buf: &mut dyn Write) -> fmt::Result {
+    if !f.is_finite() {
+        FormulaError::Num.write_formula(buf)
+    } else if f != 0.0 && (f.abs() >= 1e16 || f.abs() < 1e-6) {
+        write!(buf, "{:e}", v)
+    } else {
+        write!(buf, "{}", v)
+    }
+}
+
+/// Error constants.
+///
+/// ```
+/// use spreadsheet_ods::cell;
+/// use spreadsheet_ods_formula::{formula, of, FormulaError};
+/// use spreadsheet_ods_formula::prelude::*;
+///
+/// let f = formula(of::iferror(cell!(0, 0), FormulaError::NA));
+/// assert_eq!(f, "of:=IFERROR([.A1];#N/A)");
+/// let f = formula(of::error_type(cell!(0, 0)).eq(FormulaError::Div0.type_code()));
+/// assert_eq!(f, "of:=ERROR.TYPE([.A1])=2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormulaError {
+    /// #NULL!
+    Null,
+    /// #DIV/0!
+    Div0,
+    /// #VALUE!
+    Value,
+    /// #REF!
+    Ref,
+    /// #NAME?
+    Name,
+    /// #NUM!
+    Num,
+    /// #N/A
+    NA,
+}
+
+impl FormulaError {
+    /// The code returned by ERROR.TYPE().
+    pub fn type_code(&self) -> u8 {
+        match self {
+            FormulaError::Null => 1,
+            FormulaError::Div0 => 2,
+            FormulaError::Value => 3,
+            FormulaError::Ref => 4,
+            FormulaError::Name => 5,
+            FormulaError::Num => 6,
+            FormulaError::NA => 7,
+        }
+    }
+
+    /// Error for a code returned by ERROR.TYPE().
+    pub fn from_type_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(FormulaError::Null),
+            2 => Some(FormulaError::Div0),
+            3 => Some(FormulaError::Value),
+            4 => Some(FormulaError::Ref),
+            5 => Some(FormulaError::Name),
+            6 => Some(FormulaError::Num),
+            7 => Some(FormulaError::NA),
+            _ => None,
+        }
+    }
+
+    /// Error constant.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FormulaError::Null => "#NULL!",
+            FormulaError::Div0 => "#DIV/0!",
+            FormulaError::Value => "#VALUE!",
+            FormulaError::Ref => "#REF!",
+            FormulaError::Name => "#NAME?",
+            FormulaError::Num => "#NUM!",
+            FormulaError::NA => "#N/A",
+        }
+    }
+}
+
+impl Display for FormulaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Any for FormulaError {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(self.as_str())
+    }
+}
+impl Number for FormulaError {}
+impl Text for FormulaError {}
+impl Logical for FormulaError {}
+impl Sequence for FormulaError {}
+impl Scalar for FormulaError {}
+impl DateTime for FormulaError {}
+impl TextOrNumber for FormulaError {}
+impl NumberOrArray for FormulaError {}
+
+/// What to do with NaN and the infinities in [FNumber].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberPolicy {
+    /// Write #NUM!. This is what the plain f32/f64 do.
+    #[default]
+    ErrorValue,
+    /// Fail with [NumberError::NotFinite].
+    Reject,
+}
+
+/// Errors for [FNumber].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberError {
+    /// NaN or an infinity.
+    NotFinite(f64),
+    /// The integer can't be represented exactly as spreadsheet number.
+    /// Contains the value the spreadsheet would use.
+    PrecisionLoss(f64),
+}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NumberError::NotFinite(v) => write!(f, "{} is not a valid number", v),
+            NumberError::PrecisionLoss(v) => {
+                write!(f, "integer loses precision, becomes {:e}", v)
+            }
+        }
+    }
+}
+
+impl Error for NumberError {}
+
+/// Checked number literal.
+///
+/// Spreadsheet numbers are doubles. Integers beyond 2^53 and non-finite
+/// floats can't be written without loss.
+///
+/// ```
+/// use spreadsheet_ods_formula::{formula, FNumber, NumberError, NumberPolicy};
+///
+/// assert_eq!(formula(FNumber::from_f64(1.5e-9, NumberPolicy::Reject).unwrap()), "of:=1.5e-9");
+/// assert_eq!(formula(FNumber::from_f64(f64::NAN, NumberPolicy::ErrorValue).unwrap()), "of:=#NUM!");
+/// assert!(FNumber::from_f64(f64::INFINITY, NumberPolicy::Reject).is_err());
+/// assert!(matches!(FNumber::from_i128((1 << 53) + 1), Err(NumberError::PrecisionLoss(_))));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FNumber(f64);
+
+impl FNumber {
+    /// Float with the given policy for NaN and the infinities.
+    pub fn from_f64(v: f64, policy: NumberPolicy) -> Result<Self, NumberError> {
+        if !v.is_finite() && policy == NumberPolicy::Reject {
+            return Err(NumberError::NotFinite(v));
+        }
+        Ok(Self(v))
+    }
+
+    /// Integer, fails if it can't be represented exactly.
+    pub fn from_i128(v: i128) -> Result<Self, NumberError> {
+        if !exact_f64(v.unsigned_abs()) {
+            return Err(NumberError::PrecisionLoss(v as f64));
+        }
+        Ok(Self(v as f64))
+    }
+
+    /// Integer, fails if it can't be represented exactly.
+    pub fn from_u128(v: u128) -> Result<Self, NumberError> {
+        if !exact_f64(v) {
+            return Err(NumberError::PrecisionLoss(v as f64));
+        }
+        Ok(Self(v as f64))
+    }
+
+    /// Value.
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+/// The integer fits in the 53 bit mantissa of a double.
+fn exact_f64(v: u128) -> bool {
+    v == 0 || (v >> v.trailing_zeros()) < (1 << 53)
+}
+
+impl Any for FNumber {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.0.write_formula(buf)
+    }
+}
+value_number!(__TRAITS FNumber);
 
 /// Creates a formula-number from a rust number literal.
 #[inline]
@@ -1196,9 +1607,14 @@ pub fn num<A: Number>(n: A) -> ValNumber<A> {
 }
 
 impl Any for bool {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(if *self { "TRUE()" } else { "FALSE()" })
+    }
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(if *self { "TRUE()" } else { "FALSE()" });
+    fn formula_type(&self) -> FormulaType {
+        FormulaType::Boolean
     }
 }
 impl Number for bool {}
@@ -1208,22 +1624,28 @@ impl Scalar for bool {}
 impl NumberOrArray for bool {}
 
 impl Any for &str {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
         if self.contains('"') {
-            buf.push('"');
+            buf.write_char('"')?;
             for (i, s) in self.split('"').enumerate() {
                 if i > 0 {
-                    buf.push_str("\"\"");
+                    buf.write_str("\"\"")?;
                 }
-                buf.push_str(s);
+                buf.write_str(s)?;
             }
-            buf.push('"');
+            buf.write_char('"')?;
         } else {
-            buf.push('"');
-            buf.push_str(self);
-            buf.push('"');
+            buf.write_char('"')?;
+            buf.write_str(self)?;
+            buf.write_char('"')?;
         }
+        Ok(())
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        FormulaType::Text
     }
 }
 impl Text for &str {}
@@ -1235,10 +1657,15 @@ impl TextOrNumber for &str {}
 impl TextOrReference for &str {}
 
 impl<'a> Any for Cow<'a, str> {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
         let s: &str = self.borrow();
-        s.formula(buf)
+        s.write_formula(buf)
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        FormulaType::Text
     }
 }
 impl<'a> Text for Cow<'a, str> {}
@@ -1250,9 +1677,14 @@ impl<'a> TextOrNumber for Cow<'a, str> {}
 impl<'a> TextOrReference for Cow<'a, str> {}
 
 impl Any for String {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        self.as_str().formula(buf)
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.as_str().write_formula(buf)
+    }
+    #[inline]
+    fn formula_type(&self) -> FormulaType {
+        FormulaType::Text
     }
 }
 impl Text for String {}
@@ -1263,10 +1695,81 @@ impl DateTime for String {}
 impl TextOrNumber for String {}
 impl TextOrReference for String {}
 
+/// Writes a cell value as literal.
+///
+/// * Empty is written as empty text "", the usual blank result of a formula.
+/// * Percentages are written with the % operator, 0.15 becomes 15%.
+///   NaN and the infinities become #NUM! like other numbers.
+/// * Currency values are written as plain number. The currency code can't
+///   be part of a formula and is lost, use a currency style for the cell.
+/// * DateTime and TimeDuration follow [date::DatePolicy::Function].
+impl Any for Value {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        match self {
+            Value::Empty => buf.write_str("\"\"")?,
+            Value::Boolean(v) => v.write_formula(buf)?,
+            Value::Number(v) => v.write_formula(buf)?,
+            Value::Percentage(v) if !v.is_finite() => fmt_float(v, *v, buf)?,
+            Value::Percentage(v) => {
+                buf.write_str(percent(*v).as_str())?;
+                buf.write_char('%')?;
+            }
+            Value::Currency(v, _) => v.write_formula(buf)?,
+            Value::Text(v) => v.write_formula(buf)?,
+            Value::TextXml(_) => self.as_cow_str_or("").write_formula(buf)?,
+            Value::DateTime(v) => v.date_fn(buf)?,
+            Value::TimeDuration(v) => v.date_fn(buf)?,
+        }
+        Ok(())
+    }
+    fn formula_type(&self) -> FormulaType {
+        match self {
+            Value::Empty | Value::Text(_) | Value::TextXml(_) => FormulaType::Text,
+            Value::Boolean(_) => FormulaType::Boolean,
+            Value::Number(_) | Value::Currency(..) => FormulaType::Number,
+            Value::Percentage(_) => FormulaType::Percentage,
+            Value::DateTime(v) => v.date_type(),
+            Value::TimeDuration(v) => v.date_type(),
+        }
+    }
+}
+impl Number for Value {}
+impl Text for Value {}
+impl Logical for Value {}
+impl Sequence for Value {}
+impl Scalar for Value {}
+impl Field for Value {}
+impl DateTime for Value {}
+impl TextOrNumber for Value {}
+
+/// Shifts the decimal point of the number by two places. Done on the
+/// string to avoid the rounding errors of v * 100. The number must be
+/// finite.
+fn percent(v: f64) -> String {
+    let s = v.to_string();
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => ("-", s),
+        None => ("", s.as_str()),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    let frac = format!("{:0<2}", frac);
+    let int = format!("{}{}", int, &frac[..2]);
+    let int = int.trim_start_matches('0');
+    let frac = frac[2..].trim_end_matches('0');
+    let int = if int.is_empty() { "0" } else { int };
+    if frac.is_empty() {
+        format!("{}{}", sign, int)
+    } else {
+        format!("{}{}.{}", sign, int, frac)
+    }
+}
+
 impl Any for CellRef {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(self.to_formula().as_str())
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "[{}]", self)
     }
 }
 impl Number for CellRef {}
@@ -1287,9 +1790,10 @@ impl TextOrReference for CellRef {}
 impl NumberOrArray for CellRef {}
 
 impl Any for CellRange {
+    any_formula!();
     #[inline]
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(self.to_formula().as_str())
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(buf, "[{}]", self)
     }
 }
 impl Number for CellRange {}
@@ -1309,6 +1813,18 @@ impl ReferenceOrArray for CellRange {}
 impl TextOrReference for CellRange {}
 impl NumberOrArray for CellRange {}
 
+impl Any for CellStyleRef {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        self.as_str().write_formula(buf)
+    }
+}
+impl Text for CellStyleRef {}
+impl Scalar for CellStyleRef {}
+impl TextOrNumber for CellStyleRef {}
+impl TextOrReference for CellStyleRef {}
+
 // -----------------------------------------------------------------------
 
 macro_rules! number_op {
@@ -1373,12 +1889,14 @@ macro_rules! number_op {
 // number_op!(ValAny<A>);
 // number_op!(OpAny<A, B>);
 number_op!(FnAnyVar);
+number_op!(FLet);
 number_op!(FnAny0);
 number_op!(FnAny1<A>);
 number_op!(FnAny2<A, B>);
 number_op!(FnAny3<A, B, C>);
 number_op!(FnAny4<A, B, C, D>);
 number_op!(FnAny5<A, B, C, D, E>);
+number_op!(FnAny6<A, B, C, D, E, F>);
 
 number_op!(ValNumber<A>);
 number_op!(OpNumber<A, B>);
@@ -1412,6 +1930,8 @@ number_op!(FnReference4<A, B, C, D>);
 number_op!(FnReference5<A, B, C, D, E>);
 
 number_op!(FParentheses<A>);
+number_op!(FShared<A>);
+number_op!(LetName);
 
 // -----------------------------------------------------------------------
 
@@ -1437,6 +1957,8 @@ text_op!(FnText2<A, B>);
 text_op!(FnText3<A, B, C>);
 text_op!(FnText4<A, B, C, D>);
 text_op!(FnText5<A, B, C, D, E>);
+text_op!(LetName);
+text_op!(FShared<A>);
 
 // -----------------------------------------------------------------------
 
@@ -1444,7 +1966,61 @@ text_op!(FnText5<A, B, C, D, E>);
 #[inline]
 pub fn formula<T: Any>(f: T) -> String {
     let mut buf = String::new();
-    buf.push_str("of:=");
-    f.formula(&mut buf);
+    formula_into(&f, &mut buf);
     buf
 }
+
+/// Appends the formula to the buffer. The buffer can be reused
+/// for many formulas.
+///
+/// ```
+/// use spreadsheet_ods::cell;
+/// use spreadsheet_ods_formula::{formula_into, of};
+///
+/// let mut buf = String::new();
+/// for row in 0..3 {
+///     buf.clear();
+///     formula_into(&of::sum(cell!(row, 0, row, 9)), &mut buf);
+/// }
+/// assert_eq!(buf, "of:=SUM([.A3:.J3])");
+/// ```
+#[inline]
+pub fn formula_into<T: Any + ?Sized>(f: &T, buf: &mut String) {
+    buf.push_str("of:=");
+    f.formula(buf);
+}
+
+/// Writes the formula to any fmt::Write without intermediate Strings.
+///
+/// ```
+/// use spreadsheet_ods::cell;
+/// use spreadsheet_ods_formula::{formula_to, num};
+///
+/// let mut out = String::new();
+/// formula_to(&(num(cell!(0, 0)) + 1), &mut out).expect("write");
+/// assert_eq!(out, "of:=[.A1]+1");
+/// ```
+#[inline]
+pub fn formula_to<T: Any + ?Sized, W: Write>(f: &T, w: &mut W) -> fmt::Result {
+    w.write_str("of:=")?;
+    f.write_formula(w)
+}
+
+/// Creates a formula in the given dialect.
+///
+/// The formula is parsed into an [expr::Expr] and written with the
+/// syntax and function names of the dialect.
+pub fn formula_with<T: Any>(f: T, dialect: Dialect) -> Result<String, DialectError> {
+    let expr = Expr::from_formula(&f)?;
+    let mut buf = String::new();
+    dialect::write(&expr, dialect, &mut buf)?;
+    Ok(buf)
+}
+
+/// Parses a formula in the given dialect.
+///
+/// Function names are mapped to OpenFormula. Functions without an
+/// equivalent are reported as an error.
+pub fn parse_with(f: &str, dialect: Dialect) -> Result<Expr, DialectError> {
+    dialect::read(f, dialect)
+}
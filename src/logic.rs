@@ -4,7 +4,16 @@
 
 pub use crate::generated::logic::*;
 
-use crate::{Any, FnAny2, FnAny3, Logical};
+use crate::sheet::FormulaType;
+use crate::{
+    Any, Array, DateTime, Field, FnAny2, FnAny3, Logical, Matrix, Number, NumberOrArray, Reference,
+    ReferenceOrArray, Scalar, Sequence, Text, TextOrNumber, TextOrReference,
+};
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Return one of two values, depending on a condition.
 #[inline]
@@ -27,3 +36,309 @@ pub fn if_then<A: Logical, B: Any>(condition: A, if_true: B) -> FnAny2<A, B> {
 pub fn if_else<A: Logical, B: Any>(condition: A, if_false: B) -> FnAny3<A, (), B> {
     FnAny3("IF", condition, (), if_false)
 }
+
+/// Errors of the [Let] builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LetError {
+    /// Not a valid name. Names must start with a letter or underscore,
+    /// and must not look like a cell reference or a logical constant.
+    InvalidName(String),
+    /// The name is already bound. Names are case-insensitive.
+    DuplicateName(String),
+    /// LET needs at least one binding.
+    NoBinding,
+    /// The name is bound by another LET. Use [Let::nested] to use the
+    /// names of a LET inside another one.
+    UnboundName(String),
+}
+
+impl Display for LetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LetError::InvalidName(n) => write!(f, "invalid LET name {:?}", n),
+            LetError::DuplicateName(n) => write!(f, "duplicate LET name {:?}", n),
+            LetError::NoBinding => write!(f, "LET without binding"),
+            LetError::UnboundName(n) => write!(f, "LET name {:?} is not bound here", n),
+        }
+    }
+}
+
+impl Error for LetError {}
+
+static NEXT_LET: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The LETs whose names can be used, while a binding or
+    /// calculation is checked.
+    static SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+struct Scope {
+    lets: Vec<u64>,
+    unbound: Option<String>,
+}
+
+/// Renders the value and reports the first name that is not bound
+/// by one of the LETs.
+fn check_scope(lets: Vec<u64>, value: &dyn Any) -> Result<(), LetError> {
+    let outer = SCOPE.with(|s| {
+        s.replace(Some(Scope {
+            lets,
+            unbound: None,
+        }))
+    });
+    let _ = value.write_formula(&mut String::new());
+    let scope = SCOPE.with(|s| s.replace(outer));
+    match scope.and_then(|v| v.unbound) {
+        Some(name) => Err(LetError::UnboundName(name)),
+        None => Ok(()),
+    }
+}
+
+/// A name bound with [Let::bind]. Can be used anywhere in the
+/// following bindings and the calculation of the same LET, and
+/// in LETs created with [Let::nested].
+#[derive(Debug, Clone)]
+pub struct LetName {
+    let_id: u64,
+    name: String,
+}
+
+impl LetName {
+    /// The bound name.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl Any for LetName {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        SCOPE.with(|s| {
+            if let Some(scope) = s.borrow_mut().as_mut()
+                && scope.unbound.is_none()
+                && !scope.lets.contains(&self.let_id)
+            {
+                scope.unbound = Some(self.name.clone());
+            }
+        });
+        buf.write_str(self.name.as_str())
+    }
+}
+impl Number for LetName {}
+impl Text for LetName {}
+impl Logical for LetName {}
+impl Reference for LetName {}
+impl Matrix for LetName {}
+impl Array for LetName {}
+impl Sequence for LetName {}
+impl Scalar for LetName {}
+impl DateTime for LetName {}
+impl TextOrNumber for LetName {}
+impl ReferenceOrArray for LetName {}
+impl TextOrReference for LetName {}
+impl NumberOrArray for LetName {}
+
+/// Builder for LET().
+///
+/// The names are only available via the [LetName] returned from
+/// [Let::bind], so the calculation can't refer to a name that was
+/// never bound. Using the name of another LET is an error, unless this
+/// LET was created with [Let::nested] and is part of the other one.
+///
+/// ```
+/// use spreadsheet_ods::cell;
+/// use spreadsheet_ods_formula::{formula, of};
+/// use spreadsheet_ods_formula::logic::Let;
+///
+/// let mut l = Let::new();
+/// let x = l.bind("x", cell!(0, 0)).unwrap();
+/// let y = l.bind("y", x.clone() * 2).unwrap();
+/// let f = formula(l.calc(x + y).unwrap());
+///
+/// assert_eq!(f, "of:=COM.MICROSOFT.LET(x;[.A1];y;x*2;x+y)");
+/// ```
+///
+/// [documentfoundation->LET](https://wiki.documentfoundation.org/Documentation/Calc_Functions/LET)
+#[derive(Clone)]
+pub struct Let {
+    /// This LET and the ones it is nested in.
+    lets: Vec<u64>,
+    names: Vec<String>,
+    param: Vec<Arc<dyn Any + Send + Sync>>,
+}
+
+impl Default for Let {
+    fn default() -> Self {
+        Self {
+            lets: vec![NEXT_LET.fetch_add(1, Ordering::Relaxed)],
+            names: Vec::new(),
+            param: Vec::new(),
+        }
+    }
+}
+
+impl Let {
+    /// New LET without bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// New LET that is used inside this one. Its bindings and
+    /// calculation can use the names of this LET.
+    ///
+    /// ```
+    /// use spreadsheet_ods::cell;
+    /// use spreadsheet_ods_formula::{formula, num};
+    /// use spreadsheet_ods_formula::logic::Let;
+    ///
+    /// let mut outer = Let::new();
+    /// let x = outer.bind("x", cell!(0, 0)).unwrap();
+    /// let mut inner = outer.nested();
+    /// let y = inner.bind("y", x.clone() * 2).unwrap();
+    /// let f = outer.calc(num(x) + inner.calc(y).unwrap()).unwrap();
+    ///
+    /// assert_eq!(formula(f), "of:=COM.MICROSOFT.LET(x;[.A1];x+COM.MICROSOFT.LET(y;x*2;y))");
+    /// ```
+    pub fn nested(&self) -> Self {
+        let mut v = Self::new();
+        v.lets.extend(self.lets.iter().copied());
+        v
+    }
+
+    /// Binds a value to a name.
+    pub fn bind<V: Any + Send + Sync + 'static>(
+        &mut self,
+        name: &str,
+        value: V,
+    ) -> Result<LetName, LetError> {
+        if !valid_let_name(name) {
+            return Err(LetError::InvalidName(name.into()));
+        }
+        if self.names.iter().any(|v| v.eq_ignore_ascii_case(name)) {
+            return Err(LetError::DuplicateName(name.into()));
+        }
+        check_scope(self.lets.clone(), &value)?;
+
+        let name = LetName {
+            let_id: self.lets[0],
+            name: name.into(),
+        };
+        self.names.push(name.name.clone());
+        self.param.push(Arc::new(name.clone()));
+        self.param.push(Arc::new(value));
+
+        Ok(name)
+    }
+
+    /// Finishes the LET with the calculation.
+    pub fn calc<C: Any + Send + Sync + 'static>(
+        mut self,
+        calculation: C,
+    ) -> Result<FLet, LetError> {
+        if self.names.is_empty() {
+            return Err(LetError::NoBinding);
+        }
+        check_scope(self.lets.clone(), &calculation)?;
+        self.param.push(Arc::new(calculation));
+        Ok(FLet {
+            let_id: self.lets[0],
+            param: self.param,
+        })
+    }
+}
+
+/// LET() as created by [Let::calc].
+#[derive(Clone)]
+pub struct FLet {
+    let_id: u64,
+    param: Vec<Arc<dyn Any + Send + Sync>>,
+}
+
+impl Any for FLet {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        // The names of this LET are bound inside.
+        let checked = SCOPE.with(|s| match s.borrow_mut().as_mut() {
+            Some(scope) => {
+                scope.lets.push(self.let_id);
+                true
+            }
+            None => false,
+        });
+        let res = (|| {
+            buf.write_str("COM.MICROSOFT.LET(")?;
+            for (i, v) in self.param.iter().enumerate() {
+                if i > 0 {
+                    buf.write_char(';')?;
+                }
+                v.write_formula(buf)?;
+            }
+            buf.write_char(')')
+        })();
+        if checked {
+            SCOPE.with(|s| {
+                if let Some(scope) = s.borrow_mut().as_mut() {
+                    scope.lets.pop();
+                }
+            });
+        }
+        res
+    }
+
+    /// The type of the calculation.
+    fn formula_type(&self) -> FormulaType {
+        self.param
+            .last()
+            .map(|v| v.formula_type())
+            .unwrap_or_default()
+    }
+}
+impl Number for FLet {}
+impl Text for FLet {}
+impl Logical for FLet {}
+impl Sequence for FLet {}
+impl Scalar for FLet {}
+impl Field for FLet {}
+impl DateTime for FLet {}
+impl TextOrNumber for FLet {}
+impl TextOrReference for FLet {}
+impl NumberOrArray for FLet {}
+
+fn valid_let_name(name: &str) -> bool {
+    let mut it = name.chars();
+    match it.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    if !it.all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+        return false;
+    }
+    if name.eq_ignore_ascii_case("TRUE") || name.eq_ignore_ascii_case("FALSE") {
+        return false;
+    }
+
+    // A1 style cell reference.
+    let alpha = name.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+    let digit = name[alpha..].chars().all(|c| c.is_ascii_digit());
+    if (1..=3).contains(&alpha) && alpha < name.len() && digit {
+        return false;
+    }
+    // R1C1 style cell reference.
+    let upper = name.to_ascii_uppercase();
+    let rc = match upper.strip_prefix('R') {
+        Some(rc) => rc.trim_start_matches(|c: char| c.is_ascii_digit()),
+        None => upper.as_str(),
+    };
+    if rc.len() < upper.len() && rc.is_empty() {
+        return false;
+    }
+    if let Some(c) = rc.strip_prefix('C')
+        && c.chars().all(|c| c.is_ascii_digit())
+    {
+        return false;
+    }
+
+    true
+}
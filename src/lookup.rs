@@ -4,11 +4,15 @@
 
 pub use crate::generated::lookup::*;
 
-use crate::{Any, FnAny2, FnAnyVar, Reference, Scalar, Text};
-use std::fmt::Write;
+use crate::{
+    Any, FnAny2, FnAny3, FnAny4, FnAny5, FnAny6, FnAnyVar, FnArray1, FnArray2, FnArray3, FnArray4,
+    FnArrayVar, FnNumber2, FnNumber3, FnNumber4, Number, Reference, ReferenceOrArray, Scalar, Text,
+};
+use std::fmt::{self, Write};
+use std::sync::Arc;
 
 /// Parameter for ADDRESS().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressAbs {
     RowAbsColAbs,
     RowAbsColRel,
@@ -17,8 +21,9 @@ pub enum AddressAbs {
 }
 
 impl Any for AddressAbs {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -27,12 +32,12 @@ impl Any for AddressAbs {
                 AddressAbs::RowRelColAbs => 3,
                 AddressAbs::RowRelColRel => 4,
             }
-        );
+        )
     }
 }
 
 /// Parameter for MATCH().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MatchType {
     MaxInDescendingList,
     ExactMatch,
@@ -40,8 +45,9 @@ pub enum MatchType {
 }
 
 impl Any for MatchType {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -49,7 +55,7 @@ impl Any for MatchType {
                 MatchType::ExactMatch => 0,
                 MatchType::MaxInAscendingList => 1,
             }
-        );
+        )
     }
 }
 
@@ -62,25 +68,455 @@ pub fn getpivotdata<A: Text, B: Reference>(datafield: A, table: B) -> FnAny2<A,
 /// Return a value from a data pilot table.
 #[inline]
 pub fn getpivotdata_fields<
-    A: Text + 'static,
-    B: Reference + 'static,
-    F: Text + 'static,
-    S: Scalar + 'static,
+    A: Text + Send + Sync + 'static,
+    B: Reference + Send + Sync + 'static,
+    F: Text + Send + Sync + 'static,
+    S: Scalar + Send + Sync + 'static,
     const N: usize,
 >(
     datafield: A,
     table: B,
     fields: [(F, S); N],
 ) -> FnAnyVar {
-    let mut param: Vec<Box<dyn Any>> = Vec::new();
+    let mut param: Vec<Arc<dyn Any + Send + Sync>> = Vec::new();
 
-    param.push(Box::new(datafield));
-    param.push(Box::new(table));
+    param.push(Arc::new(datafield));
+    param.push(Arc::new(table));
 
     for (n, sc) in fields {
-        param.push(Box::new(n));
-        param.push(Box::new(sc));
+        param.push(Arc::new(n));
+        param.push(Arc::new(sc));
     }
 
     FnAnyVar("GETPIVOTDATA", param)
 }
+
+/// Parameter for XLOOKUP() and XMATCH().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupMatchMode {
+    /// Exact match.
+    Exact,
+    /// Exact match or the next smaller item.
+    ExactOrNextSmaller,
+    /// Exact match or the next larger item.
+    ExactOrNextLarger,
+    /// Wildcard match.
+    Wildcard,
+    /// Regular expression match. LibreOffice only.
+    Regex,
+}
+
+impl Any for LookupMatchMode {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
+            buf,
+            "{}",
+            match self {
+                LookupMatchMode::Exact => 0,
+                LookupMatchMode::ExactOrNextSmaller => -1,
+                LookupMatchMode::ExactOrNextLarger => 1,
+                LookupMatchMode::Wildcard => 2,
+                LookupMatchMode::Regex => 3,
+            }
+        )
+    }
+}
+
+/// Parameter for XLOOKUP() and XMATCH().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupSearchMode {
+    /// Search from the first item.
+    FirstToLast,
+    /// Search from the last item.
+    LastToFirst,
+    /// Binary search in an ascending list.
+    BinaryAscending,
+    /// Binary search in a descending list.
+    BinaryDescending,
+}
+
+impl Any for LookupSearchMode {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
+            buf,
+            "{}",
+            match self {
+                LookupSearchMode::FirstToLast => 1,
+                LookupSearchMode::LastToFirst => -1,
+                LookupSearchMode::BinaryAscending => 2,
+                LookupSearchMode::BinaryDescending => -2,
+            }
+        )
+    }
+}
+
+/// Parameter for SORT() and SORTBY().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest value first.
+    Ascending,
+    /// Largest value first.
+    Descending,
+}
+
+impl Any for SortOrder {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            SortOrder::Ascending => "1",
+            SortOrder::Descending => "-1",
+        })
+    }
+}
+
+/// A by-array of SORTBY() with its sort order. The by-arrays of one
+/// SORTBY() can have different types.
+#[derive(Clone)]
+pub struct SortKey(Arc<dyn Any + Send + Sync>, SortOrder);
+
+impl SortKey {
+    /// Sort by the array in the given order.
+    pub fn new<B: ReferenceOrArray + Send + Sync + 'static>(by: B, order: SortOrder) -> Self {
+        Self(Arc::new(by), order)
+    }
+}
+
+impl<B: ReferenceOrArray + Send + Sync + 'static> From<(B, SortOrder)> for SortKey {
+    fn from(value: (B, SortOrder)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+/// Parameter for SORT() and UNIQUE().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayDirection {
+    /// Operate on rows.
+    ByRow,
+    /// Operate on columns.
+    ByColumn,
+}
+
+impl Any for ArrayDirection {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            ArrayDirection::ByRow => "FALSE()",
+            ArrayDirection::ByColumn => "TRUE()",
+        })
+    }
+}
+
+/// Parameter for UNIQUE().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniqueMode {
+    /// Every distinct value once.
+    Distinct,
+    /// Only values that occur exactly once.
+    ExactlyOnce,
+}
+
+impl Any for UniqueMode {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            UniqueMode::Distinct => "FALSE()",
+            UniqueMode::ExactlyOnce => "TRUE()",
+        })
+    }
+}
+
+/// Searches a range or array and returns the corresponding item of a
+/// second range or array.
+///
+/// [documentfoundation->XLOOKUP](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XLOOKUP)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.XLOOKUP( Lookup: Any; LookupArray: Reference|Array; ReturnArray: Reference|Array [; IfNotFound: Any [; MatchMode: Integer [; SearchMode: Integer ]]] )
+/// ```
+///
+/// __See also__: [crate::of::xlookup_()], [crate::of::xlookup__()], [crate::of::xlookup___()], [crate::of::xmatch()]
+#[inline]
+pub fn xlookup<A: Any, B: ReferenceOrArray, C: ReferenceOrArray>(
+    lookup: A,
+    lookup_array: B,
+    return_array: C,
+) -> FnAny3<A, B, C> {
+    FnAny3("COM.MICROSOFT.XLOOKUP", lookup, lookup_array, return_array)
+}
+
+/// Searches a range or array and returns the corresponding item of a
+/// second range or array. Returns if_not_found if there is no match.
+///
+/// [documentfoundation->XLOOKUP](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XLOOKUP)
+///
+/// __See also__: [crate::of::xlookup()], [crate::of::xlookup__()], [crate::of::xlookup___()]
+#[inline]
+pub fn xlookup_<A: Any, B: ReferenceOrArray, C: ReferenceOrArray, D: Any>(
+    lookup: A,
+    lookup_array: B,
+    return_array: C,
+    if_not_found: D,
+) -> FnAny4<A, B, C, D> {
+    FnAny4(
+        "COM.MICROSOFT.XLOOKUP",
+        lookup,
+        lookup_array,
+        return_array,
+        if_not_found,
+    )
+}
+
+/// Searches a range or array and returns the corresponding item of a
+/// second range or array.
+///
+/// [documentfoundation->XLOOKUP](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XLOOKUP)
+///
+/// __See also__: [crate::of::xlookup()], [crate::of::xlookup_()], [crate::of::xlookup___()]
+#[inline]
+pub fn xlookup__<A: Any, B: ReferenceOrArray, C: ReferenceOrArray, D: Any>(
+    lookup: A,
+    lookup_array: B,
+    return_array: C,
+    if_not_found: D,
+    match_mode: LookupMatchMode,
+) -> FnAny5<A, B, C, D, LookupMatchMode> {
+    FnAny5(
+        "COM.MICROSOFT.XLOOKUP",
+        lookup,
+        lookup_array,
+        return_array,
+        if_not_found,
+        match_mode,
+    )
+}
+
+/// Searches a range or array and returns the corresponding item of a
+/// second range or array.
+///
+/// [documentfoundation->XLOOKUP](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XLOOKUP)
+///
+/// __See also__: [crate::of::xlookup()], [crate::of::xlookup_()], [crate::of::xlookup__()]
+#[inline]
+pub fn xlookup___<A: Any, B: ReferenceOrArray, C: ReferenceOrArray, D: Any>(
+    lookup: A,
+    lookup_array: B,
+    return_array: C,
+    if_not_found: D,
+    match_mode: LookupMatchMode,
+    search_mode: LookupSearchMode,
+) -> FnAny6<A, B, C, D, LookupMatchMode, LookupSearchMode> {
+    FnAny6(
+        "COM.MICROSOFT.XLOOKUP",
+        lookup,
+        lookup_array,
+        return_array,
+        if_not_found,
+        match_mode,
+        search_mode,
+    )
+}
+
+/// Returns the relative position of an item in a range or array.
+///
+/// [documentfoundation->XMATCH](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XMATCH)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.XMATCH( Lookup: Any; LookupArray: Reference|Array [; MatchMode: Integer [; SearchMode: Integer ]] )
+/// ```
+///
+/// __See also__: [crate::of::xmatch_()], [crate::of::xmatch__()], [crate::of::xlookup()]
+#[inline]
+pub fn xmatch<A: Any, B: ReferenceOrArray>(lookup: A, lookup_array: B) -> FnNumber2<A, B> {
+    FnNumber2("COM.MICROSOFT.XMATCH", lookup, lookup_array)
+}
+
+/// Returns the relative position of an item in a range or array.
+///
+/// [documentfoundation->XMATCH](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XMATCH)
+///
+/// __See also__: [crate::of::xmatch()], [crate::of::xmatch__()]
+#[inline]
+pub fn xmatch_<A: Any, B: ReferenceOrArray>(
+    lookup: A,
+    lookup_array: B,
+    match_mode: LookupMatchMode,
+) -> FnNumber3<A, B, LookupMatchMode> {
+    FnNumber3("COM.MICROSOFT.XMATCH", lookup, lookup_array, match_mode)
+}
+
+/// Returns the relative position of an item in a range or array.
+///
+/// [documentfoundation->XMATCH](https://wiki.documentfoundation.org/Documentation/Calc_Functions/XMATCH)
+///
+/// __See also__: [crate::of::xmatch()], [crate::of::xmatch_()]
+#[inline]
+pub fn xmatch__<A: Any, B: ReferenceOrArray>(
+    lookup: A,
+    lookup_array: B,
+    match_mode: LookupMatchMode,
+    search_mode: LookupSearchMode,
+) -> FnNumber4<A, B, LookupMatchMode, LookupSearchMode> {
+    FnNumber4(
+        "COM.MICROSOFT.XMATCH",
+        lookup,
+        lookup_array,
+        match_mode,
+        search_mode,
+    )
+}
+
+/// Filters an array by a boolean array of the same height or width.
+///
+/// [documentfoundation->FILTER](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FILTER)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.FILTER( Array: Reference|Array; Include: Array [; IfEmpty: Any ] )
+/// ```
+///
+/// __See also__: [crate::of::filter_()], [crate::of::sort()], [crate::of::unique()]
+#[inline]
+pub fn filter<A: ReferenceOrArray, B: Any>(array: A, include: B) -> FnArray2<A, B> {
+    FnArray2("COM.MICROSOFT.FILTER", array, include)
+}
+
+/// Filters an array by a boolean array of the same height or width.
+/// Returns if_empty if nothing remains.
+///
+/// [documentfoundation->FILTER](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FILTER)
+///
+/// __See also__: [crate::of::filter()]
+#[inline]
+pub fn filter_<A: ReferenceOrArray, B: Any, C: Any>(
+    array: A,
+    include: B,
+    if_empty: C,
+) -> FnArray3<A, B, C> {
+    FnArray3("COM.MICROSOFT.FILTER", array, include, if_empty)
+}
+
+/// Sorts the contents of a range or array.
+///
+/// [documentfoundation->SORT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SORT)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.SORT( Array: Reference|Array [; SortIndex: Integer [; SortOrder: Integer [; ByCol: Logical ]]] )
+/// ```
+///
+/// __See also__: [crate::of::sort_()], [crate::of::sort__()], [crate::of::sort___()], [crate::of::sortby()]
+#[inline]
+pub fn sort<A: ReferenceOrArray>(array: A) -> FnArray1<A> {
+    FnArray1("COM.MICROSOFT.SORT", array)
+}
+
+/// Sorts the contents of a range or array by the given row or column.
+///
+/// [documentfoundation->SORT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SORT)
+///
+/// __See also__: [crate::of::sort()], [crate::of::sort__()], [crate::of::sort___()]
+#[inline]
+pub fn sort_<A: ReferenceOrArray, B: Number>(array: A, sort_index: B) -> FnArray2<A, B> {
+    FnArray2("COM.MICROSOFT.SORT", array, sort_index)
+}
+
+/// Sorts the contents of a range or array by the given row or column.
+///
+/// [documentfoundation->SORT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SORT)
+///
+/// __See also__: [crate::of::sort()], [crate::of::sort_()], [crate::of::sort___()]
+#[inline]
+pub fn sort__<A: ReferenceOrArray, B: Number>(
+    array: A,
+    sort_index: B,
+    sort_order: SortOrder,
+) -> FnArray3<A, B, SortOrder> {
+    FnArray3("COM.MICROSOFT.SORT", array, sort_index, sort_order)
+}
+
+/// Sorts the contents of a range or array by the given row or column.
+///
+/// [documentfoundation->SORT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SORT)
+///
+/// __See also__: [crate::of::sort()], [crate::of::sort_()], [crate::of::sort__()]
+#[inline]
+pub fn sort___<A: ReferenceOrArray, B: Number>(
+    array: A,
+    sort_index: B,
+    sort_order: SortOrder,
+    by_col: ArrayDirection,
+) -> FnArray4<A, B, SortOrder, ArrayDirection> {
+    FnArray4("COM.MICROSOFT.SORT", array, sort_index, sort_order, by_col)
+}
+
+/// Sorts the contents of a range or array by the values of other ranges or arrays.
+///
+/// [documentfoundation->SORTBY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SORTBY)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.SORTBY( Array: Reference|Array; ByArray1: Reference|Array [; SortOrder1: Integer ] { ; ByArray2: Reference|Array [; SortOrder2: Integer ] }... )
+/// ```
+///
+/// __See also__: [crate::of::sort()]
+#[inline]
+pub fn sortby<A: ReferenceOrArray + Send + Sync + 'static, const N: usize>(
+    array: A,
+    by: [SortKey; N],
+) -> FnArrayVar {
+    let mut param: Vec<Arc<dyn Any + Send + Sync>> = Vec::new();
+
+    param.push(Arc::new(array));
+    for SortKey(b, o) in by {
+        param.push(b);
+        param.push(Arc::new(o));
+    }
+
+    FnArrayVar("COM.MICROSOFT.SORTBY", param)
+}
+
+/// Returns the unique values of a range or array.
+///
+/// [documentfoundation->UNIQUE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/UNIQUE)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.UNIQUE( Array: Reference|Array [; ByCol: Logical [; ExactlyOnce: Logical ]] )
+/// ```
+///
+/// __See also__: [crate::of::unique_()], [crate::of::unique__()], [crate::of::filter()]
+#[inline]
+pub fn unique<A: ReferenceOrArray>(array: A) -> FnArray1<A> {
+    FnArray1("COM.MICROSOFT.UNIQUE", array)
+}
+
+/// Returns the unique rows or columns of a range or array.
+///
+/// [documentfoundation->UNIQUE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/UNIQUE)
+///
+/// __See also__: [crate::of::unique()], [crate::of::unique__()]
+#[inline]
+pub fn unique_<A: ReferenceOrArray>(
+    array: A,
+    by_col: ArrayDirection,
+) -> FnArray2<A, ArrayDirection> {
+    FnArray2("COM.MICROSOFT.UNIQUE", array, by_col)
+}
+
+/// Returns the unique rows or columns of a range or array.
+///
+/// [documentfoundation->UNIQUE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/UNIQUE)
+///
+/// __See also__: [crate::of::unique()], [crate::of::unique_()]
+#[inline]
+pub fn unique__<A: ReferenceOrArray>(
+    array: A,
+    by_col: ArrayDirection,
+    mode: UniqueMode,
+) -> FnArray3<A, ArrayDirection, UniqueMode> {
+    FnArray3("COM.MICROSOFT.UNIQUE", array, by_col, mode)
+}
@@ -4,11 +4,18 @@
 
 pub use crate::generated::math::*;
 
-use crate::{Any, Criterion, FnNumberVar, Reference};
-use std::fmt::Write;
+#[doc(inline)]
+pub use crate::round::RoundingMode;
 
-/// Parameter for CONVERT().
-#[derive(Debug)]
+use crate::{Any, Criterion, FnNumber0, FnNumber2, FnNumber3, FnNumberVar, Number, Reference};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::sync::Arc;
+
+/// Unit symbol for CONVERT().
+///
+/// Combine with a prefix and a power in a [ConvertUnit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BaseUnit {
     UKAcre,
     USAcre,
@@ -113,9 +120,10 @@ pub enum BaseUnit {
     CbYard,
 }
 
-impl Any for BaseUnit {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+impl BaseUnit {
+    /// Unit symbol as used by CONVERT().
+    pub fn symbol(&self) -> &'static str {
+        match self {
             BaseUnit::UKAcre => "uk_acre",
             BaseUnit::USAcre => "us_acre",
             BaseUnit::SqAngstrom => "ang2",
@@ -123,7 +131,7 @@ impl Any for BaseUnit {
             BaseUnit::SqFoot => "ft2",
             BaseUnit::Hectare => "ha",
             BaseUnit::SqInch => "in2",
-            BaseUnit::SqMeter => "ly2",
+            BaseUnit::SqMeter => "m2",
             BaseUnit::Morgen => "Morgen",
             BaseUnit::SqMile => "mi2",
             BaseUnit::SqNauticalMile => "Nmi2",
@@ -205,7 +213,7 @@ impl Any for BaseUnit {
             BaseUnit::CbMeter => "m3",
             BaseUnit::CbMile => "mi3",
             BaseUnit::MTon => "MTON",
-            BaseUnit::CbNauticalMile => "Mmi3",
+            BaseUnit::CbNauticalMile => "Nmi3",
             BaseUnit::FluidOunce => "oz",
             BaseUnit::CbPica => "Pica3",
             BaseUnit::Pint => "pt",
@@ -214,15 +222,141 @@ impl Any for BaseUnit {
             BaseUnit::Teaspoon => "tsp",
             BaseUnit::ModernTeaspoon => "tspm",
             BaseUnit::UKGallon => "uk_gal",
-            BaseUnit::UKPint => "gu_pt",
+            BaseUnit::UKPint => "uk_pt",
             BaseUnit::UKQuart => "uk_qt",
             BaseUnit::CbYard => "yd3",
-        });
+        }
+    }
+
+    /// Unit group. Only units of the same group can be converted.
+    pub fn dimension(&self) -> UnitDimension {
+        use BaseUnit::*;
+        match self {
+            UKAcre | USAcre | SqAngstrom | Ar | SqFoot | Hectare | SqInch | SqMeter | Morgen
+            | SqMile | SqNauticalMile | SqPica | SqYard => UnitDimension::Area,
+            Angstrom | Ell | Foot | Inch | LightYear | Meter | Mile | NauticalMile | Parsec
+            | Pica | SurveyMile | Yard => UnitDimension::Length,
+            BTU | ThermCalorie | ITCalorie | Erg | ElectronVolt | Flb | HPh | Joule | WattHour => {
+                UnitDimension::Energy
+            }
+            Dyne | Newton | Lbf | Pond => UnitDimension::Force,
+            Bit | Byte => UnitDimension::Information,
+            Gauss | Tesla => UnitDimension::Magnetism,
+            Gram | Grain | Cwt | UKCwt | Lbm | Stone | Ton | Ozm | Sg | AtomicMassUnit | UKTon => {
+                UnitDimension::Mass
+            }
+            HP | PS | Watt => UnitDimension::Power,
+            Atm | MmHg | Pascal | Psi | Torr => UnitDimension::Pressure,
+            AdmiralityKnot | Knot | MetersPerHour | MetersPerSecond | MilesPerHour => {
+                UnitDimension::Speed
+            }
+            Celsius | Fahrenheit | Kelvin | Rankine | Reaumur => UnitDimension::Temperature,
+            Day | Hour | Minute | Second | Year => UnitDimension::Time,
+            CbAngstrom | Barrel | Bushel | Cup | CbFoot | Gallon | GRT | CbInch | Liter
+            | CbLightYear | CbMeter | CbMile | MTon | CbNauticalMile | FluidOunce | CbPica
+            | Pint | Quart | Tablespoon | Teaspoon | ModernTeaspoon | UKGallon | UKPint
+            | UKQuart | CbYard => UnitDimension::Volume,
+        }
+    }
+
+    /// Units that accept a decimal prefix.
+    pub fn allows_decimal_prefix(&self) -> bool {
+        use BaseUnit::*;
+        matches!(
+            self,
+            SqAngstrom
+                | Ar
+                | SqMeter
+                | Angstrom
+                | LightYear
+                | Meter
+                | Parsec
+                | ThermCalorie
+                | ITCalorie
+                | Erg
+                | ElectronVolt
+                | Joule
+                | WattHour
+                | Dyne
+                | Newton
+                | Pond
+                | Bit
+                | Byte
+                | Gauss
+                | Tesla
+                | Gram
+                | AtomicMassUnit
+                | Watt
+                | Atm
+                | MmHg
+                | Pascal
+                | MetersPerHour
+                | MetersPerSecond
+                | Kelvin
+                | Second
+                | CbAngstrom
+                | Liter
+                | CbMeter
+        )
+    }
+
+    /// Units that accept a binary prefix.
+    pub fn allows_binary_prefix(&self) -> bool {
+        matches!(self, BaseUnit::Bit | BaseUnit::Byte)
+    }
+
+    /// Length units that can be squared and cubed.
+    pub fn allows_power(&self) -> bool {
+        use BaseUnit::*;
+        matches!(
+            self,
+            Angstrom | Foot | Inch | LightYear | Meter | Mile | NauticalMile | Pica | Yard
+        )
     }
 }
 
-/// Parameter for CONVERT().
-#[derive(Debug)]
+impl Any for BaseUnit {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_char('"')?;
+        buf.write_str(self.symbol())?;
+        buf.write_char('"')
+    }
+}
+
+/// Unit groups for CONVERT().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitDimension {
+    /// Area, e.g. m2 or ha.
+    Area,
+    /// Distance, e.g. m or ft.
+    Length,
+    /// Energy, e.g. J or Wh.
+    Energy,
+    /// Force, e.g. N or dyn.
+    Force,
+    /// Information, bit and byte.
+    Information,
+    /// Magnetic flux density, T and ga.
+    Magnetism,
+    /// Mass, e.g. g or lbm.
+    Mass,
+    /// Power, e.g. W or HP.
+    Power,
+    /// Pressure, e.g. Pa or atm.
+    Pressure,
+    /// Speed, e.g. m/s or mph.
+    Speed,
+    /// Temperature, e.g. C or K.
+    Temperature,
+    /// Duration, e.g. sec or yr.
+    Time,
+    /// Volume, e.g. l or gal.
+    Volume,
+}
+
+/// Decimal prefix for CONVERT().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DecimalPrefix {
     Yotta,
     Zetta,
@@ -243,11 +377,13 @@ pub enum DecimalPrefix {
     Femto,
     Atto,
     Zepto,
+    Yocto,
 }
 
 impl Any for DecimalPrefix {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             DecimalPrefix::Yotta => "Y",
             DecimalPrefix::Zetta => "Z",
             DecimalPrefix::Exa => "E",
@@ -267,12 +403,13 @@ impl Any for DecimalPrefix {
             DecimalPrefix::Femto => "f",
             DecimalPrefix::Atto => "a",
             DecimalPrefix::Zepto => "z",
-        });
+            DecimalPrefix::Yocto => "y",
+        })
     }
 }
 
-/// Parameter for CONVERT().
-#[derive(Debug)]
+/// Binary prefix for CONVERT(). Only for bit and byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinaryPrefix {
     Yobi,
     Zebi,
@@ -285,8 +422,9 @@ pub enum BinaryPrefix {
 }
 
 impl Any for BinaryPrefix {
-    fn formula(&self, buf: &mut String) {
-        buf.push_str(match self {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
             BinaryPrefix::Yobi => "Yi",
             BinaryPrefix::Zebi => "Zi",
             BinaryPrefix::Exbi => "Ei",
@@ -295,39 +433,336 @@ impl Any for BinaryPrefix {
             BinaryPrefix::Gibi => "Gi",
             BinaryPrefix::Mebi => "Mi",
             BinaryPrefix::Kibi => "Ki",
-        });
+        })
     }
 }
 
-/// Parameter for CONVERT().
-#[derive(Debug)]
-pub enum ConvertUnit {
-    Unit(BaseUnit),
-    SI(DecimalPrefix, BaseUnit),
-    Bin(BinaryPrefix, BaseUnit),
+/// Errors for CONVERT() units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertError {
+    /// The unit doesn't accept this kind of prefix.
+    PrefixNotAllowed(BaseUnit),
+    /// Only length units can be squared or cubed.
+    PowerNotAllowed(BaseUnit),
+    /// From and Into belong to different unit groups.
+    DimensionMismatch(UnitDimension, UnitDimension),
 }
 
-impl Any for ConvertUnit {
-    #[inline]
-    fn formula(&self, buf: &mut String) {
+impl Display for ConvertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            ConvertUnit::Unit(v) => {
-                v.formula(buf);
+            ConvertError::PrefixNotAllowed(u) => {
+                write!(f, "prefix not allowed for unit {:?}", u.symbol())
             }
-            ConvertUnit::SI(p, v) => {
-                p.formula(buf);
-                v.formula(buf);
+            ConvertError::PowerNotAllowed(u) => {
+                write!(f, "unit {:?} can't be squared or cubed", u.symbol())
             }
-            ConvertUnit::Bin(p, v) => {
-                p.formula(buf);
-                v.formula(buf);
+            ConvertError::DimensionMismatch(a, b) => {
+                write!(f, "can't convert {:?} into {:?}", a, b)
             }
         }
     }
 }
 
+impl Error for ConvertError {}
+
+/// Prefix of a [ConvertUnit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitPrefix {
+    Decimal(DecimalPrefix),
+    Binary(BinaryPrefix),
+}
+
+impl Any for UnitPrefix {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        match self {
+            UnitPrefix::Decimal(p) => p.write_formula(buf)?,
+            UnitPrefix::Binary(p) => p.write_formula(buf)?,
+        }
+        Ok(())
+    }
+}
+
+/// Parameter for CONVERT().
+///
+/// A unit symbol with an optional prefix. Length units can be
+/// squared or cubed.
+///
+/// ```
+/// use spreadsheet_ods_formula::{formula, of};
+/// use spreadsheet_ods_formula::math::{BaseUnit, ConvertUnit, DecimalPrefix};
+///
+/// let km2 = ConvertUnit::decimal(DecimalPrefix::Kilo, BaseUnit::Meter)
+///     .and_then(|v| v.squared())
+///     .expect("unit");
+/// let f = formula(of::convert(1, km2, BaseUnit::Hectare.into()).expect("convert"));
+/// assert_eq!(f, "of:=CONVERT(1;\"km2\";\"ha\")");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertUnit {
+    prefix: Option<UnitPrefix>,
+    unit: BaseUnit,
+    power: u8,
+}
+
+impl From<BaseUnit> for ConvertUnit {
+    fn from(unit: BaseUnit) -> Self {
+        ConvertUnit::new(unit)
+    }
+}
+
+impl ConvertUnit {
+    /// Unit without prefix.
+    pub fn new(unit: BaseUnit) -> Self {
+        Self {
+            prefix: None,
+            unit,
+            power: 1,
+        }
+    }
+
+    /// Unit with a decimal prefix.
+    pub fn decimal(prefix: DecimalPrefix, unit: BaseUnit) -> Result<Self, ConvertError> {
+        if !unit.allows_decimal_prefix() {
+            return Err(ConvertError::PrefixNotAllowed(unit));
+        }
+        Ok(Self {
+            prefix: Some(UnitPrefix::Decimal(prefix)),
+            unit,
+            power: 1,
+        })
+    }
+
+    /// Unit with a binary prefix.
+    pub fn binary(prefix: BinaryPrefix, unit: BaseUnit) -> Result<Self, ConvertError> {
+        if !unit.allows_binary_prefix() {
+            return Err(ConvertError::PrefixNotAllowed(unit));
+        }
+        Ok(Self {
+            prefix: Some(UnitPrefix::Binary(prefix)),
+            unit,
+            power: 1,
+        })
+    }
+
+    /// Square of a length unit.
+    pub fn squared(self) -> Result<Self, ConvertError> {
+        self.pow(2)
+    }
+
+    /// Cube of a length unit.
+    pub fn cubed(self) -> Result<Self, ConvertError> {
+        self.pow(3)
+    }
+
+    fn pow(self, power: u8) -> Result<Self, ConvertError> {
+        if self.power != 1 || !self.unit.allows_power() {
+            return Err(ConvertError::PowerNotAllowed(self.unit));
+        }
+        Ok(Self { power, ..self })
+    }
+
+    /// Prefix.
+    pub fn prefix(&self) -> Option<UnitPrefix> {
+        self.prefix
+    }
+
+    /// Unit symbol.
+    pub fn unit(&self) -> BaseUnit {
+        self.unit
+    }
+
+    /// Power 1, 2 or 3.
+    pub fn power(&self) -> u8 {
+        self.power
+    }
+
+    /// Unit group. Squared and cubed length units are area and volume.
+    pub fn dimension(&self) -> UnitDimension {
+        match self.power {
+            2 => UnitDimension::Area,
+            3 => UnitDimension::Volume,
+            _ => self.unit.dimension(),
+        }
+    }
+}
+
+impl Any for ConvertUnit {
+    any_formula!();
+    #[inline]
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_char('"')?;
+        if let Some(prefix) = &self.prefix {
+            prefix.write_formula(buf)?;
+        }
+        buf.write_str(self.unit.symbol())?;
+        if self.power != 1 {
+            write!(buf, "{}", self.power)?;
+        }
+        buf.write_char('"')
+    }
+}
+
+/// Returns a number converted from one unit system into another.
+///
+/// [documentfoundation->CONVERT](https://wiki.documentfoundation.org/Documentation/Calc_Functions/CONVERT)
+///
+/// __Syntax__:
+/// ```ods
+///     CONVERT( N: Number; From: Text; Into: Text )
+/// ```
+///
+/// __Constraints__:
+/// From and Into shall be legal units, and shall be in the same unit group.
+/// Fails with [ConvertError::DimensionMismatch] otherwise.
+///
+/// __Semantics__:
+/// Returns the number converted from the unit identified by From into the unit
+/// identified by Into. A unit is a unit symbol , optionally preceded by a unit
+/// prefix (either a decimal prefix or a binary prefix, as specified in Table
+/// 25 - Decimal Prefixes for use in CONVERT and Table 26 - Binary prefixes for
+/// use in CONVERT respectively). Units (including both the unit symbol and the
+/// optional unit prefix) are case-sensitive.
+///
+/// Evaluators claiming to implement this function shall support at least the
+/// following unit symbols (with conversions between them and other units in
+/// the same group):
+///
+/// If a conversion factor (as listed above) is not exact, an implementation
+/// may use a more accurate conversion factor instead.
+///
+/// Implementation-defined unit names should contain a 'FULL STOP' (U+002E)
+/// character.
+///
+/// Evaluators shall support decimal prefixes for unit symbols marked with *
+/// and binary prefixes for unit symbols marked with †. Evaluators should not
+/// support prefixes for other unit symbols.
+///
+/// The unit symbols in parentheses are deprecated unit symbols; evaluators
+/// shall support these unit symbols.
+///
+/// Evaluators should use internationally-standardized unit name abbreviations
+/// for such additions where possible. Evaluators may support the obsolete
+/// symbols “p” and “P” as unit names for Pascals.
+///
+/// For purposes of this function, a year is exactly 365.25 days long.
+///
+/// Evaluators claiming to support this function shall permit the unit decimal
+/// prefixes specified in Table 25 - Decimal Prefixes for use in CONVERT to be
+/// prepended to any unit symbol marked with * in Table 24 - Unit names. Adding
+/// a unit prefix indicates multiplication of the (scalar) unit by the given
+/// prefix value; for example km indicates kilometres, and km2 or km^2 indicate
+/// square kilometres.
+///
+/// __Note__:
+/// The prefix “e” for 10^1 is nonstandard and included for backward
+/// compatibility with legacy applications and documents.
+///
+/// The unit names marked with † in Table 24 - Unit names (see the
+/// Information Unit group) shall also support the following binary prefixes
+/// per IEC 60027-2:
+///
+/// In the case where there is a naming conflict (a unit name with a prefix is
+/// the same as an unprefixed name), the unprefixed name shall take precedence.
+///
+/// Evaluators may implement this conversion by first converting to some SI
+/// unit (e.g., meter and kilogram), and then convert again to the final unit.
+///
+/// __See also__: [crate::of::euroconvert()]
+#[inline]
+pub fn convert<A: Number>(
+    n: A,
+    from: ConvertUnit,
+    into: ConvertUnit,
+) -> Result<FnNumber3<A, ConvertUnit, ConvertUnit>, ConvertError> {
+    if from.dimension() != into.dimension() {
+        return Err(ConvertError::DimensionMismatch(
+            from.dimension(),
+            into.dimension(),
+        ));
+    }
+    Ok(FnNumber3("CONVERT", n, from, into))
+}
+
+/// Parameter for EUROCONVERT().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EuroCurrency {
+    /// Austrian schilling
+    ATS,
+    /// Belgian franc
+    BEF,
+    /// Cypriot pound
+    CYP,
+    /// German mark
+    DEM,
+    /// Estonian kroon
+    EEK,
+    /// Spanish peseta
+    ESP,
+    /// Euro
+    EUR,
+    /// Finnish markka
+    FIM,
+    /// French franc
+    FRF,
+    /// Greek drachma
+    GRD,
+    /// Croatian kuna
+    HRK,
+    /// Irish pound
+    IEP,
+    /// Italian lira
+    ITL,
+    /// Lithuanian litas
+    LTL,
+    /// Luxembourg franc
+    LUF,
+    /// Latvian lats
+    LVL,
+    /// Maltese lira
+    MTL,
+    /// Dutch guilder
+    NLG,
+    /// Portuguese escudo
+    PTE,
+    /// Slovenian tolar
+    SIT,
+    /// Slovak koruna
+    SKK,
+}
+
+impl Any for EuroCurrency {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            EuroCurrency::ATS => "\"ATS\"",
+            EuroCurrency::BEF => "\"BEF\"",
+            EuroCurrency::CYP => "\"CYP\"",
+            EuroCurrency::DEM => "\"DEM\"",
+            EuroCurrency::EEK => "\"EEK\"",
+            EuroCurrency::ESP => "\"ESP\"",
+            EuroCurrency::EUR => "\"EUR\"",
+            EuroCurrency::FIM => "\"FIM\"",
+            EuroCurrency::FRF => "\"FRF\"",
+            EuroCurrency::GRD => "\"GRD\"",
+            EuroCurrency::HRK => "\"HRK\"",
+            EuroCurrency::IEP => "\"IEP\"",
+            EuroCurrency::ITL => "\"ITL\"",
+            EuroCurrency::LTL => "\"LTL\"",
+            EuroCurrency::LUF => "\"LUF\"",
+            EuroCurrency::LVL => "\"LVL\"",
+            EuroCurrency::MTL => "\"MTL\"",
+            EuroCurrency::NLG => "\"NLG\"",
+            EuroCurrency::PTE => "\"PTE\"",
+            EuroCurrency::SIT => "\"SIT\"",
+            EuroCurrency::SKK => "\"SKK\"",
+        })
+    }
+}
+
 /// Parameter for SUBTOTAL().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SubtotalFunction {
     Average,
     Count,
@@ -354,8 +789,9 @@ pub enum SubtotalFunction {
 }
 
 impl Any for SubtotalFunction {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -382,12 +818,12 @@ impl Any for SubtotalFunction {
                 SubtotalFunction::VarExclCollapsed => 110,
                 SubtotalFunction::VarPExclCollapsed => 111,
             }
-        );
+        )
     }
 }
 
 /// Parameter for AGGREGATE().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AggregateFunction {
     Average,
     Count,
@@ -411,8 +847,9 @@ pub enum AggregateFunction {
 }
 
 impl Any for AggregateFunction {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -436,12 +873,12 @@ impl Any for AggregateFunction {
                 AggregateFunction::PercentileExc => 18,
                 AggregateFunction::QuartileExc => 19,
             }
-        );
+        )
     }
 }
 
 /// Parameter for AGGREGATE().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AggregateOption {
     /// Ignore only nested SUBTOTAL and AGGREGATE functions
     IgnoreNSA,
@@ -462,8 +899,9 @@ pub enum AggregateOption {
 }
 
 impl Any for AggregateOption {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
@@ -476,27 +914,71 @@ impl Any for AggregateOption {
                 AggregateOption::IgnoreE => 6,
                 AggregateOption::IgnoreHE => 7,
             }
-        );
+        )
     }
 }
 
 #[inline]
 pub fn sumifs<
-    A: Reference + 'static,
-    R: Reference + 'static,
-    C: Criterion + 'static,
+    A: Reference + Send + Sync + 'static,
+    R: Reference + Send + Sync + 'static,
+    C: Criterion + Send + Sync + 'static,
     const N: usize,
 >(
     range: A,
     list: [(R, C); N],
 ) -> FnNumberVar {
-    let mut param: Vec<Box<dyn Any>> = Vec::new();
+    let mut param: Vec<Arc<dyn Any + Send + Sync>> = Vec::new();
 
-    param.push(Box::new(range));
+    param.push(Arc::new(range));
     for (r, c) in list {
-        param.push(Box::new(r));
-        param.push(Box::new(c));
+        param.push(Arc::new(r));
+        param.push(Arc::new(c));
     }
 
     FnNumberVar("SUMIFS", param)
 }
+
+/// Return a random number between 0 (inclusive) and 1 (exclusive).
+/// The value is not recalculated on every change.
+///
+/// [documentfoundation->RAND.NV](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RAND.NV)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.LIBREOFFICE.RAND.NV( )
+/// ```
+///
+/// __Semantics__:
+/// Non-volatile variant of RAND(). The value is only recalculated when the
+/// cell is edited or a hard recalculation is done. This function is specific
+/// to LibreOffice.
+///
+/// __See also__: [crate::of::rand()], [crate::of::randbetween_nv()]
+#[inline]
+pub fn rand_nv() -> FnNumber0 {
+    FnNumber0("ORG.LIBREOFFICE.RAND.NV")
+}
+
+/// Return a random integer number between A and B.
+/// The value is not recalculated on every change.
+///
+/// [documentfoundation->RANDBETWEEN.NV](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDBETWEEN.NV)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.LIBREOFFICE.RANDBETWEEN.NV( A: Integer; B: Integer )
+/// ```
+///
+/// __Constraints__:
+/// A ≤ B
+///
+/// __Semantics__:
+/// Non-volatile variant of RANDBETWEEN(). This function is specific to
+/// LibreOffice.
+///
+/// __See also__: [crate::of::randbetween()], [crate::of::rand_nv()]
+#[inline]
+pub fn randbetween_nv<A: Number, B: Number>(a: A, b: B) -> FnNumber2<A, B> {
+    FnNumber2("ORG.LIBREOFFICE.RANDBETWEEN.NV", a, b)
+}
@@ -3,3 +3,297 @@
 //!
 
 pub use crate::generated::matrix::*;
+
+use crate::{
+    Any, FnArray0, FnArray1, FnArray2, FnArray3, FnArray4, FnArray5, FnMatrix2, FnMatrix3,
+    FnMatrix4, FnMatrix5, Matrix, Number,
+};
+use std::fmt::{self, Write};
+
+/// Parameter for FOURIER()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FourierGrouping {
+    /// The data is arranged in rows.
+    ByRows,
+    /// The data is arranged in columns.
+    ByColumns,
+}
+
+impl Any for FourierGrouping {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            FourierGrouping::ByRows => "FALSE()",
+            FourierGrouping::ByColumns => "TRUE()",
+        })
+    }
+}
+
+/// Parameter for FOURIER()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FourierDirection {
+    /// Forward transformation.
+    Forward,
+    /// Inverse transformation.
+    Inverse,
+}
+
+impl Any for FourierDirection {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            FourierDirection::Forward => "FALSE()",
+            FourierDirection::Inverse => "TRUE()",
+        })
+    }
+}
+
+/// Parameter for FOURIER()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FourierOutput {
+    /// Real and imaginary part.
+    Rectangular,
+    /// Magnitude and phase.
+    Polar,
+}
+
+impl Any for FourierOutput {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            FourierOutput::Rectangular => "FALSE()",
+            FourierOutput::Polar => "TRUE()",
+        })
+    }
+}
+
+/// Computes the discrete Fourier transform of an array.
+///
+/// [documentfoundation->FOURIER](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FOURIER)
+///
+/// __Syntax__:
+/// ```ods
+///     ORG.LIBREOFFICE.FOURIER( Array: Array; GroupedByColumns: Logical [; Inverse: Logical [; Polar: Logical [; MinimumMagnitude: Number ]]] )
+/// ```
+///
+/// __Semantics__:
+/// Returns a two column/row array with the real and imaginary parts of the
+/// transformation. This function is specific to LibreOffice.
+///
+/// __See also__: [crate::of::fourier_()], [crate::of::fourier__()], [crate::of::fourier___()]
+#[inline]
+pub fn fourier<A: Matrix>(array: A, grouping: FourierGrouping) -> FnMatrix2<A, FourierGrouping> {
+    FnMatrix2("ORG.LIBREOFFICE.FOURIER", array, grouping)
+}
+
+/// Computes the discrete Fourier transform of an array.
+///
+/// [documentfoundation->FOURIER](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FOURIER)
+///
+/// __See also__: [crate::of::fourier()], [crate::of::fourier__()], [crate::of::fourier___()]
+#[inline]
+pub fn fourier_<A: Matrix>(
+    array: A,
+    grouping: FourierGrouping,
+    direction: FourierDirection,
+) -> FnMatrix3<A, FourierGrouping, FourierDirection> {
+    FnMatrix3("ORG.LIBREOFFICE.FOURIER", array, grouping, direction)
+}
+
+/// Computes the discrete Fourier transform of an array.
+///
+/// [documentfoundation->FOURIER](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FOURIER)
+///
+/// __See also__: [crate::of::fourier()], [crate::of::fourier_()], [crate::of::fourier___()]
+#[inline]
+pub fn fourier__<A: Matrix>(
+    array: A,
+    grouping: FourierGrouping,
+    direction: FourierDirection,
+    output: FourierOutput,
+) -> FnMatrix4<A, FourierGrouping, FourierDirection, FourierOutput> {
+    FnMatrix4(
+        "ORG.LIBREOFFICE.FOURIER",
+        array,
+        grouping,
+        direction,
+        output,
+    )
+}
+
+/// Computes the discrete Fourier transform of an array.
+/// With polar output, magnitudes below the minimum magnitude are set to 0.
+///
+/// [documentfoundation->FOURIER](https://wiki.documentfoundation.org/Documentation/Calc_Functions/FOURIER)
+///
+/// __See also__: [crate::of::fourier()], [crate::of::fourier_()], [crate::of::fourier__()]
+#[inline]
+pub fn fourier___<A: Matrix, B: Number>(
+    array: A,
+    grouping: FourierGrouping,
+    direction: FourierDirection,
+    output: FourierOutput,
+    minimum_magnitude: B,
+) -> FnMatrix5<A, FourierGrouping, FourierDirection, FourierOutput, B> {
+    FnMatrix5(
+        "ORG.LIBREOFFICE.FOURIER",
+        array,
+        grouping,
+        direction,
+        output,
+        minimum_magnitude,
+    )
+}
+
+/// Parameter for RANDARRAY()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandArrayValues {
+    /// Decimal numbers.
+    Decimal,
+    /// Whole numbers.
+    Integer,
+}
+
+impl Any for RandArrayValues {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            RandArrayValues::Decimal => "FALSE()",
+            RandArrayValues::Integer => "TRUE()",
+        })
+    }
+}
+
+/// Generates an array of sequential numbers.
+///
+/// [documentfoundation->SEQUENCE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SEQUENCE)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.SEQUENCE( Rows: Integer [; Columns: Integer [; Start: Number [; Step: Number ]]] )
+/// ```
+///
+/// __See also__: [crate::of::sequence_()], [crate::of::sequence__()], [crate::of::sequence___()]
+#[inline]
+pub fn sequence<A: Number>(rows: A) -> FnArray1<A> {
+    FnArray1("COM.MICROSOFT.SEQUENCE", rows)
+}
+
+/// Generates an array of sequential numbers.
+///
+/// [documentfoundation->SEQUENCE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SEQUENCE)
+///
+/// __See also__: [crate::of::sequence()], [crate::of::sequence__()], [crate::of::sequence___()]
+#[inline]
+pub fn sequence_<A: Number, B: Number>(rows: A, columns: B) -> FnArray2<A, B> {
+    FnArray2("COM.MICROSOFT.SEQUENCE", rows, columns)
+}
+
+/// Generates an array of sequential numbers.
+///
+/// [documentfoundation->SEQUENCE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SEQUENCE)
+///
+/// __See also__: [crate::of::sequence()], [crate::of::sequence_()], [crate::of::sequence___()]
+#[inline]
+pub fn sequence__<A: Number, B: Number, C: Number>(
+    rows: A,
+    columns: B,
+    start: C,
+) -> FnArray3<A, B, C> {
+    FnArray3("COM.MICROSOFT.SEQUENCE", rows, columns, start)
+}
+
+/// Generates an array of sequential numbers.
+///
+/// [documentfoundation->SEQUENCE](https://wiki.documentfoundation.org/Documentation/Calc_Functions/SEQUENCE)
+///
+/// __See also__: [crate::of::sequence()], [crate::of::sequence_()], [crate::of::sequence__()]
+#[inline]
+pub fn sequence___<A: Number, B: Number, C: Number, D: Number>(
+    rows: A,
+    columns: B,
+    start: C,
+    step: D,
+) -> FnArray4<A, B, C, D> {
+    FnArray4("COM.MICROSOFT.SEQUENCE", rows, columns, start, step)
+}
+
+/// Generates an array of random numbers.
+///
+/// [documentfoundation->RANDARRAY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDARRAY)
+///
+/// __Syntax__:
+/// ```ods
+///     COM.MICROSOFT.RANDARRAY( [ Rows: Integer [; Columns: Integer [; Min: Number [; Max: Number [; WholeNumber: Logical ]]]]] )
+/// ```
+///
+/// __See also__: [crate::of::randarray_()], [crate::of::randarray_____()], [crate::of::rand()]
+#[inline]
+pub fn randarray() -> FnArray0 {
+    FnArray0("COM.MICROSOFT.RANDARRAY")
+}
+
+/// Generates an array of random numbers.
+///
+/// [documentfoundation->RANDARRAY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDARRAY)
+///
+/// __See also__: [crate::of::randarray()], [crate::of::randarray__()]
+#[inline]
+pub fn randarray_<A: Number>(rows: A) -> FnArray1<A> {
+    FnArray1("COM.MICROSOFT.RANDARRAY", rows)
+}
+
+/// Generates an array of random numbers.
+///
+/// [documentfoundation->RANDARRAY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDARRAY)
+///
+/// __See also__: [crate::of::randarray()], [crate::of::randarray___()]
+#[inline]
+pub fn randarray__<A: Number, B: Number>(rows: A, columns: B) -> FnArray2<A, B> {
+    FnArray2("COM.MICROSOFT.RANDARRAY", rows, columns)
+}
+
+/// Generates an array of random numbers.
+///
+/// [documentfoundation->RANDARRAY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDARRAY)
+///
+/// __See also__: [crate::of::randarray()], [crate::of::randarray____()]
+#[inline]
+pub fn randarray___<A: Number, B: Number, C: Number>(
+    rows: A,
+    columns: B,
+    min: C,
+) -> FnArray3<A, B, C> {
+    FnArray3("COM.MICROSOFT.RANDARRAY", rows, columns, min)
+}
+
+/// Generates an array of random numbers.
+///
+/// [documentfoundation->RANDARRAY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDARRAY)
+///
+/// __See also__: [crate::of::randarray()], [crate::of::randarray_____()]
+#[inline]
+pub fn randarray____<A: Number, B: Number, C: Number, D: Number>(
+    rows: A,
+    columns: B,
+    min: C,
+    max: D,
+) -> FnArray4<A, B, C, D> {
+    FnArray4("COM.MICROSOFT.RANDARRAY", rows, columns, min, max)
+}
+
+/// Generates an array of random numbers.
+///
+/// [documentfoundation->RANDARRAY](https://wiki.documentfoundation.org/Documentation/Calc_Functions/RANDARRAY)
+///
+/// __See also__: [crate::of::randarray()], [crate::of::randarray____()]
+#[inline]
+pub fn randarray_____<A: Number, B: Number, C: Number, D: Number>(
+    rows: A,
+    columns: B,
+    min: C,
+    max: D,
+    values: RandArrayValues,
+) -> FnArray5<A, B, C, D, RandArrayValues> {
+    FnArray5("COM.MICROSOFT.RANDARRAY", rows, columns, min, max, values)
+}
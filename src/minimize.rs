@@ -0,0 +1,363 @@
+//!
+//! Boolean minimization of AND, OR, NOT and XOR.
+//!
+//! Rules combined with [crate::LogicalOp] often end up with redundant
+//! conditions. [minimize_logic] finds the subtrees that only consist of
+//! AND, OR, NOT, XOR, TRUE() and FALSE() over atoms, and replaces them with
+//! the shortest equivalent AND/OR form.
+//!
+//! Atoms are comparisons and the IS functions like ISBLANK. Atoms that are
+//! written the same way are the same atom. A subtree with any other
+//! parameter, e.g. a plain reference, is not changed, because AND and OR
+//! treat numbers, text and empty cells in their own way. Atoms with
+//! volatile functions are never merged. Atoms with a range, an inline
+//! array or a name as operand are no atoms, as they can give an array of
+//! results.
+//!
+//! AND, OR, NOT and XOR return an error if any parameter is an error.
+//! With [AtomPolicy::Keep] all atoms stay in the result, so an error in
+//! any of them is still returned. [AtomPolicy::Drop] also removes atoms
+//! that don't change the logical result, e.g. `AND([.A1]>0;NOT([.A1]>0))`
+//! becomes `FALSE()`, even if [.A1] contains an error.
+//!
+//! ```
+//! use spreadsheet_ods_formula::expr::Expr;
+//! use spreadsheet_ods_formula::formula;
+//! use spreadsheet_ods_formula::minimize::{minimize_logic, AtomPolicy};
+//!
+//! let e = Expr::parse("of:=OR(AND([.A1]>0;[.B1]>0);AND([.A1]>0;[.C1]>0))").expect("expr");
+//! assert_eq!(
+//!     formula(minimize_logic(&e, AtomPolicy::Keep)),
+//!     "of:=AND([.A1]>0;OR([.B1]>0;[.C1]>0))"
+//! );
+//!
+//! let e = Expr::parse("of:=AND(OR([.A1]>0;[.B1]>0);OR([.A1]>0;[.C1]>0);[.A1]>0)").expect("expr");
+//! assert_eq!(formula(minimize_logic(&e, AtomPolicy::Keep)), formula(e.clone()));
+//! assert_eq!(formula(minimize_logic(&e, AtomPolicy::Drop)), "of:=[.A1]>0");
+//! ```
+//!
+
+use crate::Any;
+use crate::canon::Canonical;
+use crate::expr::{Expr, ExprRef, InfixOp};
+use crate::simplify::is_volatile;
+
+/// More atoms are not minimized.
+const MAX_ATOMS: usize = 8;
+
+/// Functions that always return a logical value.
+const IS_FUNCTIONS: &[&str] = &[
+    "ISBLANK",
+    "ISERR",
+    "ISERROR",
+    "ISEVEN",
+    "ISFORMULA",
+    "ISLOGICAL",
+    "ISNA",
+    "ISNONTEXT",
+    "ISNUMBER",
+    "ISODD",
+    "ISREF",
+    "ISTEXT",
+];
+
+/// What [minimize_logic] may do with the atoms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtomPolicy {
+    /// All atoms stay in the result. Errors are returned as before.
+    #[default]
+    Keep,
+    /// Atoms that don't change the logical result are removed.
+    /// An error in a removed atom is not returned anymore.
+    Drop,
+}
+
+/// Logic over atoms.
+enum Logic {
+    Const(bool),
+    Atom(usize),
+    Not(Box<Logic>),
+    And(Vec<Logic>),
+    Or(Vec<Logic>),
+    Xor(Vec<Logic>),
+}
+
+impl Logic {
+    fn eval(&self, bits: u32) -> bool {
+        match self {
+            Logic::Const(v) => *v,
+            Logic::Atom(i) => bits & (1 << i) != 0,
+            Logic::Not(v) => !v.eval(bits),
+            Logic::And(v) => v.iter().all(|v| v.eval(bits)),
+            Logic::Or(v) => v.iter().any(|v| v.eval(bits)),
+            Logic::Xor(v) => v.iter().filter(|v| v.eval(bits)).count() % 2 == 1,
+        }
+    }
+}
+
+/// Minimizes all AND/OR/NOT/XOR subtrees of the expression.
+pub fn minimize_logic(expr: &Expr, policy: AtomPolicy) -> Expr {
+    let min = |v: &Expr| minimize_logic(v, policy);
+    let e = match expr {
+        Expr::Array(rows) => {
+            Expr::Array(rows.iter().map(|r| r.iter().map(min).collect()).collect())
+        }
+        Expr::Func(name, args) => Expr::Func(name.clone(), args.iter().map(min).collect()),
+        Expr::Unary(op, v) => Expr::Unary(*op, Box::new(min(v))),
+        Expr::Infix(a, op, b) => Expr::infix(min(a), *op, min(b)),
+        Expr::Paren(v) => Expr::Paren(Box::new(min(v))),
+        _ => expr.clone(),
+    };
+    if let Expr::Func(name, _) = &e
+        && ["AND", "OR", "NOT", "XOR"]
+            .iter()
+            .any(|v| name.eq_ignore_ascii_case(v))
+    {
+        minimize(&e, policy).unwrap_or(e)
+    } else {
+        e
+    }
+}
+
+fn is_atom(e: &Expr) -> bool {
+    let atom = match e {
+        Expr::Infix(a, op, b) => op.is_comparison() && is_scalar(a) && is_scalar(b),
+        Expr::Func(name, args) => {
+            args.len() == 1
+                && IS_FUNCTIONS.iter().any(|v| name.eq_ignore_ascii_case(v))
+                && is_scalar(&args[0])
+        }
+        _ => false,
+    };
+    atom && !is_volatile(e)
+}
+
+/// The operand gives a single value. Ranges, inline arrays and names,
+/// which can be ranges, give an array of results. Functions are
+/// assumed to return a single value.
+fn is_scalar(e: &Expr) -> bool {
+    match e {
+        Expr::Ref(ExprRef::Cell(_)) => true,
+        Expr::Ref(_) | Expr::Array(_) | Expr::Name(_) => false,
+        Expr::Infix(_, InfixOp::Union | InfixOp::Intersect | InfixOp::Range, _) => false,
+        Expr::Infix(a, _, b) => is_scalar(a) && is_scalar(b),
+        Expr::Unary(_, v) | Expr::Paren(v) => is_scalar(v),
+        _ => true,
+    }
+}
+
+/// Logic of the expression, or None if it isn't only logic over atoms.
+fn logic(e: &Expr, atoms: &mut Vec<(String, Expr)>) -> Option<Logic> {
+    if let Some(v) = e.as_bool() {
+        return Some(Logic::Const(v));
+    }
+    match e {
+        Expr::Paren(v) => logic(v, atoms),
+        Expr::Func(name, args) if name.eq_ignore_ascii_case("NOT") && args.len() == 1 => {
+            Some(Logic::Not(Box::new(logic(&args[0], atoms)?)))
+        }
+        Expr::Func(name, args) if !args.is_empty() => {
+            let f = if name.eq_ignore_ascii_case("AND") {
+                Logic::And
+            } else if name.eq_ignore_ascii_case("OR") {
+                Logic::Or
+            } else if name.eq_ignore_ascii_case("XOR") {
+                Logic::Xor
+            } else if is_atom(e) {
+                return Some(atom(e, atoms));
+            } else {
+                return None;
+            };
+            let args: Option<Vec<Logic>> = args.iter().map(|v| logic(v, atoms)).collect();
+            Some(f(args?))
+        }
+        _ if is_atom(e) => Some(atom(e, atoms)),
+        _ => None,
+    }
+}
+
+fn atom(e: &Expr, atoms: &mut Vec<(String, Expr)>) -> Logic {
+    let key = Canonical::key(e, None);
+    match atoms.iter().position(|(k, _)| *k == key) {
+        Some(i) => Logic::Atom(i),
+        None => {
+            atoms.push((key, e.clone()));
+            Logic::Atom(atoms.len() - 1)
+        }
+    }
+}
+
+/// Shortest of the expression, its minimal sum of products and its
+/// minimal product of sums. With [AtomPolicy::Keep] only forms that
+/// use all atoms are considered.
+fn minimize(e: &Expr, policy: AtomPolicy) -> Option<Expr> {
+    let mut atoms = Vec::new();
+    let l = logic(e, &mut atoms)?;
+    if atoms.len() > MAX_ATOMS {
+        return None;
+    }
+    let n = atoms.len();
+    let (on, off): (Vec<u32>, Vec<u32>) = (0..1u32 << n).partition(|bits| l.eval(*bits));
+    let atoms: Vec<Expr> = atoms.into_iter().map(|(_, v)| v).collect();
+
+    let all = (1u32 << n) - 1;
+    let uses_all = |terms: &[Implicant]| terms.iter().fold(0, |u, p| u | !p.1) & all == all;
+    let sop = cover(&on, n);
+    let pos = cover(&off, n);
+    let mut candidates = Vec::new();
+    if policy == AtomPolicy::Drop || uses_all(&sop) {
+        candidates.push(sum_of_products(&sop, n, &atoms));
+    }
+    if policy == AtomPolicy::Drop || uses_all(&pos) {
+        candidates.push(product_of_sums(&pos, n, &atoms));
+    }
+    let len = |e: &Expr| {
+        let mut buf = String::new();
+        let _ = e.write_formula(&mut buf);
+        buf.len()
+    };
+    let mut best = e.clone();
+    for v in candidates {
+        if len(&v) < len(&best) {
+            best = v;
+        }
+    }
+    Some(best)
+}
+
+/// Implicant with the values of the atoms and a mask of the atoms that
+/// don't matter.
+type Implicant = (u32, u32);
+
+fn covers(p: Implicant, minterm: u32) -> bool {
+    minterm & !p.1 == p.0
+}
+
+/// Prime implicants after Quine-McCluskey.
+fn primes(minterms: &[u32]) -> Vec<Implicant> {
+    let mut current: Vec<Implicant> = minterms.iter().map(|m| (*m, 0)).collect();
+    let mut primes = Vec::new();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        let mut used = vec![false; current.len()];
+        for i in 0..current.len() {
+            for j in i + 1..current.len() {
+                let ((a, ma), (b, mb)) = (current[i], current[j]);
+                let d = a ^ b;
+                if ma == mb && d.count_ones() == 1 {
+                    used[i] = true;
+                    used[j] = true;
+                    let p = (a & !d, ma | d);
+                    if !next.contains(&p) {
+                        next.push(p);
+                    }
+                }
+            }
+        }
+        for (p, used) in current.iter().zip(used) {
+            if !used && !primes.contains(p) {
+                primes.push(*p);
+            }
+        }
+        current = next;
+    }
+    primes
+}
+
+/// Cheapest set of prime implicants that covers all minterms.
+fn cover(minterms: &[u32], n: usize) -> Vec<Implicant> {
+    let primes = primes(minterms);
+    let mut best = None;
+    search(&primes, minterms, n, &mut Vec::new(), &mut best);
+    let mut best = best.unwrap_or_default();
+    // Terms with the first atoms first.
+    best.sort_by_key(|p| (p.1.trailing_ones(), p.1, p.0));
+    best
+}
+
+/// Number of terms and literals.
+fn cost(set: &[Implicant], n: usize) -> usize {
+    set.iter().map(|p| 1 + n - p.1.count_ones() as usize).sum()
+}
+
+fn search(
+    primes: &[Implicant],
+    minterms: &[u32],
+    n: usize,
+    chosen: &mut Vec<Implicant>,
+    best: &mut Option<Vec<Implicant>>,
+) {
+    if let Some(best) = best
+        && cost(chosen, n) >= cost(best, n)
+    {
+        return;
+    }
+    // Branch on the minterm with the fewest candidates.
+    let open = minterms
+        .iter()
+        .filter(|m| !chosen.iter().any(|p| covers(*p, **m)))
+        .min_by_key(|m| primes.iter().filter(|p| covers(**p, **m)).count());
+    let Some(m) = open else {
+        *best = Some(chosen.clone());
+        return;
+    };
+    for p in primes.iter().filter(|p| covers(**p, *m)) {
+        chosen.push(*p);
+        search(primes, minterms, n, chosen, best);
+        chosen.pop();
+    }
+}
+
+fn literals(p: Implicant, n: usize, atoms: &[Expr], negate: bool) -> Vec<Expr> {
+    (0..n)
+        .filter(|i| p.1 & (1 << i) == 0)
+        .map(|i| {
+            if (p.0 & (1 << i) != 0) != negate {
+                atoms[i].clone()
+            } else {
+                Expr::func("NOT", vec![atoms[i].clone()])
+            }
+        })
+        .collect()
+}
+
+/// Single parameter or the function.
+fn join(name: &str, mut args: Vec<Expr>) -> Expr {
+    if args.len() == 1 {
+        args.remove(0)
+    } else {
+        Expr::func(name, args)
+    }
+}
+
+fn sum_of_products(terms: &[Implicant], n: usize, atoms: &[Expr]) -> Expr {
+    let mut products = Vec::new();
+    for p in terms {
+        let lit = literals(*p, n, atoms, false);
+        if lit.is_empty() {
+            return Expr::func("TRUE", Vec::new());
+        }
+        products.push(join("AND", lit));
+    }
+    if products.is_empty() {
+        Expr::func("FALSE", Vec::new())
+    } else {
+        join("OR", products)
+    }
+}
+
+fn product_of_sums(terms: &[Implicant], n: usize, atoms: &[Expr]) -> Expr {
+    let mut sums = Vec::new();
+    for p in terms {
+        let lit = literals(*p, n, atoms, true);
+        if lit.is_empty() {
+            return Expr::func("FALSE", Vec::new());
+        }
+        sums.push(join("OR", lit));
+    }
+    if sums.is_empty() {
+        Expr::func("TRUE", Vec::new())
+    } else {
+        join("AND", sums)
+    }
+}
@@ -5,24 +5,25 @@
 pub use crate::generated::round::*;
 
 use crate::Any;
-use std::fmt::Write;
+use std::fmt::{self, Write};
 
 /// Parameter for CEILING() and FLOOR().
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RoundingMode {
     AwayFrom0,
     TowardsPlusInf,
 }
 
 impl Any for RoundingMode {
-    fn formula(&self, buf: &mut String) {
-        let _ = write!(
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        write!(
             buf,
             "{}",
             match self {
                 RoundingMode::AwayFrom0 => 1,
                 RoundingMode::TowardsPlusInf => 0,
             }
-        );
+        )
     }
 }
@@ -0,0 +1,282 @@
+//!
+//! Formulas with a value type for spreadsheet_ods cells.
+//!
+//! A cell with a formula still has a value type. It tells a spreadsheet
+//! how to show the result before it is calculated, and which value-format
+//! fits. [FormulaSheet::set_typed_formula] writes the formula together
+//! with a placeholder value of the type [Any::formula_type] derives from it.
+//! [FormulaSheet::set_styled_formula] also sets the matching default
+//! style, as created by [spreadsheet_ods::defaultstyles::create_default_styles].
+//!
+//! The type is taken from the nodes of the formula:
+//!
+//! * Text and logical functions give a text or a logical value, as do
+//!   `&`, the comparisons and text or bool values.
+//! * Other functions have the return type of the OpenFormula
+//!   specification. DATE and TODAY give a date, NOW a date with time,
+//!   TIME a time, PV and PMT a currency and IRR a percentage.
+//! * A date plus or minus a number is a date, a date minus a date is a
+//!   number. A time plus or minus a time is a time.
+//! * IF, IFS, SWITCH, CHOOSE and LET have a type if all their values agree.
+//! * chrono values and [Value]s have their own type.
+//!
+//! Everything else, including references and functions that are not
+//! in the specification, is a number. For a parsed [Expr] only the
+//! function names are known, all functions use the specification.
+//!
+//! ```
+//! use spreadsheet_ods::{cell, Sheet, Value};
+//! use spreadsheet_ods_formula::of;
+//! use spreadsheet_ods_formula::sheet::{FormulaSheet, FormulaType};
+//!
+//! let mut sheet = Sheet::new("1");
+//! let t = sheet.set_typed_formula(0, 1, &(of::today() + cell!(0, 0)));
+//! assert_eq!(t, FormulaType::Date);
+//! assert_eq!(sheet.formula(0, 1).map(|v| v.as_str()), Some("of:=TODAY()+[.A1]"));
+//! assert!(matches!(sheet.value(0, 1), Value::DateTime(_)));
+//! ```
+//!
+
+use crate::expr::{Expr, InfixOp, UnaryOp};
+use crate::generated::returns::RETURNS;
+use crate::{Any, formula_into};
+use chrono::{Duration, NaiveDate};
+use spreadsheet_ods::defaultstyles::DefaultStyle;
+use spreadsheet_ods::{CellStyleRef, Sheet, Value, ValueType};
+
+/// Type of the result of a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormulaType {
+    /// Plain number.
+    #[default]
+    Number,
+    /// Text.
+    Text,
+    /// Logical value.
+    Boolean,
+    /// Date without time.
+    Date,
+    /// Date with time.
+    DateTime,
+    /// Time of day.
+    Time,
+    /// Amount of money.
+    Currency,
+    /// Percentage.
+    Percentage,
+}
+
+impl FormulaType {
+    /// The value type of the cell.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            FormulaType::Number => ValueType::Number,
+            FormulaType::Text => ValueType::Text,
+            FormulaType::Boolean => ValueType::Boolean,
+            FormulaType::Date | FormulaType::DateTime => ValueType::DateTime,
+            FormulaType::Time => ValueType::TimeDuration,
+            FormulaType::Currency => ValueType::Currency,
+            FormulaType::Percentage => ValueType::Percentage,
+        }
+    }
+
+    /// A placeholder value of this type. It is replaced when the
+    /// spreadsheet calculates the formula. The currency has no
+    /// currency code, set a value with one if it's known.
+    pub fn value(&self) -> Value {
+        match self {
+            FormulaType::Number => Value::Number(0.0),
+            FormulaType::Text => Value::Text(String::new()),
+            FormulaType::Boolean => Value::Boolean(false),
+            FormulaType::Date | FormulaType::DateTime => Value::DateTime(
+                NaiveDate::from_ymd_opt(1899, 12, 30)
+                    .and_then(|v| v.and_hms_opt(0, 0, 0))
+                    .expect("date"),
+            ),
+            FormulaType::Time => Value::TimeDuration(Duration::zero()),
+            FormulaType::Currency => Value::Currency(0.0, "".into()),
+            FormulaType::Percentage => Value::Percentage(0.0),
+        }
+    }
+
+    /// The matching default style. There is none for text.
+    pub fn style(&self) -> Option<CellStyleRef> {
+        match self {
+            FormulaType::Number => Some(DefaultStyle::number()),
+            FormulaType::Text => None,
+            FormulaType::Boolean => Some(DefaultStyle::bool()),
+            FormulaType::Date => Some(DefaultStyle::date()),
+            FormulaType::DateTime => Some(DefaultStyle::datetime()),
+            FormulaType::Time => Some(DefaultStyle::time_of_day()),
+            FormulaType::Currency => Some(DefaultStyle::currency()),
+            FormulaType::Percentage => Some(DefaultStyle::percent()),
+        }
+    }
+}
+
+/// Result of the node types of this crate, e.g. FnText2 gives a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeKind {
+    Any,
+    Number,
+    Text,
+    Logical,
+    Matrix,
+    Reference,
+    Array,
+}
+
+/// Type of a function node. `args` are the types of the parameters,
+/// None for an omitted parameter.
+pub(crate) fn node_type(kind: NodeKind, name: &str, args: &[Option<FormulaType>]) -> FormulaType {
+    match kind {
+        NodeKind::Any | NodeKind::Number => func_type(name, args),
+        NodeKind::Text => FormulaType::Text,
+        NodeKind::Logical => FormulaType::Boolean,
+        NodeKind::Matrix | NodeKind::Reference | NodeKind::Array => FormulaType::Number,
+    }
+}
+
+/// Type of an operator node. A unary operator has `()` as the other
+/// operand.
+pub(crate) fn op_type(kind: NodeKind, a: FormulaType, op: &str, b: FormulaType) -> FormulaType {
+    match (kind, op) {
+        (NodeKind::Text, _) => FormulaType::Text,
+        (NodeKind::Logical, _) => FormulaType::Boolean,
+        (NodeKind::Number, "%") => FormulaType::Percentage,
+        (NodeKind::Number, "+") => infix_type(a, InfixOp::Add, b),
+        (NodeKind::Number, "-") => infix_type(a, InfixOp::Sub, b),
+        (NodeKind::Number, "*") => infix_type(a, InfixOp::Mul, b),
+        (NodeKind::Number, "/") => infix_type(a, InfixOp::Div, b),
+        _ => FormulaType::Number,
+    }
+}
+
+/// Type of a parsed formula.
+pub(crate) fn expr_type(expr: &Expr) -> FormulaType {
+    match expr {
+        Expr::Text(_) => FormulaType::Text,
+        Expr::Array(rows) => rows
+            .first()
+            .and_then(|r| r.first())
+            .map(expr_type)
+            .unwrap_or_default(),
+        Expr::Func(name, args) => {
+            let args: Vec<_> = args
+                .iter()
+                .map(|v| (*v != Expr::Empty).then(|| expr_type(v)))
+                .collect();
+            func_type(name, &args)
+        }
+        Expr::Unary(UnaryOp::Percent, _) => FormulaType::Percentage,
+        Expr::Unary(_, v) | Expr::Paren(v) => expr_type(v),
+        Expr::Infix(a, op, b) => infix_type(expr_type(a), *op, expr_type(b)),
+        _ => FormulaType::Number,
+    }
+}
+
+fn is_date(t: FormulaType) -> bool {
+    matches!(
+        t,
+        FormulaType::Date | FormulaType::DateTime | FormulaType::Time
+    )
+}
+
+fn infix_type(a: FormulaType, op: InfixOp, b: FormulaType) -> FormulaType {
+    match op {
+        _ if op.is_comparison() => FormulaType::Boolean,
+        InfixOp::Concat => FormulaType::Text,
+        InfixOp::Add | InfixOp::Sub if a == FormulaType::Time && b == FormulaType::Time => {
+            FormulaType::Time
+        }
+        InfixOp::Add if is_date(a) && is_date(b) => FormulaType::DateTime,
+        InfixOp::Sub if is_date(a) && b == FormulaType::Time => FormulaType::DateTime,
+        InfixOp::Sub if is_date(a) && is_date(b) => FormulaType::Number,
+        InfixOp::Add | InfixOp::Sub if is_date(a) || a == FormulaType::Currency => a,
+        InfixOp::Add if is_date(b) || b == FormulaType::Currency => b,
+        InfixOp::Mul if a == FormulaType::Currency || b == FormulaType::Currency => {
+            FormulaType::Currency
+        }
+        InfixOp::Div if a == FormulaType::Currency && b != FormulaType::Currency => {
+            FormulaType::Currency
+        }
+        _ => FormulaType::Number,
+    }
+}
+
+/// Common type of the values, or Number if they differ.
+fn common<'a>(values: impl Iterator<Item = &'a Option<FormulaType>>) -> FormulaType {
+    let mut res = None;
+    for t in values.flatten() {
+        match res {
+            None => res = Some(*t),
+            Some(r) if r != *t => return FormulaType::Number,
+            Some(_) => {}
+        }
+    }
+    res.unwrap_or_default()
+}
+
+fn func_type(name: &str, args: &[Option<FormulaType>]) -> FormulaType {
+    let name = name.to_ascii_uppercase();
+    match name.as_str() {
+        "IF" | "CHOOSE" => common(args.iter().skip(1)),
+        "COM.MICROSOFT.IFS" => common(args.iter().skip(1).step_by(2)),
+        "COM.MICROSOFT.SWITCH" => {
+            // Pairs of case and value, with an optional default.
+            let values = args.iter().skip(2).step_by(2);
+            let default = args.len().is_multiple_of(2).then(|| args.last()).flatten();
+            common(values.chain(default))
+        }
+        "COM.MICROSOFT.LET" => args.last().copied().flatten().unwrap_or_default(),
+        name => spec_type(name),
+    }
+}
+
+/// Return type from the OpenFormula specification.
+fn spec_type(name: &str) -> FormulaType {
+    let Ok(idx) = RETURNS.binary_search_by(|(v, _)| (*v).cmp(name)) else {
+        return FormulaType::Number;
+    };
+    match RETURNS[idx].1 {
+        "Text" | "String" => FormulaType::Text,
+        "Logical" => FormulaType::Boolean,
+        "Date" => FormulaType::Date,
+        "DateTime" => FormulaType::DateTime,
+        "Time" => FormulaType::Time,
+        "Currency" => FormulaType::Currency,
+        "Percentage" => FormulaType::Percentage,
+        _ => FormulaType::Number,
+    }
+}
+
+/// Sets formulas with a value type on a [Sheet].
+pub trait FormulaSheet {
+    /// Sets the formula and a placeholder value of the derived type.
+    /// Returns the type.
+    fn set_typed_formula<T: Any + ?Sized>(&mut self, row: u32, col: u32, f: &T) -> FormulaType;
+
+    /// Sets the formula, a placeholder value and the matching default
+    /// style. The workbook needs the default styles for this.
+    /// Text gets no style. Returns the type.
+    fn set_styled_formula<T: Any + ?Sized>(&mut self, row: u32, col: u32, f: &T) -> FormulaType;
+}
+
+impl FormulaSheet for Sheet {
+    fn set_typed_formula<T: Any + ?Sized>(&mut self, row: u32, col: u32, f: &T) -> FormulaType {
+        let t = f.formula_type();
+        let mut buf = String::new();
+        formula_into(f, &mut buf);
+        self.set_formula(row, col, buf);
+        self.set_value(row, col, t.value());
+        t
+    }
+
+    fn set_styled_formula<T: Any + ?Sized>(&mut self, row: u32, col: u32, f: &T) -> FormulaType {
+        let t = self.set_typed_formula(row, col, f);
+        if let Some(style) = t.style() {
+            self.set_cellstyle(row, col, &style);
+        }
+        t
+    }
+}
@@ -0,0 +1,288 @@
+//!
+//! Constant folding and algebraic simplification.
+//!
+//! Formulas composed from generic building blocks often contain parts that
+//! don't change the result. [simplify] removes them:
+//!
+//! * arithmetic, comparisons and `&` with constant operands are folded,
+//! * NOT, AND, OR and XOR with constant parameters are folded,
+//! * `x+0`, `x-0`, `0+x`, `x*1`, `1*x`, `x/1`, `x^1`, `--x` and `NOT(NOT(x))`
+//!   are reduced to `x`, if x is known to be a number or a logical value,
+//! * adjacent text constants in `&` and CONCATENATE are joined,
+//! * IF with a constant condition is replaced by the branch,
+//! * parentheses and the unary plus are dropped.
+//!
+//! References are never assumed to be numbers. `[.A1]*1` turns an empty cell
+//! into 0 and text into a number, so it stays as it is. Branches of IF that
+//! contain a volatile function like RAND() or NOW() are kept.
+//! Numbers are compared and subtracted the way LibreOffice does it,
+//! values that are almost equal are treated as equal.
+//!
+//! ```
+//! use spreadsheet_ods_formula::expr::Expr;
+//! use spreadsheet_ods_formula::formula;
+//! use spreadsheet_ods_formula::simplify::simplify;
+//!
+//! let e = Expr::parse("of:=IF(1>2;0;(([.A1]*2)+0)*1)&CONCATENATE(\"a\";\"b\")").expect("expr");
+//! assert_eq!(formula(simplify(&e)), "of:=[.A1]*2&\"ab\"");
+//! ```
+//!
+
+use crate::expr::{Expr, InfixOp, UnaryOp};
+
+/// Functions that are recalculated on every change.
+const VOLATILE: &[&str] = &[
+    "CELL",
+    "COM.MICROSOFT.RANDARRAY",
+    "INDIRECT",
+    "INFO",
+    "NOW",
+    "OFFSET",
+    "RAND",
+    "RANDBETWEEN",
+    "TODAY",
+];
+
+/// Simplifies the expression. The result has the same value as the
+/// original for all cell contents.
+pub fn simplify(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Number(_)
+        | Expr::Text(_)
+        | Expr::Error(_)
+        | Expr::Ref(_)
+        | Expr::Name(_)
+        | Expr::Empty => expr.clone(),
+        Expr::Array(rows) => Expr::Array(
+            rows.iter()
+                .map(|r| r.iter().map(simplify).collect())
+                .collect(),
+        ),
+        Expr::Func(name, args) => func(name, args.iter().map(simplify).collect()),
+        Expr::Unary(op, v) => unary(*op, simplify(v)),
+        Expr::Infix(a, op, b) => infix(simplify(a), *op, simplify(b)),
+        Expr::Paren(v) => simplify(v),
+    }
+}
+
+/// Contains a volatile function.
+pub fn is_volatile(expr: &Expr) -> bool {
+    match expr {
+        Expr::Func(name, args) => {
+            VOLATILE.iter().any(|v| name.eq_ignore_ascii_case(v)) || args.iter().any(is_volatile)
+        }
+        Expr::Array(rows) => rows.iter().flatten().any(is_volatile),
+        Expr::Unary(_, v) | Expr::Paren(v) => is_volatile(v),
+        Expr::Infix(a, _, b) => is_volatile(a) || is_volatile(b),
+        _ => false,
+    }
+}
+
+/// The result is always a number, an array of numbers or an error.
+fn is_number(e: &Expr) -> bool {
+    match e {
+        Expr::Number(_) => true,
+        Expr::Unary(UnaryOp::Minus | UnaryOp::Percent, _) => true,
+        Expr::Infix(_, op, _) => matches!(
+            op,
+            InfixOp::Add | InfixOp::Sub | InfixOp::Mul | InfixOp::Div | InfixOp::Pow
+        ),
+        _ => false,
+    }
+}
+
+/// The result is always a logical value, an array of them or an error.
+fn is_logical(e: &Expr) -> bool {
+    match e {
+        Expr::Infix(_, op, _) => op.is_comparison(),
+        Expr::Func(name, _) => ["TRUE", "FALSE", "NOT", "AND", "OR", "XOR"]
+            .iter()
+            .any(|v| name.eq_ignore_ascii_case(v)),
+        _ => false,
+    }
+}
+
+/// The result is always a text.
+fn is_text(e: &Expr) -> bool {
+    matches!(e, Expr::Text(_) | Expr::Infix(_, InfixOp::Concat, _))
+}
+
+/// Numeric value of a constant. Logical values are numbers too.
+fn constant(e: &Expr) -> Option<f64> {
+    match e {
+        Expr::Number(v) => Some(*v),
+        _ => e.as_bool().map(|v| if v { 1.0 } else { 0.0 }),
+    }
+}
+
+fn boolean(v: bool) -> Expr {
+    Expr::func(if v { "TRUE" } else { "FALSE" }, Vec::new())
+}
+
+/// Same as rtl::math::approxEqual in LibreOffice.
+fn approx_eq(a: f64, b: f64) -> bool {
+    const E48: f64 = 1.0 / (16777216.0 * 16777216.0);
+    if a == b {
+        return true;
+    }
+    if a == 0.0 || b == 0.0 {
+        return false;
+    }
+    let d = (a - b).abs();
+    d.is_finite() && d < a.abs() * E48 && d < b.abs() * E48
+}
+
+/// Same as rtl::math::approxAdd in LibreOffice.
+fn approx_add(a: f64, b: f64) -> f64 {
+    if ((a < 0.0 && b > 0.0) || (b < 0.0 && a > 0.0)) && approx_eq(a, -b) {
+        0.0
+    } else {
+        a + b
+    }
+}
+
+fn fold(a: f64, op: InfixOp, b: f64) -> Option<Expr> {
+    let v = match op {
+        InfixOp::Add => approx_add(a, b),
+        InfixOp::Sub => approx_add(a, -b),
+        InfixOp::Mul => a * b,
+        InfixOp::Div if b != 0.0 => a / b,
+        InfixOp::Pow if a != 0.0 || b != 0.0 => a.powf(b),
+        InfixOp::Eq => return Some(boolean(approx_eq(a, b))),
+        InfixOp::Ne => return Some(boolean(!approx_eq(a, b))),
+        InfixOp::Lt => return Some(boolean(a < b && !approx_eq(a, b))),
+        InfixOp::Gt => return Some(boolean(a > b && !approx_eq(a, b))),
+        InfixOp::Le => return Some(boolean(a < b || approx_eq(a, b))),
+        InfixOp::Ge => return Some(boolean(a > b || approx_eq(a, b))),
+        _ => return None,
+    };
+    v.is_finite().then_some(Expr::Number(v))
+}
+
+fn unary(op: UnaryOp, v: Expr) -> Expr {
+    match (op, v) {
+        (UnaryOp::Plus, v) => v,
+        (UnaryOp::Minus, Expr::Number(v)) => Expr::Number(-v),
+        (UnaryOp::Minus, Expr::Unary(UnaryOp::Minus, v)) if is_number(&v) => *v,
+        (UnaryOp::Percent, Expr::Number(v)) => Expr::Number(v / 100.0),
+        (op, v) => Expr::Unary(op, Box::new(v)),
+    }
+}
+
+fn infix(a: Expr, op: InfixOp, b: Expr) -> Expr {
+    if op == InfixOp::Concat {
+        return concat(a, b);
+    }
+    if let (Some(x), Some(y)) = (constant(&a), constant(&b))
+        && let Some(v) = fold(x, op, y)
+    {
+        return v;
+    }
+    let ca = constant(&a).filter(|_| matches!(a, Expr::Number(_)));
+    let cb = constant(&b).filter(|_| matches!(b, Expr::Number(_)));
+    match op {
+        InfixOp::Add if cb == Some(0.0) && is_number(&a) => a,
+        InfixOp::Add if ca == Some(0.0) && is_number(&b) => b,
+        InfixOp::Sub if cb == Some(0.0) && is_number(&a) => a,
+        InfixOp::Mul if cb == Some(1.0) && is_number(&a) => a,
+        InfixOp::Mul if ca == Some(1.0) && is_number(&b) => b,
+        InfixOp::Div | InfixOp::Pow if cb == Some(1.0) && is_number(&a) => a,
+        _ => Expr::infix(a, op, b),
+    }
+}
+
+/// Parts of a `&` chain.
+fn concat_parts(e: Expr, parts: &mut Vec<Expr>) {
+    match e {
+        Expr::Infix(a, InfixOp::Concat, b) => {
+            concat_parts(*a, parts);
+            concat_parts(*b, parts);
+        }
+        e => parts.push(e),
+    }
+}
+
+/// Joins adjacent text constants.
+fn join_texts(parts: Vec<Expr>) -> Vec<Expr> {
+    let mut res: Vec<Expr> = Vec::with_capacity(parts.len());
+    for p in parts {
+        match (res.last_mut(), p) {
+            (Some(Expr::Text(a)), Expr::Text(b)) => a.push_str(&b),
+            (_, p) => res.push(p),
+        }
+    }
+    res
+}
+
+fn concat(a: Expr, b: Expr) -> Expr {
+    let mut parts = Vec::new();
+    concat_parts(a, &mut parts);
+    concat_parts(b, &mut parts);
+    let parts = join_texts(parts);
+    // Empty texts can go if the result is still converted to text.
+    let rest: Vec<Expr> = parts
+        .iter()
+        .filter(|v| !matches!(v, Expr::Text(t) if t.is_empty()))
+        .cloned()
+        .collect();
+    let parts = match rest.len() {
+        0 => return Expr::Text(String::new()),
+        1 if is_text(&rest[0]) => rest,
+        1 => parts,
+        _ => rest,
+    };
+    let mut it = parts.into_iter();
+    let first = it.next().expect("parts");
+    it.fold(first, |a, b| Expr::infix(a, InfixOp::Concat, b))
+}
+
+fn func(name: &str, args: Vec<Expr>) -> Expr {
+    let upper = name.to_ascii_uppercase();
+    match upper.as_str() {
+        "IF" => {
+            if let Some(v) = args.first().and_then(constant) {
+                let branch = if v != 0.0 { 1 } else { 2 };
+                let dropped = if v != 0.0 { 2 } else { 1 };
+                let keep = !args.get(dropped).is_some_and(is_volatile);
+                match args.get(branch) {
+                    Some(Expr::Empty) => {}
+                    Some(e) if keep => return e.clone(),
+                    None if keep && args.len() == 2 => return boolean(false),
+                    _ => {}
+                }
+            }
+        }
+        "NOT" if args.len() == 1 => {
+            if let Some(v) = constant(&args[0]) {
+                return boolean(v == 0.0);
+            }
+            if let Expr::Func(inner, inner_args) = &args[0]
+                && inner.eq_ignore_ascii_case("NOT")
+                && inner_args.len() == 1
+                && is_logical(&inner_args[0])
+            {
+                return inner_args[0].clone();
+            }
+        }
+        "AND" | "OR" | "XOR" if !args.is_empty() => {
+            let values: Option<Vec<bool>> =
+                args.iter().map(|v| constant(v).map(|v| v != 0.0)).collect();
+            if let Some(values) = values {
+                return boolean(match upper.as_str() {
+                    "AND" => values.iter().all(|v| *v),
+                    "OR" => values.iter().any(|v| *v),
+                    _ => values.iter().filter(|v| **v).count() % 2 == 1,
+                });
+            }
+        }
+        "CONCATENATE" if !args.contains(&Expr::Empty) => {
+            let args = join_texts(args);
+            if let [Expr::Text(_)] = args.as_slice() {
+                return args.into_iter().next().expect("text");
+            }
+            return Expr::Func(name.to_string(), args);
+        }
+        _ => {}
+    }
+    Expr::Func(name.to_string(), args)
+}
@@ -3,9 +3,71 @@
 //!
 
 use crate::{Any, Criterion, FnNumberVar, Reference};
+use std::fmt::{self, Write};
+use std::sync::Arc;
 
 pub use crate::generated::stat::*;
 
+/// Parameter for RANK()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankOrder {
+    /// The largest value has rank 1.
+    Descending,
+    /// The smallest value has rank 1.
+    Ascending,
+}
+
+impl Any for RankOrder {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            RankOrder::Descending => "0",
+            RankOrder::Ascending => "1",
+        })
+    }
+}
+
+/// Parameter for TTEST()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TTestTails {
+    /// One-tailed distribution.
+    OneTailed,
+    /// Two-tailed distribution.
+    TwoTailed,
+}
+
+impl Any for TTestTails {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            TTestTails::OneTailed => "1",
+            TTestTails::TwoTailed => "2",
+        })
+    }
+}
+
+/// Parameter for TTEST()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TTestType {
+    /// Paired test.
+    Paired,
+    /// Two samples with equal variance.
+    EqualVariance,
+    /// Two samples with unequal variance.
+    UnequalVariance,
+}
+
+impl Any for TTestType {
+    any_formula!();
+    fn write_formula(&self, buf: &mut dyn Write) -> fmt::Result {
+        buf.write_str(match self {
+            TTestType::Paired => "1",
+            TTestType::EqualVariance => "2",
+            TTestType::UnequalVariance => "3",
+        })
+    }
+}
+
 /// Summary: Average the values of cells that meet multiple criteria in multiple ranges.
 ///
 /// __Syntax__:
@@ -30,20 +92,20 @@ pub use crate::generated::stat::*;
 /// Infix Operator "<>" 6.4.8, Infix Operator Ordered Comparison ("<", "<=", ">", ">=") 6.4.9
 #[inline]
 pub fn averageifs<
-    A: Reference + 'static,
-    R: Reference + 'static,
-    C: Criterion + 'static,
+    A: Reference + Send + Sync + 'static,
+    R: Reference + Send + Sync + 'static,
+    C: Criterion + Send + Sync + 'static,
     const N: usize,
 >(
     range: A,
     list: [(R, C); N],
 ) -> FnNumberVar {
-    let mut param: Vec<Box<dyn Any>> = Vec::new();
+    let mut param: Vec<Arc<dyn Any + Send + Sync>> = Vec::new();
 
-    param.push(Box::new(range));
+    param.push(Arc::new(range));
     for (r, c) in list {
-        param.push(Box::new(r));
-        param.push(Box::new(c));
+        param.push(Arc::new(r));
+        param.push(Arc::new(c));
     }
 
     FnNumberVar("SUMIFS", param)
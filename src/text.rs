@@ -3,16 +3,19 @@
 //!
 
 use crate::{Any, FnTextVar, Text};
+use std::sync::Arc;
 
 pub use crate::generated::text::*;
 
 /// Count the number of cells that meet multiple criteria in multiple ranges.
 #[inline]
-pub fn concatenate_array<R: Text + 'static, const N: usize>(list: [R; N]) -> FnTextVar {
-    let mut param: Vec<Box<dyn Any>> = Vec::new();
+pub fn concatenate_array<R: Text + Send + Sync + 'static, const N: usize>(
+    list: [R; N],
+) -> FnTextVar {
+    let mut param: Vec<Arc<dyn Any + Send + Sync>> = Vec::new();
 
     for r in list {
-        param.push(Box::new(r));
+        param.push(Arc::new(r));
     }
 
     FnTextVar("CONCATENATE", param)
@@ -0,0 +1,509 @@
+//!
+//! Formulas as shown in the LibreOffice user interface.
+//!
+//! The UI form uses the localized function names, references without
+//! brackets and the separators of the locale. Function names without a
+//! translation are shown with their english name.
+//!
+//! ```
+//! use icu_locale_core::locale;
+//! use spreadsheet_ods::cell;
+//! use spreadsheet_ods_formula::{num, of};
+//! use spreadsheet_ods_formula::ui::formula_ui;
+//!
+//! let f = formula_ui(of::round_(of::sum(cell!(0, 0, 9, 0)), 2), &locale!("de-DE"));
+//! assert_eq!(f.expect("ui"), "=RUNDEN(SUMME(A1:A10);2)");
+//! let f = formula_ui(num(1.5) * cell!("Data" => 0, 0), &locale!("fr-FR"));
+//! assert_eq!(f.expect("ui"), "=1,5*$Data.$A$1");
+//! ```
+//!
+
+use crate::dialect::{a1_col, a1_row};
+use crate::expr::{Expr, ExprRef, ParseError, UnaryOp};
+use crate::{Any, fmt_float};
+use icu_locale_core::Locale;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Languages with translated function names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UiLanguage {
+    En,
+    De,
+    Fr,
+}
+
+/// Function names in german and french. Prefixed names like LEGACY.TDIST
+/// are listed without the prefix, unless the name without it is a
+/// different function.
+const NAMES: &[(&str, &str, &str)] = &[
+    ("ACOS", "ARCCOS", "ACOS"),
+    ("ACOSH", "ARCCOSHYP", "ACOSH"),
+    ("ACOT", "ARCCOT", "ACOT"),
+    ("ACOTH", "ARCCOTHYP", "ACOTH"),
+    ("ADDRESS", "ADRESSE", "ADRESSE"),
+    ("AND", "UND", "ET"),
+    ("AREAS", "BEREICHE", "ZONES"),
+    ("ASIN", "ARCSIN", "ASIN"),
+    ("ASINH", "ARCSINHYP", "ASINH"),
+    ("ATAN", "ARCTAN", "ATAN"),
+    ("ATAN2", "ARCTAN2", "ATAN2"),
+    ("ATANH", "ARCTANHYP", "ATANH"),
+    ("AVEDEV", "MITTELABW", "ECART.MOYEN"),
+    ("AVERAGE", "MITTELWERT", "MOYENNE"),
+    ("AVERAGEA", "MITTELWERTA", "MOYENNEA"),
+    ("AVERAGEIF", "MITTELWERTWENN", "MOYENNE.SI"),
+    ("AVERAGEIFS", "MITTELWERTWENNS", "MOYENNE.SI.ENS"),
+    ("BASE", "BASIS", "BASE"),
+    ("CEILING", "OBERGRENZE", "PLAFOND"),
+    ("CELL", "ZELLE", "CELLULE"),
+    ("CHAR", "ZEICHEN", "CAR"),
+    ("CHIDIST", "CHIVERT", "LOI.KHIDEUX"),
+    ("CHIINV", "CHIINV", "KHIDEUX.INVERSE"),
+    ("CHITEST", "CHITEST", "TEST.KHIDEUX"),
+    ("CHOOSE", "WAHL", "CHOISIR"),
+    ("CLEAN", "SÄUBERN", "EPURAGE"),
+    ("COLUMN", "SPALTE", "COLONNE"),
+    ("COLUMNS", "SPALTEN", "COLONNES"),
+    ("COM.MICROSOFT.CONCAT", "TEXTKETTE", "CONCAT"),
+    ("COM.MICROSOFT.FILTER", "FILTER", "FILTRE"),
+    ("COM.MICROSOFT.IFS", "WENNS", "SI.CONDITIONS"),
+    ("COM.MICROSOFT.MAXIFS", "MAXWENNS", "MAX.SI.ENS"),
+    ("COM.MICROSOFT.MINIFS", "MINWENNS", "MIN.SI.ENS"),
+    ("COM.MICROSOFT.RANDARRAY", "ZUFALLSMATRIX", "TABLEAU.ALEA"),
+    ("COM.MICROSOFT.SEQUENCE", "FOLGE", "SEQUENCE"),
+    ("COM.MICROSOFT.SORT", "SORTIEREN", "TRIER"),
+    ("COM.MICROSOFT.SORTBY", "SORTIERENNACH", "TRIERPAR"),
+    ("COM.MICROSOFT.SWITCH", "ERSTERWERT", "SI.MULTIPLE"),
+    ("COM.MICROSOFT.TEXTJOIN", "TEXTVERKETTEN", "JOINDRE.TEXTE"),
+    ("COM.MICROSOFT.UNIQUE", "EINDEUTIG", "UNIQUE"),
+    ("COM.MICROSOFT.XLOOKUP", "XVERWEIS", "RECHERCHEX"),
+    ("COM.MICROSOFT.XMATCH", "XVERGLEICH", "EQUIVX"),
+    ("COMBIN", "KOMBINATIONEN", "COMBIN"),
+    ("CONCATENATE", "VERKETTEN", "CONCATENER"),
+    ("CONVERT", "UMWANDELN", "CONVERT"),
+    ("CORREL", "KORREL", "COEFFICIENT.CORRELATION"),
+    ("COSH", "COSHYP", "COSH"),
+    ("COTH", "COTHYP", "COTH"),
+    ("COUNT", "ANZAHL", "NB"),
+    ("COUNTA", "ANZAHL2", "NBVAL"),
+    ("COUNTBLANK", "ANZAHLLEEREZELLEN", "NB.VIDE"),
+    ("COUNTIF", "ZÄHLENWENN", "NB.SI"),
+    ("COUNTIFS", "ZÄHLENWENNS", "NB.SI.ENS"),
+    ("COVAR", "KOVAR", "COVARIANCE"),
+    ("DATE", "DATUM", "DATE"),
+    ("DATEDIF", "DATUMDIF", "DATEDIF"),
+    ("DATEVALUE", "DATWERT", "DATEVAL"),
+    ("DAVERAGE", "DBMITTELWERT", "BDMOYENNE"),
+    ("DAY", "TAG", "JOUR"),
+    ("DAYS", "TAGE", "JOURS"),
+    ("DAYS360", "TAGE360", "JOURS360"),
+    ("DB", "GDA2", "DB"),
+    ("DCOUNT", "DBANZAHL", "BDNB"),
+    ("DCOUNTA", "DBANZAHL2", "BDNBVAL"),
+    ("DDB", "GDA", "DDB"),
+    ("DEGREES", "GRAD", "DEGRES"),
+    ("DGET", "DBAUSZUG", "BDLIRE"),
+    ("DMAX", "DBMAX", "BDMAX"),
+    ("DMIN", "DBMIN", "BDMIN"),
+    ("DPRODUCT", "DBPRODUKT", "BDPRODUIT"),
+    ("DSTDEV", "DBSTDABW", "BDECARTYPE"),
+    ("DSTDEVP", "DBSTDABWN", "BDECARTYPEP"),
+    ("DSUM", "DBSUMME", "BDSOMME"),
+    ("DVAR", "DBVARIANZ", "BDVAR"),
+    ("DVARP", "DBVARIANZEN", "BDVARP"),
+    ("EDATE", "EDATUM", "MOIS.DECALER"),
+    ("EFFECT", "EFFEKTIV", "TAUX.EFFECTIF"),
+    ("EOMONTH", "MONATSENDE", "FIN.MOIS"),
+    ("ERROR.TYPE", "FEHLER.TYP", "TYPE.ERREUR"),
+    ("EVEN", "GERADE", "PAIR"),
+    ("EXACT", "IDENTISCH", "EXACT"),
+    ("FACT", "FAKULTÄT", "FACT"),
+    ("FALSE", "FALSCH", "FAUX"),
+    ("FDIST", "FVERT", "LOI.F"),
+    ("FIND", "FINDEN", "TROUVE"),
+    ("FINV", "FINV", "INVERSE.LOI.F"),
+    ("FIXED", "FEST", "CTXT"),
+    ("FLOOR", "UNTERGRENZE", "PLANCHER"),
+    ("FORECAST", "SCHÄTZER", "PREVISION"),
+    ("FREQUENCY", "HÄUFIGKEIT", "FREQUENCE"),
+    ("FV", "ZW", "VC"),
+    ("GCD", "GGT", "PGCD"),
+    ("GEOMEAN", "GEOMITTEL", "MOYENNE.GEOMETRIQUE"),
+    ("HARMEAN", "HARMITTEL", "MOYENNE.HARMONIQUE"),
+    ("HLOOKUP", "WVERWEIS", "RECHERCHEH"),
+    ("HOUR", "STUNDE", "HEURE"),
+    ("HYPERLINK", "HYPERLINK", "LIEN_HYPERTEXTE"),
+    ("IF", "WENN", "SI"),
+    ("IFERROR", "WENNFEHLER", "SIERREUR"),
+    ("IFNA", "WENNNV", "SINA"),
+    ("INDIRECT", "INDIREKT", "INDIRECT"),
+    ("INT", "GANZZAHL", "ENT"),
+    ("INTERCEPT", "ACHSENABSCHNITT", "ORDONNEE.ORIGINE"),
+    ("IPMT", "ZINSZ", "INTPER"),
+    ("IRR", "IKV", "TRI"),
+    ("ISBLANK", "ISTLEER", "ESTVIDE"),
+    ("ISERR", "ISTFEHL", "ESTERR"),
+    ("ISERROR", "ISTFEHLER", "ESTERREUR"),
+    ("ISEVEN", "ISTGERADE", "EST.PAIR"),
+    ("ISFORMULA", "ISTFORMEL", "ESTFORMULE"),
+    ("ISLOGICAL", "ISTLOG", "ESTLOGIQUE"),
+    ("ISNA", "ISTNV", "ESTNA"),
+    ("ISNONTEXT", "ISTKTEXT", "ESTNONTEXTE"),
+    ("ISNUMBER", "ISTZAHL", "ESTNUM"),
+    ("ISODD", "ISTUNGERADE", "EST.IMPAIR"),
+    ("ISOWEEKNUM", "ISOKALENDERWOCHE", "NO.SEMAINE.ISO"),
+    ("ISREF", "ISTBEZUG", "ESTREF"),
+    ("ISTEXT", "ISTTEXT", "ESTTEXTE"),
+    ("LARGE", "KGRÖSSTE", "GRANDE.VALEUR"),
+    ("LCM", "KGV", "PPCM"),
+    ("LEFT", "LINKS", "GAUCHE"),
+    ("LEN", "LÄNGE", "NBCAR"),
+    ("LOOKUP", "VERWEIS", "RECHERCHE"),
+    ("LOWER", "KLEIN", "MINUSCULE"),
+    ("MATCH", "VERGLEICH", "EQUIV"),
+    ("MDETERM", "MDET", "DETERMAT"),
+    ("MEDIAN", "MEDIAN", "MEDIANE"),
+    ("MID", "TEIL", "STXT"),
+    ("MINVERSE", "MINV", "INVERSEMAT"),
+    ("MMULT", "MMULT", "PRODUITMAT"),
+    ("MOD", "REST", "MOD"),
+    ("MODE", "MODALWERT", "MODE"),
+    ("MONTH", "MONAT", "MOIS"),
+    ("MROUND", "VRUNDEN", "ARRONDI.AU.MULTIPLE"),
+    ("NA", "NV", "NA"),
+    ("NETWORKDAYS", "NETTOARBEITSTAGE", "NB.JOURS.OUVRES"),
+    ("NORMSDIST", "STANDNORMVERT", "LOI.NORMALE.STANDARD"),
+    ("NORMSINV", "STANDNORMINV", "LOI.NORMALE.STANDARD.INVERSE"),
+    ("NOT", "NICHT", "NON"),
+    ("NOW", "JETZT", "MAINTENANT"),
+    ("NPER", "ZZR", "NPM"),
+    ("NPV", "NBW", "VAN"),
+    ("NUMBERVALUE", "ZAHLWERT", "VALEURNOMBRE"),
+    ("ODD", "UNGERADE", "IMPAIR"),
+    ("OFFSET", "VERSCHIEBUNG", "DECALER"),
+    ("OR", "ODER", "OU"),
+    ("PERCENTILE", "QUANTIL", "CENTILE"),
+    ("PMT", "RMZ", "VPM"),
+    ("POWER", "POTENZ", "PUISSANCE"),
+    ("PPMT", "KAPZ", "PRINCPER"),
+    ("PRODUCT", "PRODUKT", "PRODUIT"),
+    ("PROPER", "GROSS2", "NOMPROPRE"),
+    ("PV", "BW", "VA"),
+    ("RADIANS", "BOGENMASS", "RADIANS"),
+    ("RAND", "ZUFALLSZAHL", "ALEA"),
+    ("RANDBETWEEN", "ZUFALLSBEREICH", "ALEA.ENTRE.BORNES"),
+    ("RANK", "RANG", "RANG"),
+    ("RATE", "ZINS", "TAUX"),
+    ("REPLACE", "ERSETZEN", "REMPLACER"),
+    ("REPT", "WIEDERHOLEN", "REPT"),
+    ("RIGHT", "RECHTS", "DROITE"),
+    ("ROMAN", "RÖMISCH", "ROMAIN"),
+    ("ROUND", "RUNDEN", "ARRONDI"),
+    ("ROUNDDOWN", "ABRUNDEN", "ARRONDI.INF"),
+    ("ROUNDUP", "AUFRUNDEN", "ARRONDI.SUP"),
+    ("ROW", "ZEILE", "LIGNE"),
+    ("ROWS", "ZEILEN", "LIGNES"),
+    ("RSQ", "BESTIMMTHEITSMASS", "COEFFICIENT.DETERMINATION"),
+    ("SEARCH", "SUCHEN", "CHERCHE"),
+    ("SECOND", "SEKUNDE", "SECONDE"),
+    ("SHEET", "TABELLE", "FEUILLE"),
+    ("SHEETS", "TABELLEN", "FEUILLES"),
+    ("SIGN", "VORZEICHEN", "SIGNE"),
+    ("SINH", "SINHYP", "SINH"),
+    ("SLN", "LIA", "AMORLIN"),
+    ("SLOPE", "STEIGUNG", "PENTE"),
+    ("SMALL", "KKLEINSTE", "PETITE.VALEUR"),
+    ("SQRT", "WURZEL", "RACINE"),
+    ("STDEV", "STABW", "ECARTYPE"),
+    ("STDEVP", "STABWN", "ECARTYPEP"),
+    ("SUBSTITUTE", "WECHSELN", "SUBSTITUE"),
+    ("SUBTOTAL", "TEILERGEBNIS", "SOUS.TOTAL"),
+    ("SUM", "SUMME", "SOMME"),
+    ("SUMIF", "SUMMEWENN", "SOMME.SI"),
+    ("SUMIFS", "SUMMEWENNS", "SOMME.SI.ENS"),
+    ("SUMPRODUCT", "SUMMENPRODUKT", "SOMMEPROD"),
+    ("SUMSQ", "QUADRATESUMME", "SOMME.CARRES"),
+    ("SYD", "DIA", "SYD"),
+    ("TANH", "TANHYP", "TANH"),
+    ("TDIST", "TVERT", "LOI.STUDENT"),
+    ("TEXT", "TEXT", "TEXTE"),
+    ("TIME", "ZEIT", "TEMPS"),
+    ("TIMEVALUE", "ZEITWERT", "TEMPSVAL"),
+    ("TODAY", "HEUTE", "AUJOURDHUI"),
+    ("TRANSPOSE", "MTRANS", "TRANSPOSE"),
+    ("TRIM", "GLÄTTEN", "SUPPRESPACE"),
+    ("TRUE", "WAHR", "VRAI"),
+    ("TRUNC", "KÜRZEN", "TRONQUE"),
+    ("TYPE", "TYP", "TYPE"),
+    ("UNICHAR", "UNIZEICHEN", "UNICAR"),
+    ("UPPER", "GROSS", "MAJUSCULE"),
+    ("VALUE", "WERT", "CNUM"),
+    ("VAR", "VARIANZ", "VAR"),
+    ("VARP", "VARIANZEN", "VAR.P"),
+    ("VLOOKUP", "SVERWEIS", "RECHERCHEV"),
+    ("WEEKDAY", "WOCHENTAG", "JOURSEM"),
+    ("WEEKNUM", "KALENDERWOCHE", "NO.SEMAINE"),
+    ("WORKDAY", "ARBEITSTAG", "SERIE.JOUR.OUVRE"),
+    ("XOR", "XODER", "OUX"),
+    ("YEAR", "JAHR", "ANNEE"),
+    ("YEARFRAC", "BRTEILJAHRE", "FRACTION.ANNEE"),
+];
+
+/// Position of the function in [NAMES].
+fn lookup(name: &str) -> Option<usize> {
+    let name = name.to_ascii_uppercase();
+    NAMES
+        .binary_search_by(|(of, _, _)| of.cmp(&name.as_str()))
+        .ok()
+}
+
+/// Errors for the UI form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiError {
+    /// The formula could not be parsed.
+    Parse(ParseError),
+    /// No conventions known for the locale. Supported are en, de and fr.
+    UnsupportedLocale(String),
+}
+
+impl Display for UiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UiError::Parse(e) => write!(f, "{}", e),
+            UiError::UnsupportedLocale(l) => write!(f, "unsupported locale {}", l),
+        }
+    }
+}
+
+impl Error for UiError {}
+
+impl From<ParseError> for UiError {
+    fn from(value: ParseError) -> Self {
+        UiError::Parse(value)
+    }
+}
+
+/// Conventions of a locale for formulas in the UI.
+///
+/// The separators follow the defaults of LibreOffice. Locales with a
+/// decimal comma use `;` between arguments and `.` between array columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiLocale {
+    language: UiLanguage,
+    decimal: char,
+    arg_sep: char,
+    array_col: char,
+    array_row: char,
+}
+
+impl UiLocale {
+    /// Conventions for the locale. Fails for languages other than
+    /// en, de and fr.
+    pub fn new(locale: &Locale) -> Result<Self, UiError> {
+        let region = locale.id.region.as_ref().map(|v| v.as_str());
+        let (language, decimal) = match (locale.id.language.as_str(), region) {
+            ("de", Some("CH" | "LI")) => (UiLanguage::De, '.'),
+            ("de", _) => (UiLanguage::De, ','),
+            ("fr", _) => (UiLanguage::Fr, ','),
+            ("en", _) => (UiLanguage::En, '.'),
+            _ => return Err(UiError::UnsupportedLocale(locale.to_string())),
+        };
+        let arg_sep = if decimal == ',' || language == UiLanguage::De {
+            ';'
+        } else {
+            ','
+        };
+        let array_col = if decimal == ',' { '.' } else { ',' };
+        Ok(Self {
+            language,
+            decimal,
+            arg_sep,
+            array_col,
+            array_row: ';',
+        })
+    }
+
+    /// Decimal separator.
+    pub fn decimal_separator(&self) -> char {
+        self.decimal
+    }
+
+    /// Separator between function arguments.
+    pub fn arg_separator(&self) -> char {
+        self.arg_sep
+    }
+
+    /// Separator between array columns.
+    pub fn array_column_separator(&self) -> char {
+        self.array_col
+    }
+
+    /// Separator between array rows.
+    pub fn array_row_separator(&self) -> char {
+        self.array_row
+    }
+
+    /// Localized name of the OpenFormula function.
+    pub fn function_name<'a>(&self, name: &'a str) -> &'a str {
+        let mut short = name;
+        for prefix in [
+            "COM.MICROSOFT.",
+            "ORG.OPENOFFICE.",
+            "ORG.LIBREOFFICE.",
+            "LEGACY.",
+        ] {
+            if name.len() > prefix.len()
+                && name.is_char_boundary(prefix.len())
+                && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+            {
+                short = &name[prefix.len()..];
+                break;
+            }
+        }
+        let idx = lookup(name).or_else(|| lookup(short));
+        match (idx, self.language) {
+            (Some(idx), UiLanguage::De) => NAMES[idx].1,
+            (Some(idx), UiLanguage::Fr) => NAMES[idx].2,
+            _ => short,
+        }
+    }
+
+    /// Writes the formula in the UI form.
+    pub fn write(&self, expr: &Expr, buf: &mut String) {
+        match expr {
+            Expr::Number(v) => {
+                let start = buf.len();
+                let _ = fmt_float(*v, *v, buf);
+                if self.decimal != '.' {
+                    let s = buf[start..].replace('.', &self.decimal.to_string());
+                    buf.truncate(start);
+                    buf.push_str(&s);
+                }
+            }
+            Expr::Text(_) | Expr::Error(_) | Expr::Name(_) | Expr::Empty => expr.formula(buf),
+            Expr::Ref(r) => self.write_ref(r, buf),
+            Expr::Array(rows) => {
+                buf.push('{');
+                for (i, r) in rows.iter().enumerate() {
+                    if i > 0 {
+                        buf.push(self.array_row);
+                    }
+                    for (j, v) in r.iter().enumerate() {
+                        if j > 0 {
+                            buf.push(self.array_col);
+                        }
+                        self.write(v, buf);
+                    }
+                }
+                buf.push('}');
+            }
+            Expr::Func(name, args) => {
+                buf.push_str(self.function_name(name));
+                buf.push('(');
+                for (i, v) in args.iter().enumerate() {
+                    if i > 0 {
+                        buf.push(self.arg_sep);
+                    }
+                    self.write(v, buf);
+                }
+                buf.push(')');
+            }
+            Expr::Unary(op, v) => {
+                if *op == UnaryOp::Percent {
+                    self.operand(v, op.precedence(), buf);
+                    buf.push_str(op.symbol());
+                } else {
+                    buf.push_str(op.symbol());
+                    self.operand(v, op.precedence(), buf);
+                }
+            }
+            Expr::Infix(a, op, b) => {
+                self.operand(a, op.precedence(), buf);
+                buf.push_str(op.symbol());
+                self.operand(b, op.precedence() + 1, buf);
+            }
+            Expr::Paren(v) => {
+                buf.push('(');
+                self.write(v, buf);
+                buf.push(')');
+            }
+        }
+    }
+
+    fn operand(&self, e: &Expr, min: u8, buf: &mut String) {
+        if e.precedence() < min {
+            buf.push('(');
+            self.write(e, buf);
+            buf.push(')');
+        } else {
+            self.write(e, buf);
+        }
+    }
+
+    fn write_ref(&self, r: &ExprRef, buf: &mut String) {
+        if let Some(iri) = r.iri() {
+            buf.push('\'');
+            buf.push_str(&iri.replace('\'', "''"));
+            buf.push_str("'#");
+        }
+        match r {
+            ExprRef::Cell(c) => {
+                ui_table(c.table(), c.col_abs() || c.row_abs(), buf);
+                a1_col(c.col_abs(), c.col(), buf);
+                a1_row(c.row_abs(), c.row(), buf);
+            }
+            ExprRef::Range(c) => {
+                ui_table(c.table(), c.col_abs() || c.row_abs(), buf);
+                a1_col(c.col_abs(), c.col(), buf);
+                a1_row(c.row_abs(), c.row(), buf);
+                buf.push(':');
+                ui_table(c.to_table(), c.to_col_abs() || c.to_row_abs(), buf);
+                a1_col(c.to_col_abs(), c.to_col(), buf);
+                a1_row(c.to_row_abs(), c.to_row(), buf);
+            }
+            ExprRef::Cols(c) => {
+                ui_table(c.table(), c.col_abs(), buf);
+                a1_col(c.col_abs(), c.col(), buf);
+                buf.push(':');
+                ui_table(c.to_table(), c.to_col_abs(), buf);
+                a1_col(c.to_col_abs(), c.to_col(), buf);
+            }
+            ExprRef::Rows(c) => {
+                ui_table(c.table(), c.row_abs(), buf);
+                a1_row(c.row_abs(), c.row(), buf);
+                buf.push(':');
+                ui_table(c.to_table(), c.to_row_abs(), buf);
+                a1_row(c.to_row_abs(), c.to_row(), buf);
+            }
+        }
+    }
+}
+
+/// Table name with the separator '.'. The table is absolute, if the
+/// reference is.
+fn ui_table(table: Option<&String>, abs: bool, buf: &mut String) {
+    if let Some(table) = table {
+        if abs {
+            buf.push('$');
+        }
+        if table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            buf.push_str(table);
+        } else {
+            buf.push('\'');
+            buf.push_str(&table.replace('\'', "''"));
+            buf.push('\'');
+        }
+        buf.push('.');
+    }
+}
+
+/// Creates the formula as shown in the UI for the given locale.
+pub fn formula_ui<T: Any>(f: T, locale: &Locale) -> Result<String, UiError> {
+    let ui = UiLocale::new(locale)?;
+    let expr = Expr::from_formula(&f)?;
+    let mut buf = String::new();
+    buf.push('=');
+    ui.write(&expr, &mut buf);
+    Ok(buf)
+}
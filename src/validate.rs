@@ -0,0 +1,257 @@
+//!
+//! Validation against the limits of spreadsheet applications.
+//!
+//! Applications reject formulas with too many parameters for a function,
+//! too deeply nested functions or that are too long. Long tuples, variadic
+//! functions and the array parameters of COUNTIFS can produce such
+//! formulas. [validate] reports all violations with their position in the
+//! expression tree.
+//!
+//! The limits differ between applications. [Limits::default] uses the
+//! limits of LibreOffice.
+//!
+//! [fix] splits functions with too many parameters where the result stays
+//! the same, e.g. `SUM(a1;…;a300)` becomes `SUM(SUM(a1;…;a255);SUM(a256;…;a300))`.
+//! This works for SUM, SUMSQ, COUNT, COUNTA and CONCATENATE.
+//!
+//! ```
+//! use spreadsheet_ods_formula::expr::Expr;
+//! use spreadsheet_ods_formula::validate::{fix, validate, Limits, Limit};
+//!
+//! let args = (1..=300).map(|v| Expr::Number(v as f64)).collect();
+//! let e = Expr::func("SUM", args);
+//! let v = validate(&e, &Limits::default());
+//! assert_eq!(v[0].limit(), &Limit::Params("SUM".into(), 300));
+//! assert!(v[0].is_fixable());
+//! assert!(validate(&fix(&e, &Limits::default()), &Limits::default()).is_empty());
+//! ```
+//!
+
+use crate::Any;
+use crate::expr::Expr;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Limits for a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    params: usize,
+    depth: usize,
+    length: usize,
+}
+
+impl Default for Limits {
+    /// The limits of LibreOffice: 255 parameters and 8192 characters, the
+    /// nesting depth is not checked.
+    ///
+    /// * 255 parameters is FORMULA_MAXPARAMS.
+    /// * LibreOffice limits the number of tokens to 8192 (FORMULA_MAXTOKENS).
+    ///   A formula has fewer tokens than characters, so counting characters
+    ///   is on the safe side.
+    /// * LibreOffice has no limit for the nesting depth besides the number
+    ///   of tokens. Use [Limits::new] to check one.
+    fn default() -> Self {
+        Self {
+            params: 255,
+            depth: usize::MAX,
+            length: 8192,
+        }
+    }
+}
+
+impl Limits {
+    /// Limits for parameters per function, nesting depth and formula length.
+    pub fn new(params: usize, depth: usize, length: usize) -> Self {
+        Self {
+            params,
+            depth,
+            length,
+        }
+    }
+
+    /// Maximum number of parameters of a function.
+    pub fn params(&self) -> usize {
+        self.params
+    }
+
+    /// Maximum nesting of functions, parentheses and inline arrays.
+    /// usize::MAX if the depth is not checked.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Maximum number of characters of the formula without "of:=".
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+/// Exceeded limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Limit {
+    /// Function and its number of parameters.
+    Params(String, usize),
+    /// Nesting depth.
+    Depth(usize),
+    /// Formula length.
+    Length(usize),
+}
+
+/// A violation of the [Limits] at a position in the expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    path: Vec<usize>,
+    limit: Limit,
+}
+
+impl Violation {
+    /// Position in the expression. These are the indexes of the function
+    /// parameters, operands and array elements from the root. The operand
+    /// of a unary operator or parentheses has the index 0, array elements
+    /// are counted row by row.
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    /// Exceeded limit.
+    pub fn limit(&self) -> &Limit {
+        &self.limit
+    }
+
+    /// Can be fixed by [fix].
+    pub fn is_fixable(&self) -> bool {
+        matches!(&self.limit, Limit::Params(name, _) if split_with(name).is_some())
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.limit {
+            Limit::Params(name, n) => write!(f, "{} with {} parameters", name, n)?,
+            Limit::Depth(n) => write!(f, "nesting depth {}", n)?,
+            Limit::Length(n) => write!(f, "formula with {} characters", n)?,
+        }
+        if !self.path.is_empty() {
+            write!(f, " at ")?;
+            for (i, v) in self.path.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ".")?;
+                }
+                write!(f, "{}", v)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for Violation {}
+
+/// Reports all violations of the limits. For nesting only the outermost
+/// expression that is too deep is reported.
+pub fn validate(expr: &Expr, limits: &Limits) -> Vec<Violation> {
+    let mut res = Vec::new();
+    let mut buf = String::new();
+    let _ = expr.write_formula(&mut buf);
+    let length = buf.chars().count();
+    if length > limits.length {
+        res.push(Violation {
+            path: Vec::new(),
+            limit: Limit::Length(length),
+        });
+    }
+    check(expr, limits, 0, &mut Vec::new(), &mut res);
+    res
+}
+
+fn children(e: &Expr) -> Vec<&Expr> {
+    match e {
+        Expr::Func(_, args) => args.iter().collect(),
+        Expr::Array(rows) => rows.iter().flatten().collect(),
+        Expr::Unary(_, v) | Expr::Paren(v) => vec![v],
+        Expr::Infix(a, _, b) => vec![a, b],
+        _ => Vec::new(),
+    }
+}
+
+fn check(e: &Expr, limits: &Limits, depth: usize, path: &mut Vec<usize>, res: &mut Vec<Violation>) {
+    let depth = match e {
+        Expr::Func(..) | Expr::Array(_) | Expr::Paren(_) => depth + 1,
+        _ => depth,
+    };
+    if depth > limits.depth {
+        res.push(Violation {
+            path: path.clone(),
+            limit: Limit::Depth(depth + max_depth(e) - 1),
+        });
+        return;
+    }
+    if let Expr::Func(name, args) = e
+        && args.len() > limits.params
+    {
+        res.push(Violation {
+            path: path.clone(),
+            limit: Limit::Params(name.clone(), args.len()),
+        });
+    }
+    for (i, v) in children(e).into_iter().enumerate() {
+        path.push(i);
+        check(v, limits, depth, path, res);
+        path.pop();
+    }
+}
+
+/// Nesting depth of the expression.
+fn max_depth(e: &Expr) -> usize {
+    let inner = children(e).into_iter().map(max_depth).max().unwrap_or(0);
+    match e {
+        Expr::Func(..) | Expr::Array(_) | Expr::Paren(_) => inner + 1,
+        _ => inner,
+    }
+}
+
+/// The function for the parameter groups and the one that combines them.
+fn split_with(name: &str) -> Option<(&'static str, &'static str)> {
+    match name.to_ascii_uppercase().as_str() {
+        "SUM" => Some(("SUM", "SUM")),
+        "SUMSQ" => Some(("SUMSQ", "SUM")),
+        "COUNT" => Some(("COUNT", "SUM")),
+        "COUNTA" => Some(("COUNTA", "SUM")),
+        "CONCATENATE" => Some(("CONCATENATE", "CONCATENATE")),
+        _ => None,
+    }
+}
+
+/// Splits functions with too many parameters into nested calls, where
+/// that doesn't change the result. Other violations stay as they are.
+pub fn fix(expr: &Expr, limits: &Limits) -> Expr {
+    let f = |v: &Expr| fix(v, limits);
+    match expr {
+        Expr::Func(name, args) => {
+            let args: Vec<Expr> = args.iter().map(f).collect();
+            match split_with(name) {
+                Some((inner, outer))
+                    if args.len() > limits.params && !args.contains(&Expr::Empty) =>
+                {
+                    split(inner, outer, args, limits.params.max(2))
+                }
+                _ => Expr::Func(name.clone(), args),
+            }
+        }
+        Expr::Array(rows) => Expr::Array(rows.iter().map(|r| r.iter().map(f).collect()).collect()),
+        Expr::Unary(op, v) => Expr::Unary(*op, Box::new(f(v))),
+        Expr::Infix(a, op, b) => Expr::infix(f(a), *op, f(b)),
+        Expr::Paren(v) => Expr::Paren(Box::new(f(v))),
+        _ => expr.clone(),
+    }
+}
+
+fn split(inner: &str, outer: &str, args: Vec<Expr>, limit: usize) -> Expr {
+    if args.len() <= limit {
+        return Expr::func(inner, args);
+    }
+    let groups = args
+        .chunks(limit)
+        .map(|v| Expr::func(inner, v.to_vec()))
+        .collect();
+    split(outer, outer, groups, limit)
+}
@@ -1,20 +1,55 @@
-use spreadsheet_ods::{CellRange, CellRef};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use icu_locale_core::locale;
+use rust_decimal::Decimal;
+use spreadsheet_ods::color::{Rgb, Rgba};
+use spreadsheet_ods::{CellRange, CellRef, CellStyleRef, ColRange, RowRange, Sheet, Value};
+use std::collections::HashSet;
 
-use spreadsheet_ods_formula::date::{Days360Method, WeekdayMethod};
+use spreadsheet_ods_formula::canon::Canonical;
+use spreadsheet_ods_formula::conv::Radix;
+use spreadsheet_ods_formula::criterion::{CriterionBuilder, CriterionError, CriterionSyntax};
+use spreadsheet_ods_formula::cse::{extract_cells, extract_let};
+use spreadsheet_ods_formula::date::{DatePolicy, Days360Method, FDate, WeekdayMethod};
+use spreadsheet_ods_formula::db::{CriteriaTable, DbError, DbField, DbRange};
+use spreadsheet_ods_formula::dialect::{Dialect, DialectError, ooxml_name};
+use spreadsheet_ods_formula::expr::{Expr, ExprRef, ParseError};
+use spreadsheet_ods_formula::ext::DDEConversion;
+use spreadsheet_ods_formula::fin::PayType;
+use spreadsheet_ods_formula::ifchain::{IfTarget, flatten_if};
+use spreadsheet_ods_formula::info::{NumberSeparator, TypeCode};
+use spreadsheet_ods_formula::logic::{Let, LetError};
+use spreadsheet_ods_formula::lookup::{
+    ArrayDirection, LookupMatchMode, LookupSearchMode, SortKey, SortOrder, UniqueMode,
+};
+use spreadsheet_ods_formula::math::{
+    BaseUnit, BinaryPrefix, ConvertError, ConvertUnit, DecimalPrefix, EuroCurrency, RoundingMode,
+    UnitDimension,
+};
+use spreadsheet_ods_formula::matrix::{
+    FourierDirection, FourierGrouping, FourierOutput, RandArrayValues,
+};
+use spreadsheet_ods_formula::minimize::{AtomPolicy, minimize_logic};
 use spreadsheet_ods_formula::prelude::*;
+use spreadsheet_ods_formula::sheet::{FormulaSheet, FormulaType};
+use spreadsheet_ods_formula::simplify::simplify;
+use spreadsheet_ods_formula::stat::{RankOrder, TTestTails, TTestType};
+use spreadsheet_ods_formula::ui::{UiError, UiLocale, formula_ui};
+use spreadsheet_ods_formula::validate::{Limit, Limits, fix, validate};
 use spreadsheet_ods_formula::{
-    formula, p, CriterionCmp, FArray, FCriterion, FMatrix, FnAny0, ValNumber,
+    Any, CriterionCmp, FArray, FCriterion, FMatrix, FNumber, FnAny0, FormulaError, Number,
+    NumberError, NumberPolicy, ValNumber, formula, formula_into, formula_to, formula_with, p,
+    parse_with, shared,
 };
 use spreadsheet_ods_formula::{num, of};
 
-use crate::result_test::{test_ok, ReportValues};
+use crate::result_test::{ReportValues, test_ok};
 
 mod result_test;
 
 const Q: ReportValues = ReportValues;
 
-fn eq(v: &String, test: &str) -> bool {
-    v.as_str() == test
+fn eq<S: AsRef<str>>(v: &S, test: &str) -> bool {
+    v.as_ref() == test
 }
 
 #[test]
@@ -246,3 +281,1647 @@ fn test_math() {
     )))
     .q(Q);
 }
+
+#[test]
+fn test_libreoffice() {
+    test_ok(formula(of::style(CellStyleRef::from("red"))))
+        .test(eq, "of:=ORG.OPENOFFICE.STYLE(\"red\")")
+        .q(Q);
+    test_ok(formula(
+        num(1)
+            + of::style__(
+                of::if_then_else(CellRef::local(0, 0).gt(0), "pos", "neg"),
+                5,
+                "Default",
+            ),
+    ))
+    .test(
+        eq,
+        "of:=1+ORG.OPENOFFICE.STYLE(IF([.A1]>0;\"pos\";\"neg\");5;\"Default\")",
+    )
+    .q(Q);
+    test_ok(formula(of::current()))
+        .test(eq, "of:=ORG.OPENOFFICE.CURRENT()")
+        .q(Q);
+    test_ok(formula(of::color_rgb(Rgb::new(255, 128, 0))))
+        .test(eq, "of:=ORG.LIBREOFFICE.COLOR(255;128;0)")
+        .q(Q);
+    test_ok(formula(of::color_rgba(Rgba::new(Rgb::new(1, 2, 3), 4))))
+        .test(eq, "of:=ORG.LIBREOFFICE.COLOR(1;2;3;4)")
+        .q(Q);
+    test_ok(formula(of::fourier__(
+        CellRange::local(0, 0, 7, 0),
+        FourierGrouping::ByColumns,
+        FourierDirection::Inverse,
+        FourierOutput::Polar,
+    )))
+    .test(
+        eq,
+        "of:=ORG.LIBREOFFICE.FOURIER([.A1:.A8];TRUE();TRUE();TRUE())",
+    )
+    .q(Q);
+    test_ok(formula(of::randbetween_nv(1, 6)))
+        .test(eq, "of:=ORG.LIBREOFFICE.RANDBETWEEN.NV(1;6)")
+        .q(Q);
+    test_ok(formula(of::errortype(CellRef::local(0, 0))))
+        .test(eq, "of:=ORG.OPENOFFICE.ERRORTYPE([.A1])")
+        .q(Q);
+    test_ok(formula(of::filterxml(
+        of::webservice("http://example.com/rss"),
+        "//item/title",
+    )))
+    .test(
+        eq,
+        "of:=COM.MICROSOFT.FILTERXML(COM.MICROSOFT.WEBSERVICE(\"http://example.com/rss\");\"//item/title\")",
+    )
+    .q(Q);
+}
+
+#[test]
+fn test_dynamic_array() {
+    test_ok(formula(of::xlookup___(
+        "Bert",
+        CellRange::local(0, 0, 99, 0),
+        CellRange::local(0, 1, 99, 1),
+        "n/a",
+        LookupMatchMode::Wildcard,
+        LookupSearchMode::LastToFirst,
+    )))
+    .test(
+        eq,
+        "of:=COM.MICROSOFT.XLOOKUP(\"Bert\";[.A1:.A100];[.B1:.B100];\"n/a\";2;-1)",
+    )
+    .q(Q);
+    test_ok(formula(of::xmatch_(
+        5,
+        CellRange::local(0, 0, 99, 0),
+        LookupMatchMode::ExactOrNextSmaller,
+    )))
+    .test(eq, "of:=COM.MICROSOFT.XMATCH(5;[.A1:.A100];-1)")
+    .q(Q);
+    test_ok(formula(of::sum(of::filter(
+        CellRange::local(0, 1, 99, 1),
+        CellRange::local(0, 0, 99, 0).gt(10),
+    ))))
+    .test(
+        eq,
+        "of:=SUM(COM.MICROSOFT.FILTER([.B1:.B100];[.A1:.A100]>10))",
+    )
+    .q(Q);
+    test_ok(formula(of::sort___(
+        of::unique__(
+            CellRange::local(0, 0, 99, 1),
+            ArrayDirection::ByRow,
+            UniqueMode::ExactlyOnce,
+        ),
+        2,
+        SortOrder::Descending,
+        ArrayDirection::ByRow,
+    )))
+    .test(
+        eq,
+        "of:=COM.MICROSOFT.SORT(COM.MICROSOFT.UNIQUE([.A1:.B100];FALSE();TRUE());2;-1;FALSE())",
+    )
+    .q(Q);
+    test_ok(formula(of::sortby(
+        CellRange::local(0, 0, 9, 1),
+        [
+            (CellRange::local(0, 2, 9, 2), SortOrder::Ascending).into(),
+            SortKey::new(of::sequence(10), SortOrder::Descending),
+        ],
+    )))
+    .test(
+        eq,
+        "of:=COM.MICROSOFT.SORTBY([.A1:.B10];[.C1:.C10];1;COM.MICROSOFT.SEQUENCE(10);-1)",
+    )
+    .q(Q);
+    test_ok(formula(of::sequence___(3, 2, 10, 5)))
+        .test(eq, "of:=COM.MICROSOFT.SEQUENCE(3;2;10;5)")
+        .q(Q);
+    test_ok(formula(of::randarray_____(
+        3,
+        3,
+        1,
+        6,
+        RandArrayValues::Integer,
+    )))
+    .test(eq, "of:=COM.MICROSOFT.RANDARRAY(3;3;1;6;TRUE())")
+    .q(Q);
+}
+
+#[test]
+fn test_let() {
+    let mut l = Let::new();
+    let x = l.bind("x", CellRef::local(0, 0)).expect("x");
+    let y = l.bind("_y.1", x.clone() * 2).expect("y");
+    test_ok(formula(l.calc(x + y).expect("calc")))
+        .test(eq, "of:=COM.MICROSOFT.LET(x;[.A1];_y.1;x*2;x+_y.1)")
+        .q(Q);
+
+    let mut l = Let::new();
+    assert!(l.bind("a", 1).is_ok());
+    assert_eq!(
+        l.bind("A", 1).err(),
+        Some(LetError::DuplicateName("A".into()))
+    );
+    for name in [
+        "", "1x", "A1", "xfd100", "R1C1", "rc", "C", "R2", "true", "a b",
+    ] {
+        assert_eq!(
+            l.bind(name, 1).err(),
+            Some(LetError::InvalidName(name.into()))
+        );
+    }
+    assert!(l.bind("abcd1", 1).is_ok());
+    assert!(l.bind("row", 1).is_ok());
+    assert_eq!(Let::new().calc(1).err(), Some(LetError::NoBinding));
+
+    let mut l = Let::new();
+    let x = l.bind("x", 1).expect("x");
+    let mut other = Let::new();
+    assert_eq!(
+        other.bind("y", x.clone() + 1).err(),
+        Some(LetError::UnboundName("x".into()))
+    );
+    let y = other.bind("y", 2).expect("y");
+    assert_eq!(
+        l.clone().calc(num(x.clone()) + y.clone()).err(),
+        Some(LetError::UnboundName("y".into()))
+    );
+
+    let mut inner = l.nested();
+    let z = inner.bind("z", x.clone() * 2).expect("z");
+    let f = inner.calc(z.clone() + x.clone()).expect("inner");
+    assert_eq!(
+        l.clone().calc(num(x.clone()) + z).err(),
+        Some(LetError::UnboundName("z".into()))
+    );
+    let f = l.calc(num(x) + f).expect("outer");
+    test_ok(formula_with(f, Dialect::Ooxml).expect("ooxml"))
+        .test(
+            eq,
+            "=_xlfn.LET(_xlpm.x,1,_xlpm.x+_xlfn.LET(_xlpm.z,_xlpm.x*2,_xlpm.z+_xlpm.x))",
+        )
+        .q(Q);
+    test_ok(formula(
+        parse_with("=_xlfn.LET(_xlpm.x,1,_xlpm.x+Tax)", Dialect::Ooxml).expect("parse"),
+    ))
+    .test(eq, "of:=COM.MICROSOFT.LET(x;1;x+Tax)")
+    .q(Q);
+}
+
+#[test]
+fn test_param_enums() {
+    test_ok(formula(of::base_(255, Radix::HEXADECIMAL, 4)))
+        .test(eq, "of:=BASE(255;16;4)")
+        .q(Q);
+    test_ok(formula(of::decimal("zap", Radix::new(36).expect("radix"))))
+        .test(eq, "of:=DECIMAL(\"zap\";36)")
+        .q(Q);
+    assert_eq!(Radix::new(1), None);
+    assert_eq!(Radix::new(37), None);
+    test_ok(formula(of::dde_(
+        "soffice",
+        "a.ods",
+        "A1",
+        DDEConversion::Text,
+    )))
+    .test(eq, "of:=DDE(\"soffice\";\"a.ods\";\"A1\";2)")
+    .q(Q);
+    test_ok(formula(of::pmt__(
+        0.05,
+        10,
+        1000,
+        0,
+        PayType::DueAtBeginning,
+    )))
+    .test(eq, "of:=PMT(0.05;10;1000;0;1)")
+    .q(Q);
+    test_ok(formula(of::numbervalue__(
+        "1.234,5",
+        NumberSeparator::Comma,
+        NumberSeparator::Period,
+    )))
+    .test(eq, "of:=NUMBERVALUE(\"1.234,5\";\",\";\".\")")
+    .q(Q);
+    test_ok(formula(of::euroconvert(
+        1,
+        EuroCurrency::DEM,
+        EuroCurrency::EUR,
+    )))
+    .test(eq, "of:=EUROCONVERT(1;\"DEM\";\"EUR\")")
+    .q(Q);
+    test_ok(formula(of::ceiling_math__(
+        -2.5,
+        1,
+        RoundingMode::AwayFrom0,
+    )))
+    .test(eq, "of:=CEILING.MATH(-2.5;1;1)")
+    .q(Q);
+    let r = CellRange::local(0, 0, 9, 0);
+    test_ok(formula(of::rank_(3, r.clone(), RankOrder::Ascending)))
+        .test(eq, "of:=RANK(3;[.A1:.A10];1)")
+        .q(Q);
+    test_ok(formula(of::ttest(
+        r.clone(),
+        r,
+        TTestTails::TwoTailed,
+        TTestType::UnequalVariance,
+    )))
+    .test(eq, "of:=TTEST([.A1:.A10];[.A1:.A10];2;3)")
+    .q(Q);
+    test_ok(formula(
+        of::error_type(CellRef::local(0, 0)).eq(FormulaError::NA.type_code()),
+    ))
+    .test(eq, "of:=ERROR.TYPE([.A1])=7")
+    .q(Q);
+    test_ok(formula(of::type_(CellRef::local(0, 0)).eq(TypeCode::Text)))
+        .test(eq, "of:=TYPE([.A1])=2")
+        .q(Q);
+    assert_eq!(TypeCode::Array.code(), 64);
+    test_ok(formula(of::legacy_tdist(1.5, 10, TTestTails::OneTailed)))
+        .test(eq, "of:=LEGACY.TDIST(1.5;10;1)")
+        .q(Q);
+}
+
+#[test]
+fn test_convert() {
+    let km = ConvertUnit::decimal(DecimalPrefix::Kilo, BaseUnit::Meter).expect("km");
+    test_ok(formula(
+        of::convert(5, km, BaseUnit::Mile.into()).expect("convert"),
+    ))
+    .test(eq, "of:=CONVERT(5;\"km\";\"mi\")")
+    .q(Q);
+    let mm2 = ConvertUnit::decimal(DecimalPrefix::Milli, BaseUnit::Meter)
+        .and_then(|v| v.squared())
+        .expect("mm2");
+    test_ok(formula(
+        of::convert(1, mm2, BaseUnit::SqMeter.into()).expect("convert"),
+    ))
+    .test(eq, "of:=CONVERT(1;\"mm2\";\"m2\")")
+    .q(Q);
+    let kwh = ConvertUnit::decimal(DecimalPrefix::Kilo, BaseUnit::WattHour).expect("kWh");
+    let mj = ConvertUnit::decimal(DecimalPrefix::Mega, BaseUnit::Joule).expect("MJ");
+    test_ok(formula(of::convert(1, kwh, mj).expect("convert")))
+        .test(eq, "of:=CONVERT(1;\"kWh\";\"MJ\")")
+        .q(Q);
+    let mib = ConvertUnit::binary(BinaryPrefix::Mebi, BaseUnit::Byte).expect("Mibyte");
+    test_ok(formula(
+        of::convert(1, mib, BaseUnit::Bit.into()).expect("convert"),
+    ))
+    .test(eq, "of:=CONVERT(1;\"Mibyte\";\"bit\")")
+    .q(Q);
+    let ft3 = ConvertUnit::new(BaseUnit::Foot).cubed().expect("ft3");
+    test_ok(formula(
+        of::convert(1, ft3, BaseUnit::Liter.into()).expect("convert"),
+    ))
+    .test(eq, "of:=CONVERT(1;\"ft3\";\"l\")")
+    .q(Q);
+    test_ok(formula(BaseUnit::Meter)).test(eq, "of:=\"m\"").q(Q);
+
+    assert_eq!(
+        ConvertUnit::decimal(DecimalPrefix::Kilo, BaseUnit::Foot),
+        Err(ConvertError::PrefixNotAllowed(BaseUnit::Foot))
+    );
+    assert_eq!(
+        ConvertUnit::binary(BinaryPrefix::Kibi, BaseUnit::Meter),
+        Err(ConvertError::PrefixNotAllowed(BaseUnit::Meter))
+    );
+    assert_eq!(
+        ConvertUnit::new(BaseUnit::Gram).squared(),
+        Err(ConvertError::PowerNotAllowed(BaseUnit::Gram))
+    );
+    assert_eq!(
+        of::convert(1, BaseUnit::Gram.into(), BaseUnit::Meter.into()).err(),
+        Some(ConvertError::DimensionMismatch(
+            UnitDimension::Mass,
+            UnitDimension::Length
+        ))
+    );
+}
+
+#[test]
+fn test_criterion_builder() {
+    let r = CellRange::local(0, 0, 9, 0);
+    let w = CriterionBuilder::new(CriterionSyntax::Wildcard);
+    test_ok(formula(of::countif(r.clone(), w.gt(1).expect("gt"))))
+        .test(eq, "of:=COUNTIF([.A1:.A10];\">1\")")
+        .q(Q);
+    test_ok(formula(of::countif(r.clone(), w.lte(-0.5).expect("lte"))))
+        .test(eq, "of:=COUNTIF([.A1:.A10];\"<=-0.5\")")
+        .q(Q);
+    assert_eq!(w.gt(f64::NAN).err(), Some(CriterionError::NotFinite));
+    assert_eq!(w.eq(f64::INFINITY).err(), Some(CriterionError::NotFinite));
+    test_ok(formula(of::countif(r.clone(), w.text_eq("what? \"*\" ~"))))
+        .test(eq, "of:=COUNTIF([.A1:.A10];\"=what~? \"\"~*\"\" ~~\")")
+        .q(Q);
+    test_ok(formula(of::countif(
+        r.clone(),
+        w.starts_with("a*").expect("wildcard"),
+    )))
+    .test(eq, "of:=COUNTIF([.A1:.A10];\"=a~**\")")
+    .q(Q);
+    test_ok(formula(of::countif(r.clone(), w.empty())))
+        .test(eq, "of:=COUNTIF([.A1:.A10];\"=\")")
+        .q(Q);
+    test_ok(formula(of::countif(r.clone(), w.non_empty())))
+        .test(eq, "of:=COUNTIF([.A1:.A10];\"<>\")")
+        .q(Q);
+    test_ok(formula(of::countif(
+        r.clone(),
+        w.date(CriterionCmp::GtEq, of::date(2024, 1, 1)),
+    )))
+    .test(eq, "of:=COUNTIF([.A1:.A10];\">=\"&DATE(2024;1;1))")
+    .q(Q);
+    test_ok(formula(of::countif(
+        r.clone(),
+        w.calc(CriterionCmp::Lt, CellRef::local(0, 1)),
+    )))
+    .test(eq, "of:=COUNTIF([.A1:.A10];\"<\"&[.B1])")
+    .q(Q);
+
+    let x = CriterionBuilder::new(CriterionSyntax::Regex);
+    test_ok(formula(of::countif(
+        r.clone(),
+        x.contains("1.5 (net)").expect("regex"),
+    )))
+    .test(eq, "of:=COUNTIF([.A1:.A10];\"=.*1\\.5 \\(net\\).*\")")
+    .q(Q);
+
+    let p = CriterionBuilder::new(CriterionSyntax::Plain);
+    test_ok(formula(of::countif(r, p.text_ne("a*"))))
+        .test(eq, "of:=COUNTIF([.A1:.A10];\"<>a*\")")
+        .q(Q);
+    assert_eq!(
+        p.ends_with("x").err(),
+        Some(CriterionError::PatternNotSupported(CriterionSyntax::Plain))
+    );
+}
+
+#[test]
+fn test_criteria_table() {
+    let c = CriterionBuilder::default();
+    let db =
+        DbRange::new(CellRange::local(0, 0, 99, 2), &["Name", "Age", "City"]).expect("headers");
+    let mut t = CriteriaTable::new(&db);
+    let adult = t.cond("age", c.gte(18).expect("gte")).expect("age");
+    let old = t.cond("Age", c.lt(65).expect("lt")).expect("age");
+    let city = t
+        .cond("City", c.text_eq("Graz"))
+        .expect("city")
+        .or(t.cond("City", c.text_eq("Wien")).expect("city"));
+    t.push(adult.and(old).and(city));
+    t.push(
+        t.cond("Name", c.starts_with("A").expect("name"))
+            .expect("name"),
+    );
+
+    let mut sheet = Sheet::new("Crit");
+    let criteria = t.write(&mut sheet, 2, 4).expect("write");
+    let text = |r, c| match sheet.value(r, c) {
+        Value::Text(v) => v.as_str(),
+        _ => "",
+    };
+    assert_eq!(
+        (2..6)
+            .map(|r| (4..8).map(|c| text(r, c)).collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+        vec![
+            vec!["Name", "Age", "Age", "City"],
+            vec!["", ">=18", "<65", "=Graz"],
+            vec!["", ">=18", "<65", "=Wien"],
+            vec!["=A*", "", "", ""],
+        ]
+    );
+    test_ok(formula(of::dsum(t.database().clone(), "Age", criteria)))
+        .test(eq, "of:=DSUM([.A1:.C100];\"Age\";[Crit.E3:.H6])")
+        .q(Q);
+
+    assert_eq!(
+        t.cond("Town", c.empty()).err(),
+        Some(DbError::UnknownField("Town".into()))
+    );
+    let t = CriteriaTable::new(&db);
+    assert_eq!(t.write(&mut sheet, 0, 0).err(), Some(DbError::NoCondition));
+
+    let other = DbRange::new(CellRange::local(0, 0, 9, 0), &["Town"]).expect("headers");
+    let mut t = CriteriaTable::new(&db);
+    t.push(
+        CriteriaTable::new(&other)
+            .cond("Town", c.text_eq("Graz"))
+            .expect("town"),
+    );
+    assert_eq!(
+        t.write(&mut sheet, 0, 0).err(),
+        Some(DbError::UnknownField("Town".into()))
+    );
+}
+
+#[test]
+fn test_db_range() {
+    let mut sheet = Sheet::new("Data");
+    sheet.set_value(4, 1, "Name");
+    sheet.set_value(4, 2, "Amount");
+    let db = DbRange::from_sheet(&sheet, CellRange::local(4, 1, 20, 2)).expect("headers");
+    assert_eq!(db.headers(), &["Name".to_string(), "Amount".to_string()]);
+    assert_eq!(db.field("AMOUNT").expect("field").name(), Some("Amount"));
+    assert_eq!(db.field_at(1).expect("field").position(), Some(1));
+    assert_eq!(db.field_at(0), Err(DbError::FieldPosition(0)));
+    assert_eq!(db.field_at(3), Err(DbError::FieldPosition(3)));
+    assert_eq!(db.field("Amt"), Err(DbError::UnknownField("Amt".into())));
+
+    let c = CellRange::local(0, 5, 1, 5);
+    test_ok(formula(of::dsum(
+        db.clone(),
+        db.field("amount").expect("field"),
+        c.clone(),
+    )))
+    .test(eq, "of:=DSUM([.B5:.C21];\"Amount\";[.F1:.F2])")
+    .q(Q);
+    test_ok(formula(of::dcount(
+        db.clone(),
+        Some(db.field_at(2).expect("field")),
+        c.clone(),
+    )))
+    .test(eq, "of:=DCOUNT([.B5:.C21];2;[.F1:.F2])")
+    .q(Q);
+    test_ok(formula(of::dcounta(db, None::<DbField>, c)))
+        .test(eq, "of:=DCOUNTA([.B5:.C21];;[.F1:.F2])")
+        .q(Q);
+
+    assert_eq!(
+        DbRange::from_sheet(&sheet, CellRange::local(4, 1, 20, 3)).err(),
+        Some(DbError::InvalidHeader(3))
+    );
+    assert_eq!(
+        DbRange::new(CellRange::local(0, 0, 9, 1), &["A"]).err(),
+        Some(DbError::HeaderCount(1, 2))
+    );
+}
+
+#[test]
+fn test_chrono() {
+    let d = NaiveDate::from_ymd_opt(2024, 3, 1).expect("date");
+    let t = NaiveTime::from_hms_milli_opt(13, 30, 15, 500).expect("time");
+    test_ok(formula(of::weekday(d)))
+        .test(eq, "of:=WEEKDAY(DATE(2024;3;1))")
+        .q(Q);
+    test_ok(formula(of::hour(t)))
+        .test(eq, "of:=HOUR(TIME(13;30;15.5))")
+        .q(Q);
+    test_ok(formula(of::year(d.and_time(t))))
+        .test(eq, "of:=YEAR((DATE(2024;3;1)+TIME(13;30;15.5)))")
+        .q(Q);
+    test_ok(formula(num(d.and_time(t)) * 2))
+        .test(eq, "of:=(DATE(2024;3;1)+TIME(13;30;15.5))*2")
+        .q(Q);
+    test_ok(formula(num(CellRef::local(0, 0)) - d.and_time(t)))
+        .test(eq, "of:=[.A1]-(DATE(2024;3;1)+TIME(13;30;15.5))")
+        .q(Q);
+    test_ok(formula(num(d.and_time(t)).pow(2)))
+        .test(eq, "of:=(DATE(2024;3;1)+TIME(13;30;15.5))^2")
+        .q(Q);
+    test_ok(formula(of::year(d.and_time(NaiveTime::MIN))))
+        .test(eq, "of:=YEAR(DATE(2024;3;1))")
+        .q(Q);
+    test_ok(formula(num(d) + Duration::hours(6)))
+        .test(eq, "of:=DATE(2024;3;1)+TIME(6;0;0)")
+        .q(Q);
+    test_ok(formula(num(d) + Duration::hours(36)))
+        .test(eq, "of:=DATE(2024;3;1)+1.5")
+        .q(Q);
+    test_ok(formula(of::weekday(FDate(d, DatePolicy::Serial))))
+        .test(eq, "of:=WEEKDAY(45352)")
+        .q(Q);
+    test_ok(formula(of::hour(FDate(
+        NaiveTime::from_hms_opt(6, 0, 0).expect("time"),
+        DatePolicy::Serial,
+    ))))
+    .test(eq, "of:=HOUR(0.25)")
+    .q(Q);
+    test_ok(formula(of::networkdays__(
+        d,
+        NaiveDate::from_ymd_opt(2024, 3, 31).expect("date"),
+        vec![
+            NaiveDate::from_ymd_opt(2024, 3, 29).expect("date"),
+            NaiveDate::from_ymd_opt(2024, 4, 1).expect("date"),
+        ],
+        (0, 0, 0, 0, 0, 1, 1),
+    )))
+    .test(
+        eq,
+        "of:=NETWORKDAYS(DATE(2024;3;1);DATE(2024;3;31);{45380;45383};{0;0;0;0;0;1;1})",
+    )
+    .q(Q);
+    test_ok(formula(of::networkdays_(
+        d,
+        NaiveDate::from_ymd_opt(2024, 3, 31).expect("date"),
+        Vec::<NaiveDate>::new(),
+    )))
+    .test(eq, "of:=NETWORKDAYS(DATE(2024;3;1);DATE(2024;3;31);)")
+    .q(Q);
+}
+
+#[test]
+fn test_value_literals() {
+    test_ok(formula(num(Decimal::new(1250, 2)) * 3))
+        .test(eq, "of:=12.50*3")
+        .q(Q);
+    test_ok(formula(of::round(Decimal::new(-5, 1))))
+        .test(eq, "of:=ROUND(-0.5)")
+        .q(Q);
+
+    let d = NaiveDate::from_ymd_opt(2024, 3, 1).expect("date");
+    for (v, f) in [
+        (Value::Number(1.5), "of:=1.5"),
+        (Value::Boolean(true), "of:=TRUE()"),
+        (Value::Text("say \"hi\"".into()), "of:=\"say \"\"hi\"\"\""),
+        (Value::Percentage(0.15), "of:=15%"),
+        (Value::Percentage(-0.005), "of:=-0.5%"),
+        (Value::Percentage(2.0), "of:=200%"),
+        (Value::Currency(12.5, "EUR".into()), "of:=12.5"),
+        (
+            Value::DateTime(d.and_hms_opt(0, 0, 0).expect("time")),
+            "of:=DATE(2024;3;1)",
+        ),
+        (
+            Value::DateTime(d.and_hms_opt(12, 0, 0).expect("time")),
+            "of:=(DATE(2024;3;1)+TIME(12;0;0))",
+        ),
+        (
+            Value::TimeDuration(Duration::minutes(90)),
+            "of:=TIME(1;30;0)",
+        ),
+        (Value::Percentage(f64::NAN), "of:=#NUM!"),
+        (Value::Percentage(f64::INFINITY), "of:=#NUM!"),
+        (Value::Empty, "of:=\"\""),
+    ] {
+        test_ok(formula(v)).test(eq, f).q(Q);
+    }
+    test_ok(formula(of::if_then_else(
+        CellRef::local(0, 0).gt(Value::Number(3.0)),
+        Value::Text("big".into()),
+        Value::Percentage(0.5),
+    )))
+    .test(eq, "of:=IF([.A1]>3;\"big\";50%)")
+    .q(Q);
+}
+
+#[test]
+fn test_number_literals() {
+    for (v, f) in [
+        (1.5, "of:=1.5"),
+        (-0.25, "of:=-0.25"),
+        (0.0, "of:=0"),
+        (123456789.0, "of:=123456789"),
+        (1e300, "of:=1e300"),
+        (-2.5e-10, "of:=-2.5e-10"),
+        (0.1 + 0.2, "of:=0.30000000000000004"),
+        (f64::NAN, "of:=#NUM!"),
+        (f64::INFINITY, "of:=#NUM!"),
+        (f64::NEG_INFINITY, "of:=#NUM!"),
+    ] {
+        test_ok(formula(v)).test(eq, f).q(Q);
+    }
+    test_ok(formula(0.1f32)).test(eq, "of:=0.1").q(Q);
+    test_ok(formula(3e20f32)).test(eq, "of:=3e20").q(Q);
+    test_ok(formula(num(1) / f64::NAN))
+        .test(eq, "of:=1/#NUM!")
+        .q(Q);
+
+    assert!(matches!(
+        FNumber::from_f64(f64::NAN, NumberPolicy::Reject),
+        Err(NumberError::NotFinite(v)) if v.is_nan()
+    ));
+    assert!(FNumber::from_f64(f64::INFINITY, NumberPolicy::Reject).is_err());
+    test_ok(formula(
+        FNumber::from_f64(f64::INFINITY, NumberPolicy::ErrorValue).expect("num"),
+    ))
+    .test(eq, "of:=#NUM!")
+    .q(Q);
+    test_ok(formula(FNumber::from_i128(1 << 60).expect("exact")))
+        .test(eq, "of:=1.152921504606847e18")
+        .q(Q);
+    assert_eq!(
+        FNumber::from_u128(u64::MAX as u128),
+        Err(NumberError::PrecisionLoss(u64::MAX as f64))
+    );
+    assert!(FNumber::from_i128(-(1 << 53)).is_ok());
+    assert!(FNumber::from_i128((1 << 53) + 1).is_err());
+    assert!(FNumber::from_i128(i128::MIN).is_ok());
+
+    test_ok(formula(u64::MAX))
+        .test(eq, "of:=1.8446744073709552e19")
+        .q(Q);
+    test_ok(formula(-9_007_199_254_740_993_i64))
+        .test(eq, "of:=-9007199254740992")
+        .q(Q);
+    test_ok(formula(1_i64 << 60))
+        .test(eq, "of:=1152921504606846976")
+        .q(Q);
+    test_ok(formula(u128::MAX))
+        .test(eq, "of:=3.402823669209385e38")
+        .q(Q);
+    test_ok(formula(-5_i8)).test(eq, "of:=-5").q(Q);
+}
+
+#[test]
+fn test_formula_error() {
+    test_ok(formula(of::iferror(
+        num(1) / CellRef::local(0, 0),
+        FormulaError::NA,
+    )))
+    .test(eq, "of:=IFERROR(1/[.A1];#N/A)")
+    .q(Q);
+    test_ok(formula(of::sum((1, FormulaError::Div0, 3))))
+        .test(eq, "of:=SUM({1;#DIV/0!;3})")
+        .q(Q);
+    test_ok(formula(of::if_then_else(
+        CellRef::local(0, 0).lt(0),
+        FormulaError::Num,
+        of::sqrt(CellRef::local(0, 0)),
+    )))
+    .test(eq, "of:=IF([.A1]<0;#NUM!;SQRT([.A1]))")
+    .q(Q);
+    test_ok(formula(
+        of::error_type(CellRef::local(0, 0)).eq(FormulaError::Name.type_code()),
+    ))
+    .test(eq, "of:=ERROR.TYPE([.A1])=5")
+    .q(Q);
+    for e in [
+        FormulaError::Null,
+        FormulaError::Div0,
+        FormulaError::Value,
+        FormulaError::Ref,
+        FormulaError::Name,
+        FormulaError::Num,
+        FormulaError::NA,
+    ] {
+        assert_eq!(FormulaError::from_type_code(e.type_code()), Some(e));
+    }
+    assert_eq!(FormulaError::NA.type_code(), 7);
+    assert_eq!(FormulaError::from_type_code(8), None);
+    assert_eq!(FormulaError::Null.to_string(), "#NULL!");
+}
+
+/// Writer that accepts a limited number of bytes.
+struct Limited(String, usize);
+
+impl std::fmt::Write for Limited {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        if self.0.len() + s.len() > self.1 {
+            return Err(std::fmt::Error);
+        }
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+/// Implements only the String based method.
+struct Legacy;
+
+impl Any for Legacy {
+    fn formula(&self, buf: &mut String) {
+        buf.push_str("LEGACY()");
+    }
+}
+
+impl Number for Legacy {}
+
+#[test]
+fn test_write_formula() {
+    let f = of::if_then_else(
+        num(CellRef::local(0, 0)).gt(1.5),
+        of::sum(CellRange::local(0, 1, 9, 1)),
+        "a\"b",
+    );
+
+    let mut buf = String::from("x");
+    formula_into(&f, &mut buf);
+    formula_into(&Legacy, &mut buf);
+    test_ok(buf)
+        .test(
+            eq,
+            "xof:=IF([.A1]>1.5;SUM([.B1:.B10]);\"a\"\"b\")of:=LEGACY()",
+        )
+        .q(Q);
+
+    let mut out = Limited(String::new(), 1000);
+    formula_to(&(num(Legacy) + 1), &mut out).expect("write");
+    test_ok(out.0).test(eq, "of:=LEGACY()+1").q(Q);
+
+    let mut out = Limited(String::new(), 20);
+    assert!(formula_to(&f, &mut out).is_err());
+    assert_eq!(out.0, "of:=IF([.A1]>1.5;SUM");
+}
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_send_sync() {
+    let f = std::thread::spawn(|| {
+        let mut l = Let::new();
+        let x = l
+            .bind(
+                "x",
+                of::countifs([(
+                    CellRange::local(0, 0, 9, 0),
+                    CriterionBuilder::default().gt(1).expect("gt"),
+                )]),
+            )
+            .expect("bind");
+        let f = of::text(l.calc(x * 2).expect("calc"), "0")
+            & of::text(
+                of::getpivotdata_fields("sum", CellRange::local(0, 0, 9, 9), [("a", "b")]),
+                "0",
+            );
+        assert_send_sync(&f);
+        f
+    })
+    .join()
+    .expect("thread");
+    test_ok(formula(f))
+        .test(eq, "of:=TEXT(COM.MICROSOFT.LET(x;COUNTIFS([.A1:.A10];\">1\");x*2);\"0\")&TEXT(GETPIVOTDATA(\"sum\";[.A1:.J10];\"a\";\"b\");\"0\")")
+        .q(Q);
+
+    assert_send_sync(&Expr::parse("SUM([.A1:.B2])").expect("parse"));
+    assert_send_sync(&(num(CellRef::local(0, 0)) + 1));
+}
+
+#[test]
+fn test_clone_shared() {
+    let c = CriterionBuilder::default();
+    let count = of::countifs([(CellRange::local(0, 0, 9, 0), c.gt(1).expect("gt"))]);
+    let m = FMatrix([[1, 2], [3, 4]]);
+    let f = num(count.clone()) + count + of::mdeterm(m.clone()) + of::sum(p(of::mdeterm(m)));
+    test_ok(formula(f.clone()))
+        .test(eq, "of:=COUNTIFS([.A1:.A10];\">1\")+COUNTIFS([.A1:.A10];\">1\")+MDETERM({1;2|3;4})+SUM((MDETERM({1;2|3;4})))")
+        .q(Q);
+    assert_eq!(formula(f.clone()), formula(f));
+
+    let mut l = Let::new();
+    let x = l.bind("x", CellRef::local(0, 0)).expect("bind");
+    let l2 = l.clone();
+    test_ok(formula(l.calc(x.clone() + 1).expect("calc")))
+        .test(eq, "of:=COM.MICROSOFT.LET(x;[.A1];x+1)")
+        .q(Q);
+    test_ok(formula(l2.calc(x * 2).expect("calc")))
+        .test(eq, "of:=COM.MICROSOFT.LET(x;[.A1];x*2)")
+        .q(Q);
+
+    let v = shared(of::vlookup(
+        CellRef::local(0, 0),
+        CellRange::local(0, 1, 9, 2),
+        2,
+    ));
+    let f = of::if_then_else(of::isna(v.clone()), v.clone() + 1, v & "x");
+    test_ok(formula(f))
+        .test(eq, "of:=IF(ISNA(VLOOKUP([.A1];[.B1:.C10];2));VLOOKUP([.A1];[.B1:.C10];2)+1;VLOOKUP([.A1];[.B1:.C10];2)&\"x\")")
+        .q(Q);
+
+    let gt = shared(c.gt(1).expect("gt"));
+    let f = of::countif(CellRange::local(0, 0, 9, 0), gt.clone())
+        + of::sumif(CellRange::local(0, 1, 9, 1), gt);
+    test_ok(formula(f))
+        .test(
+            eq,
+            "of:=COUNTIF([.A1:.A10];\">1\")+SUMIF([.B1:.B10];\">1\")",
+        )
+        .q(Q);
+}
+
+#[test]
+fn test_ooxml() {
+    let ox = |f: String| -> String {
+        let e = Expr::parse(&f).expect("parse");
+        assert_eq!(formula(e.clone()), f);
+        formula_with(e, Dialect::Ooxml).expect("ooxml")
+    };
+    test_ok(ox(formula(of::sum(CellRange::local(0, 0, 9, 0)))))
+        .test(eq, "=SUM(A1:A10)")
+        .q(Q);
+    test_ok(ox(formula(of::if_then_else(
+        CellRef::remote("Sheet 2", 0, 0).absolute().gt(1.5),
+        "a\"b",
+        of::true_(),
+    ))))
+    .test(eq, "=IF('Sheet 2'!$A$1>1.5,\"a\"\"b\",TRUE)")
+    .q(Q);
+    test_ok(ox(formula(
+        num(-2) ^ p(num(CellRef::remote("A1", 1, 1)) + 3),
+    )))
+    .test(eq, "=-2^('A1'!B2+3)")
+    .q(Q);
+    test_ok(ox(formula(of::sum(
+        CellRange::local(0, 0, 1, 1).refcat(CellRange::local(4, 4, 5, 5)),
+    ))))
+    .test(eq, "=SUM((A1:B2,E5:F6))")
+    .q(Q);
+    test_ok(ox(formula(of::mdeterm(FMatrix([[1, 2], [3, 4]])))))
+        .test(eq, "=MDETERM({1,2;3,4})")
+        .q(Q);
+    test_ok(ox(formula(of::unique(CellRange::local(0, 0, 9, 0)))))
+        .test(eq, "=_xlfn.UNIQUE(A1:A10)")
+        .q(Q);
+    test_ok(ox(formula(of::filter(
+        CellRange::local(0, 0, 9, 0),
+        CellRange::local(0, 1, 9, 1).gt(0),
+    ))))
+    .test(eq, "=_xlfn._xlws.FILTER(A1:A10,B1:B10>0)")
+    .q(Q);
+    test_ok(ox(formula(of::legacy_normsdist(0.5))))
+        .test(eq, "=NORMSDIST(0.5)")
+        .q(Q);
+    test_ok(ox(formula(of::ifna(CellRef::local(0, 0), 0))))
+        .test(eq, "=_xlfn.IFNA(A1,0)")
+        .q(Q);
+    test_ok(ox(formula(of::fdist(1, 2, 3))))
+        .test(eq, "=_xlfn.F.DIST(1,2,3,TRUE)")
+        .q(Q);
+    test_ok(ox(formula(of::fdist_(1, 2, 3, false))))
+        .test(eq, "=_xlfn.F.DIST(1,2,3,FALSE)")
+        .q(Q);
+    test_ok(ox(formula(of::finv(0.5, 2, 3))))
+        .test(eq, "=_xlfn.F.INV(0.5,2,3)")
+        .q(Q);
+    test_ok(ox(formula(of::legacy_fdist(1, 2, 3))))
+        .test(eq, "=FDIST(1,2,3)")
+        .q(Q);
+    let back = |f: &str| formula(parse_with(&ox(f.into()), Dialect::Ooxml).expect("excel"));
+    test_ok(back("of:=FDIST(1;2;3)+FINV(0.5;2;3)"))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.F.DIST(1;2;3;TRUE())+COM.MICROSOFT.F.INV(0.5;2;3)",
+        )
+        .q(Q);
+    test_ok(back("of:=LEGACY.FDIST(1;2;3)+LEGACY.FINV(0.5;2;3)"))
+        .test(eq, "of:=LEGACY.FDIST(1;2;3)+LEGACY.FINV(0.5;2;3)")
+        .q(Q);
+    test_ok(ox(formula(of::ceiling(1.5))))
+        .test(eq, "=_xlfn.CEILING.MATH(1.5)")
+        .q(Q);
+    test_ok(ox(formula(of::floor(1.5))))
+        .test(eq, "=_xlfn.FLOOR.MATH(1.5)")
+        .q(Q);
+    test_ok(back("of:=CEILING.XCL(1.5;1)+FLOOR.XCL(1.5;1)"))
+        .test(eq, "of:=CEILING.XCL(1.5;1)+FLOOR.XCL(1.5;1)")
+        .q(Q);
+    test_ok(back(
+        "of:=CEILING(1.5)+COM.MICROSOFT.XLOOKUP(1;[.A1:.A2];[.B1:.B2])",
+    ))
+    .test(
+        eq,
+        "of:=CEILING.MATH(1.5)+COM.MICROSOFT.XLOOKUP(1;[.A1:.A2];[.B1:.B2])",
+    )
+    .q(Q);
+
+    assert_eq!(
+        formula_with(
+            of::dde_conv("soffice", "a.ods", "A1", DDEConversion::Text),
+            Dialect::Ooxml
+        ),
+        Err(DialectError::NoEquivalent("DDE".into()))
+    );
+    assert!(matches!(
+        formula_with(of::current(), Dialect::Ooxml),
+        Err(DialectError::NoEquivalent(_))
+    ));
+    assert_eq!(
+        formula_with(Expr::parse("of:=MYFUNC(1)").expect("parse"), Dialect::Ooxml),
+        Err(DialectError::NoEquivalent("MYFUNC".into()))
+    );
+    assert_eq!(
+        ooxml_name("COM.MICROSOFT.MYFUNC"),
+        Err(DialectError::NoEquivalent("COM.MICROSOFT.MYFUNC".into()))
+    );
+    assert_eq!(
+        ooxml_name("LEGACY.SUM"),
+        Err(DialectError::NoEquivalent("LEGACY.SUM".into()))
+    );
+    assert_eq!(
+        formula_with(of::sum(CellRef::local(0, 0)), Dialect::OpenFormula),
+        Ok("of:=SUM([.A1])".to_string())
+    );
+    assert!(matches!(
+        Expr::parse("of:=SUM([.A1];"),
+        Err(ParseError::UnexpectedEnd)
+    ));
+    assert_eq!(
+        Expr::parse("of:=#FOO!"),
+        Err(ParseError::ErrorLiteral(4, "#FOO!".into()))
+    );
+}
+
+#[test]
+fn test_ooxml_import() {
+    let xl = |f: &str| -> String { formula(parse_with(f, Dialect::Ooxml).expect("excel")) };
+    test_ok(xl("=SUMIFS(B:B,A:A,\">5\")"))
+        .test(eq, "of:=SUMIFS([.B:.B];[.A:.A];\">5\")")
+        .q(Q);
+    test_ok(xl("=Sheet2!$A$1+'My Sheet'!B2:C$3"))
+        .test(eq, "of:=[$Sheet2.$A$1]+[$'My Sheet'.B2:.C$3]")
+        .q(Q);
+    test_ok(xl("=SUM(Sheet1:Sheet3!A1,2:$4)"))
+        .test(eq, "of:=SUM([Sheet1.A1:Sheet3.A1];[.2:.$4])")
+        .q(Q);
+    test_ok(xl("=_xlfn.XLOOKUP(A1,B:B,C:C,\"\")"))
+        .test(eq, "of:=COM.MICROSOFT.XLOOKUP([.A1];[.B:.B];[.C:.C];\"\")")
+        .q(Q);
+    test_ok(xl("=_xlfn._xlws.SORT(A1:A10)&NORMSDIST(1)&_xlfn.NORM.DIST(1,0,1,TRUE)"))
+        .test(eq, "of:=COM.MICROSOFT.SORT([.A1:.A10])&LEGACY.NORMSDIST(1)&COM.MICROSOFT.NORM.DIST(1;0;1;TRUE())")
+        .q(Q);
+    test_ok(xl("=IF(LOG10(A1)>=1.5E+3,{1,2;3,4},-B1%)"))
+        .test(eq, "of:=IF(LOG10([.A1])>=1500;{1;2|3;4};-[.B1]%)")
+        .q(Q);
+    test_ok(xl("=SUM((A1:B2,C3)) + SUM(A1:C3 B2:B4) + Rate * 2"))
+        .test(
+            eq,
+            "of:=SUM(([.A1:.B2]~[.C3]))+SUM([.A1:.C3]![.B2:.B4])+Rate*2",
+        )
+        .q(Q);
+    test_ok(xl("=IF(A1,,\"\")"))
+        .test(eq, "of:=IF([.A1];;\"\")")
+        .q(Q);
+    test_ok(xl("=SUM(B2:A1)+SUM($A2:B$1)+SUM(B:$A)+SUM(3:1)"))
+        .test(
+            eq,
+            "of:=SUM([.A1:.B2])+SUM([.$A$1:.B2])+SUM([.$A:.B])+SUM([.1:.3])",
+        )
+        .q(Q);
+
+    assert_eq!(
+        parse_with("=TEXTSPLIT(A1,\",\")", Dialect::Ooxml),
+        Err(DialectError::NoEquivalent("TEXTSPLIT".into()))
+    );
+    assert_eq!(
+        parse_with("=_xll.MYFN(1)", Dialect::Ooxml),
+        Err(DialectError::NoEquivalent("_xll.MYFN".into()))
+    );
+    assert!(matches!(
+        parse_with("=SUM(Table1[Col])", Dialect::Ooxml),
+        Err(DialectError::Parse(ParseError::Unexpected(..)))
+    ));
+    assert!(matches!(
+        parse_with("='Sheet 1'A1", Dialect::Ooxml),
+        Err(DialectError::Parse(ParseError::Reference(1, _)))
+    ));
+}
+
+#[test]
+fn test_r1c1() {
+    // The same formula filled down a column.
+    let down = |row: u32| {
+        let f = num(CellRef::local(row - 1, 2))
+            + num(CellRef::local(row, 0)) * CellRef::local(0, 1).absolute();
+        formula_with(f, Dialect::r1c1(&CellRef::local(row, 2))).expect("r1c1")
+    };
+    test_ok(down(4)).test(eq, "=R[-1]C+RC[-2]*R1C2").q(Q);
+    test_ok(down(9)).test(eq, "=R[-1]C+RC[-2]*R1C2").q(Q);
+
+    let r1c1 = Dialect::R1C1 { row: 4, col: 2 };
+    let mut range = CellRange::remote("Data 1", 0, 0, 9, 2);
+    range.set_row_abs(true);
+    range.set_to_col_abs(true);
+    let cols = Expr::Ref(ExprRef::Cols(ColRange::new(1, 3)));
+    let rows = Expr::Ref(ExprRef::Rows(RowRange::new(4, 4)));
+    let f = of::sum(range) + of::sum(cols) + of::sum(rows);
+    test_ok(formula_with(f, r1c1).expect("r1c1"))
+        .test(eq, "=SUM('Data 1'!R1C[-2]:R[5]C3)+SUM(C[-1]:C[1])+SUM(R)")
+        .q(Q);
+
+    let xl = |f: &str| -> String { formula(parse_with(f, r1c1).expect("r1c1")) };
+    test_ok(xl("=R[-1]C+RC[-2]*R1C2"))
+        .test(eq, "of:=[.C4]+[.A5]*[.$B$1]")
+        .q(Q);
+    test_ok(xl(
+        "=SUM('Data 1'!R1C[-2]:R[5]C3,C[-1]:C[1],C2,R3:R[1],R) + Rate",
+    ))
+    .test(
+        eq,
+        "of:=SUM([$'Data 1'.A$1:.$C10];[.B:.D];[.$B:.$B];[.$3:.6];[.5:.5])+Rate",
+    )
+    .q(Q);
+    test_ok(xl("=_xlfn.XLOOKUP(rc[1],R1C1:R10C1,r1c2:r10c2)"))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.XLOOKUP([.D5];[.$A$1:.$A$10];[.$B$1:.$B$10])",
+        )
+        .q(Q);
+
+    assert!(matches!(
+        parse_with("=R[-5]C", r1c1),
+        Err(DialectError::Parse(ParseError::Unexpected(..)))
+    ));
+    test_ok(xl("=SUM(R[1]C:RC)+SUM(R[1]:R3)+SUM(C[1]:C1)"))
+        .test(eq, "of:=SUM([.C5:.C6])+SUM([.$3:.6])+SUM([.$A:.D])")
+        .q(Q);
+    assert!(matches!(parse_with("=A1", r1c1), Ok(Expr::Name(_))));
+}
+
+#[test]
+fn test_formula_ui() {
+    let de = locale!("de-DE");
+    let fr = locale!("fr-FR");
+    let en = locale!("en-US");
+    let f = || {
+        of::if_then_else(
+            of::countif(
+                CellRange::local(0, 0, 9, 0),
+                CriterionBuilder::default().gt(1.5).expect("gt"),
+            )
+            .gt(0),
+            of::vlookup(
+                CellRef::remote("Tab 2", 0, 0).absolute(),
+                CellRange::local(0, 1, 9, 2),
+                2,
+            ),
+            of::true_(),
+        )
+    };
+    test_ok(formula_ui(f(), &de).expect("ui"))
+        .test(
+            eq,
+            "=WENN(ZÄHLENWENN(A1:A10;\">1.5\")>0;SVERWEIS($'Tab 2'.$A$1;B1:C10;2);WAHR())",
+        )
+        .q(Q);
+    test_ok(formula_ui(f(), &fr).expect("ui"))
+        .test(
+            eq,
+            "=SI(NB.SI(A1:A10;\">1.5\")>0;RECHERCHEV($'Tab 2'.$A$1;B1:C10;2);VRAI())",
+        )
+        .q(Q);
+    test_ok(formula_ui(f(), &en).expect("ui"))
+        .test(
+            eq,
+            "=IF(COUNTIF(A1:A10,\">1.5\")>0,VLOOKUP($'Tab 2'.$A$1,B1:C10,2),TRUE())",
+        )
+        .q(Q);
+    test_ok(
+        formula_ui(
+            of::xlookup(
+                1,
+                CellRange::local(0, 0, 9, 0),
+                CellRange::local(0, 1, 9, 1),
+            ),
+            &de,
+        )
+        .expect("ui"),
+    )
+    .test(eq, "=XVERWEIS(1;A1:A10;B1:B10)")
+    .q(Q);
+    test_ok(
+        formula_ui(
+            num(0.5) * of::mdeterm(FMatrix([[1.5, 2.0], [3.0, 4.0]])),
+            &de,
+        )
+        .expect("ui"),
+    )
+    .test(eq, "=0,5*MDET({1,5.2;3.4})")
+    .q(Q);
+    test_ok(formula_ui(of::legacy_normsdist(0.5), &de).expect("ui"))
+        .test(eq, "=STANDNORMVERT(0,5)")
+        .q(Q);
+    test_ok(formula_ui(of::legacy_normsdist(0.5), &fr).expect("ui"))
+        .test(eq, "=LOI.NORMALE.STANDARD(0,5)")
+        .q(Q);
+    test_ok(formula_ui(of::legacy_normsdist(0.5), &en).expect("ui"))
+        .test(eq, "=NORMSDIST(0.5)")
+        .q(Q);
+
+    let ch = UiLocale::new(&locale!("de-CH")).expect("de-CH");
+    assert_eq!(ch.decimal_separator(), '.');
+    assert_eq!(ch.arg_separator(), ';');
+    assert_eq!(ch.array_column_separator(), ',');
+    assert_eq!(ch.function_name("SUM"), "SUMME");
+    assert_eq!(ch.function_name("legacy.tdist"), "TVERT");
+    assert_eq!(ch.function_name("COM.MICROSOFT.IFS"), "WENNS");
+    assert_eq!(
+        UiLocale::new(&locale!("it-IT")),
+        Err(UiError::UnsupportedLocale("it-IT".into()))
+    );
+    assert_eq!(
+        formula_ui(of::pi(), &locale!("ja")),
+        Err(UiError::UnsupportedLocale("ja".into()))
+    );
+}
+
+#[test]
+fn test_canonical() {
+    let c = |f: &str| Canonical::parse(f, Some("Sheet1")).expect("canonical");
+
+    test_ok(
+        c("of:=if(([.A1]> 0);concatenate(\"a\";[$Sheet1.B1];\"c\");+mySum)")
+            .as_str()
+            .to_string(),
+    )
+    .test(eq, "IF([.A1]>0;\"a\"&[.B1]&\"c\";MYSUM)")
+    .q(Q);
+    assert_eq!(
+        c("of:=\"a\"&(\"b\"&\"c\")"),
+        c("of:=CONCATENATE(\"a\";\"b\";\"c\")")
+    );
+    assert_eq!(c("of:=SUM([.A1]:[.B2])"), c("of:=sum([$Sheet1.A1:.B2])"));
+    assert_eq!(c("of:=([.A1]-[.B1])-[.C1]"), c("of:=[.A1]-[.B1]-[.C1]"));
+    assert_eq!(c("of:=-0"), c("of:=-0.0"));
+
+    assert_ne!(c("of:=1+2"), c("of:=2+1"));
+    assert_ne!(c("of:=[.A1]"), c("of:=[.$A$1]"));
+    assert_ne!(c("of:=[.A1]"), c("of:=[$Sheet2.A1]"));
+    assert_ne!(c("of:=[.A1]-([.B1]-[.C1])"), c("of:=[.A1]-[.B1]-[.C1]"));
+    assert_ne!(c("of:=CONCATENATE(1)"), c("of:=1"));
+    assert_ne!(c("of:=\"a\""), c("of:=\"A\""));
+
+    let sheet2 = Canonical::parse("of:=[.A1]", Some("Sheet2")).expect("canonical");
+    let other = Canonical::parse("of:=[$Sheet2.A1]", None).expect("canonical");
+    assert_ne!(sheet2, other);
+    assert_eq!(sheet2, Canonical::new(other.expr(), Some("Sheet2")));
+
+    let mut set = HashSet::new();
+    set.insert(c("of:=SUM([.A1:.A10])"));
+    set.insert(c("of:=sum(([$Sheet1.A1:.A10]))"));
+    set.insert(
+        Canonical::from_formula(&of::sum(CellRange::local(0, 0, 9, 0)), Some("Sheet1"))
+            .expect("canonical"),
+    );
+    assert_eq!(set.len(), 1);
+    test_ok(formula(set.into_iter().next().expect("one")))
+        .test(eq, "of:=SUM([.A1:.A10])")
+        .q(Q);
+}
+
+#[test]
+fn test_simplify() {
+    let s = |f: &str| formula(simplify(&Expr::parse(f).expect("expr")));
+
+    test_ok(s("of:=1*(2+3)")).test(eq, "of:=5").q(Q);
+    test_ok(s("of:=0.3-0.1-0.2")).test(eq, "of:=0").q(Q);
+    test_ok(s("of:=-(-(2*[.A1]))")).test(eq, "of:=2*[.A1]").q(Q);
+    test_ok(s("of:=([.A1]+1)*1+0")).test(eq, "of:=[.A1]+1").q(Q);
+    test_ok(s("of:=[.A1]*1")).test(eq, "of:=[.A1]*1").q(Q);
+    test_ok(s("of:=--[.A1]")).test(eq, "of:=--[.A1]").q(Q);
+    test_ok(s("of:=1/0")).test(eq, "of:=1/0").q(Q);
+    test_ok(s("of:=2>1")).test(eq, "of:=TRUE()").q(Q);
+
+    test_ok(s("of:=IF(TRUE();[.A1];[.B1])"))
+        .test(eq, "of:=[.A1]")
+        .q(Q);
+    test_ok(s("of:=IF(1-1;[.A1])")).test(eq, "of:=FALSE()").q(Q);
+    test_ok(s("of:=IF(FALSE();RAND();1)"))
+        .test(eq, "of:=IF(FALSE();RAND();1)")
+        .q(Q);
+    test_ok(s("of:=IF([.A1];1+1;2)"))
+        .test(eq, "of:=IF([.A1];2;2)")
+        .q(Q);
+
+    test_ok(s("of:=NOT(NOT([.A1]>1))"))
+        .test(eq, "of:=[.A1]>1")
+        .q(Q);
+    test_ok(s("of:=NOT(NOT([.A1]))"))
+        .test(eq, "of:=NOT(NOT([.A1]))")
+        .q(Q);
+    test_ok(s("of:=AND(TRUE();1;OR(FALSE();0))"))
+        .test(eq, "of:=FALSE()")
+        .q(Q);
+    test_ok(s("of:=OR(FALSE();[.A1])"))
+        .test(eq, "of:=OR(FALSE();[.A1])")
+        .q(Q);
+
+    test_ok(s("of:=CONCATENATE(\"a\";\"b\")"))
+        .test(eq, "of:=\"ab\"")
+        .q(Q);
+    test_ok(s("of:=CONCATENATE(\"a\";\"b\";[.A1])"))
+        .test(eq, "of:=CONCATENATE(\"ab\";[.A1])")
+        .q(Q);
+    test_ok(s("of:=\"a\"&\"\"&[.A1]&\"b\"&\"c\""))
+        .test(eq, "of:=\"a\"&[.A1]&\"bc\"")
+        .q(Q);
+    test_ok(s("of:=[.A1]&\"\"")).test(eq, "of:=[.A1]&\"\"").q(Q);
+    test_ok(s("of:=1&\"\"")).test(eq, "of:=1&\"\"").q(Q);
+
+    let f = p(p(num(CellRef::local(0, 0)) * 2)) + 0;
+    test_ok(formula(simplify(&Expr::from_formula(&f).expect("expr"))))
+        .test(eq, "of:=[.A1]*2")
+        .q(Q);
+}
+
+#[test]
+fn test_minimize_logic() {
+    let keep = |f: &str| {
+        formula(minimize_logic(
+            &Expr::parse(f).expect("expr"),
+            AtomPolicy::Keep,
+        ))
+    };
+    let drop = |f: &str| {
+        formula(minimize_logic(
+            &Expr::parse(f).expect("expr"),
+            AtomPolicy::Drop,
+        ))
+    };
+
+    let a = || num(CellRef::local(0, 0)).gt(0);
+    let b = || num(CellRef::local(0, 1)).gt(0);
+    let c = || num(CellRef::local(0, 2)).gt(0);
+    let f = a().or(b()).and(a().or(c())).and(a());
+    let e = Expr::from_formula(&f).expect("expr");
+    test_ok(formula(minimize_logic(&e, AtomPolicy::Drop)))
+        .test(eq, "of:=[.A1]>0")
+        .q(Q);
+    test_ok(formula(minimize_logic(&e, AtomPolicy::Keep)))
+        .test(eq, "of:=AND(OR([.A1]>0;AND([.B1]>0;[.C1]>0));[.A1]>0)")
+        .q(Q);
+
+    test_ok(drop(
+        "of:=OR(AND([.A1]>0;[.B1]>0);AND([.A1]>0;NOT([.B1]>0)))",
+    ))
+    .test(eq, "of:=[.A1]>0")
+    .q(Q);
+    test_ok(keep("of:=OR(AND([.A1]>0;[.B1]>0);AND([.A1]>0;[.C1]>0))"))
+        .test(eq, "of:=AND([.A1]>0;OR([.B1]>0;[.C1]>0))")
+        .q(Q);
+    test_ok(keep("of:=NOT(NOT(ISBLANK([.A1])))"))
+        .test(eq, "of:=ISBLANK([.A1])")
+        .q(Q);
+    test_ok(keep("of:=AND([.A1]>0;([.A1]>0);TRUE())"))
+        .test(eq, "of:=[.A1]>0")
+        .q(Q);
+    test_ok(drop("of:=IF(AND([.A1]>0;OR([.A1]>0;[.B1]>0));1;2)"))
+        .test(eq, "of:=IF([.A1]>0;1;2)")
+        .q(Q);
+
+    // An error in [.A1] must still be returned.
+    test_ok(keep("of:=AND([.A1]>0;NOT([.A1]>0))"))
+        .test(eq, "of:=AND([.A1]>0;NOT([.A1]>0))")
+        .q(Q);
+    test_ok(keep("of:=OR(ISBLANK([.A1]);NOT(ISBLANK([.A1])))"))
+        .test(eq, "of:=OR(ISBLANK([.A1]);NOT(ISBLANK([.A1])))")
+        .q(Q);
+    test_ok(keep("of:=IF(AND([.A1]>0;OR([.A1]>0;[.B1]>0));1;2)"))
+        .test(eq, "of:=IF(AND([.A1]>0;OR([.A1]>0;[.B1]>0));1;2)")
+        .q(Q);
+    test_ok(drop("of:=AND([.A1]>0;NOT([.A1]>0))"))
+        .test(eq, "of:=FALSE()")
+        .q(Q);
+
+    test_ok(drop("of:=XOR([.A1]>0;[.B1]>0)"))
+        .test(eq, "of:=XOR([.A1]>0;[.B1]>0)")
+        .q(Q);
+    test_ok(drop("of:=AND([.A1]>0;OR([.A1]>0;[.B1]))"))
+        .test(eq, "of:=AND([.A1]>0;OR([.A1]>0;[.B1]))")
+        .q(Q);
+    test_ok(drop("of:=AND(RAND()>0.5;RAND()>0.5)"))
+        .test(eq, "of:=AND(RAND()>0.5;RAND()>0.5)")
+        .q(Q);
+    // Comparisons with ranges and arrays give an array of results.
+    test_ok(drop("of:=AND([.A1:.A3]>0;[.A1:.A3]>0)"))
+        .test(eq, "of:=AND([.A1:.A3]>0;[.A1:.A3]>0)")
+        .q(Q);
+    test_ok(drop("of:=OR({1;2}=[.A1];NOT({1;2}=[.A1]))"))
+        .test(eq, "of:=OR({1;2}=[.A1];NOT({1;2}=[.A1]))")
+        .q(Q);
+    test_ok(drop("of:=AND(ISBLANK([.A1:.B2]);ISBLANK([.A1:.B2]))"))
+        .test(eq, "of:=AND(ISBLANK([.A1:.B2]);ISBLANK([.A1:.B2]))")
+        .q(Q);
+    test_ok(drop("of:=AND(SUM([.A1:.A3])>0;SUM([.A1:.A3])>0)"))
+        .test(eq, "of:=SUM([.A1:.A3])>0")
+        .q(Q);
+}
+
+#[test]
+fn test_flatten_if() {
+    let ext = |f: &str| {
+        formula(flatten_if(
+            &Expr::parse(f).expect("expr"),
+            IfTarget::Extended,
+        ))
+    };
+    let odf = |f: &str| formula(flatten_if(&Expr::parse(f).expect("expr"), IfTarget::Odf12));
+
+    let a = || num(CellRef::local(0, 0));
+    let f = of::if___(a().gt(10), "high", of::if___(a().gt(5), "mid", "low"));
+    let e = Expr::from_formula(&f).expect("expr");
+    test_ok(formula(flatten_if(&e, IfTarget::Extended)))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.IFS([.A1]>10;\"high\";[.A1]>5;\"mid\";TRUE();\"low\")",
+        )
+        .q(Q);
+    test_ok(formula(flatten_if(&e, IfTarget::Odf12)))
+        .test(eq, "of:=IF([.A1]>10;\"high\";IF([.A1]>5;\"mid\";\"low\"))")
+        .q(Q);
+
+    let dense = "of:=IF([.A1]=2;\"b\";IF([.A1]=1;\"a\";(IF(3=[.A1];\"c\";\"z\"))))";
+    test_ok(ext(dense))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.SWITCH([.A1];2;\"b\";1;\"a\";3;\"c\";\"z\")",
+        )
+        .q(Q);
+    test_ok(odf(dense))
+        .test(
+            eq,
+            "of:=IF(OR([.A1]={1;2;3});CHOOSE([.A1];\"a\";\"b\";\"c\");\"z\")",
+        )
+        .q(Q);
+    // #N/A of VLOOKUP is returned, not the default.
+    test_ok(odf(
+        "of:=IF(VLOOKUP([.A1];[.B1:.C9];2)=1;\"a\";IF(VLOOKUP([.A1];[.B1:.C9];2)=2;\"b\";\"z\"))",
+    ))
+    .test(
+        eq,
+        "of:=IF(OR(VLOOKUP([.A1];[.B1:.C9];2)={1;2});CHOOSE(VLOOKUP([.A1];[.B1:.C9];2);\"a\";\"b\");\"z\")",
+    )
+    .q(Q);
+
+    test_ok(ext("of:=IF([.A1]=\"x\";1;IF([.A1]=\"y\";2))"))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.SWITCH([.A1];\"x\";1;\"y\";2;FALSE())",
+        )
+        .q(Q);
+    test_ok(ext(
+        "of:=IF([.A1]=5;\"e\";IF([.A1]=1;\"a\";IF([.A1]=2;\"b\";\"z\")))",
+    ))
+    .test(
+        eq,
+        "of:=COM.MICROSOFT.SWITCH([.A1];5;\"e\";1;\"a\";2;\"b\";\"z\")",
+    )
+    .q(Q);
+    test_ok(odf(
+        "of:=IF([.A1]=5;\"e\";IF([.A1]=1;\"a\";IF([.A1]=2;\"b\";\"z\")))",
+    ))
+    .test(
+        eq,
+        "of:=IF([.A1]=5;\"e\";IF(OR([.A1]={1;2});CHOOSE([.A1];\"a\";\"b\");\"z\"))",
+    )
+    .q(Q);
+    test_ok(ext("of:=IF([.A1]=1;2;IF([.B1]=1;3;4))"))
+        .test(eq, "of:=COM.MICROSOFT.IFS([.A1]=1;2;[.B1]=1;3;TRUE();4)")
+        .q(Q);
+    test_ok(ext("of:=IF(RAND()=1;2;IF(RAND()=2;3;4))"))
+        .test(eq, "of:=COM.MICROSOFT.IFS(RAND()=1;2;RAND()=2;3;TRUE();4)")
+        .q(Q);
+    test_ok(ext("of:=SUM(IF([.A1]>1;IF([.B1]>1;1;IF([.B1]>2;2;3));4))"))
+        .test(
+            eq,
+            "of:=SUM(IF([.A1]>1;COM.MICROSOFT.IFS([.B1]>1;1;[.B1]>2;2;TRUE();3);4))",
+        )
+        .q(Q);
+
+    test_ok(ext("of:=IF([.A1]=1;2;3)"))
+        .test(eq, "of:=IF([.A1]=1;2;3)")
+        .q(Q);
+    test_ok(ext("of:=IF([.A1]=1;;IF([.A1]=2;1;2))"))
+        .test(eq, "of:=IF([.A1]=1;;IF([.A1]=2;1;2))")
+        .q(Q);
+}
+
+#[test]
+fn test_cse() {
+    let l = |f: &str| formula(extract_let(&Expr::parse(f).expect("expr")));
+
+    let v = shared(of::vlookup(
+        CellRef::local(0, 0),
+        CellRange::local(0, 3, 8, 4),
+        2,
+    ));
+    let f = of::if___(of::isna(v.clone()), 0, num(v.clone()) * 2);
+    test_ok(formula(extract_let(&Expr::from_formula(&f).expect("expr"))))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.LET(_cse1;VLOOKUP([.A1];[.D1:.E9];2);IF(ISNA(_cse1);0;_cse1*2))",
+        )
+        .q(Q);
+
+    test_ok(l("of:=SUM([.A1:.A3])*2+SUM([.A1:.A3])*2+SUM([.A1:.A3])"))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.LET(_cse2;SUM([.A1:.A3]);_cse1;_cse2*2;_cse1+_cse1+_cse2)",
+        )
+        .q(Q);
+    test_ok(l("of:=ABS([.A1])+ABS([.A1])+_cse1"))
+        .test(
+            eq,
+            "of:=COM.MICROSOFT.LET(_cse2;ABS([.A1]);_cse2+_cse2+_cse1)",
+        )
+        .q(Q);
+    test_ok(l("of:=RAND()*2+RAND()*2"))
+        .test(eq, "of:=RAND()*2+RAND()*2")
+        .q(Q);
+    test_ok(l("of:=ABS([.A1])+ABS([.B1])"))
+        .test(eq, "of:=ABS([.A1])+ABS([.B1])")
+        .q(Q);
+    test_ok(l("of:=COM.MICROSOFT.LET(x;ABS([.A1]);x+ABS([.A1]))"))
+        .test(eq, "of:=COM.MICROSOFT.LET(x;ABS([.A1]);x+ABS([.A1]))")
+        .q(Q);
+
+    let formulas = [
+        Expr::parse("of:=IF(ABS([.A1])>0;ROUND([.B1]*[.C1];2);0)").expect("expr"),
+        Expr::parse("of:=IF(ABS([.A1])>0;ROUND([.B1]*[.C1];2);0)*2").expect("expr"),
+        Expr::parse("of:=SUM([.A1:.A9])+SUM([.A1:.A9])+[.Z1]").expect("expr"),
+        Expr::parse("of:=INDEX([.D1:.E9];1)+INDEX([.D1:.E9];1)").expect("expr"),
+    ];
+    let helpers = [
+        CellRef::local(0, 25),
+        CellRef::local(1, 25),
+        CellRef::local(2, 25),
+    ];
+    let (f, h) = extract_cells(&formulas, "Sheet1", &helpers).into_parts();
+    let f: Vec<String> = f.into_iter().map(formula).collect();
+    test_ok(f.join(" "))
+        .test(
+            eq,
+            "of:=IF([.Z3];[.Z2];0) of:=IF([.Z3];[.Z2];0)*2 \
+             of:=SUM([.A1:.A9])+SUM([.A1:.A9])+[.Z1] \
+             of:=INDEX([.D1:.E9];1)+INDEX([.D1:.E9];1)",
+        )
+        .q(Q);
+    let h: Vec<String> = h
+        .iter()
+        .map(|(c, v)| format!("{}={}", c, formula(v.clone())))
+        .collect();
+    test_ok(h.join(" "))
+        .test(eq, ".Z2=of:=ROUND([.B1]*[.C1];2) .Z3=of:=ABS([.A1])>0")
+        .q(Q);
+
+    let e = extract_cells(&formulas, "Sheet1", &[CellRef::remote("Help", 0, 0)]);
+    test_ok(formula(e.formulas()[0].clone()))
+        .test(eq, "of:=IF(ABS([.A1])>0;[Help.A1];0)")
+        .q(Q);
+    test_ok(formula(e.helpers()[0].1.clone()))
+        .test(eq, "of:=ROUND([Sheet1.B1]*[Sheet1.C1];2)")
+        .q(Q);
+    assert_eq!(e.helpers().len(), 1);
+
+    let formulas = [
+        Expr::parse("of:=ROW()*2+[.A1]").expect("expr"),
+        Expr::parse("of:=ROW()*2+[.B1]").expect("expr"),
+        Expr::parse("of:=SHEET()*3+[.A1]").expect("expr"),
+        Expr::parse("of:=SHEET()*3+[.B1]").expect("expr"),
+        Expr::parse("of:=ROW([.A5])*2+[.A1]").expect("expr"),
+        Expr::parse("of:=ROW([.A5])*2+[.B1]").expect("expr"),
+    ];
+    let (f, h) = extract_cells(&formulas, "Sheet1", &[CellRef::local(20, 20)]).into_parts();
+    let f: Vec<String> = f.into_iter().map(formula).collect();
+    test_ok(f.join(" "))
+        .test(
+            eq,
+            "of:=ROW()*2+[.A1] of:=ROW()*2+[.B1] \
+             of:=SHEET()*3+[.A1] of:=SHEET()*3+[.B1] \
+             of:=[.U21]+[.A1] of:=[.U21]+[.B1]",
+        )
+        .q(Q);
+    test_ok(formula(h[0].1.clone()))
+        .test(eq, "of:=ROW([.A5])*2")
+        .q(Q);
+}
+
+#[test]
+fn test_validate() {
+    let limits = Limits::new(3, 3, 40);
+    let parse = |f: &str| Expr::parse(f).expect("expr");
+    let report = |e: &Expr| {
+        validate(e, &limits)
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let e = parse("of:=SUM(1;2;3;4;5;6;7)");
+    test_ok(report(&e)).test(eq, "SUM with 7 parameters").q(Q);
+    test_ok(formula(fix(&e, &limits)))
+        .test(eq, "of:=SUM(SUM(1;2;3);SUM(4;5;6);SUM(7))")
+        .q(Q);
+
+    let e = parse("of:=COUNT(1;2;3;4;5;6;7;8;9;10)");
+    let f = fix(&e, &limits);
+    test_ok(formula(f.clone()))
+        .test(
+            eq,
+            "of:=SUM(SUM(COUNT(1;2;3);COUNT(4;5;6);COUNT(7;8;9));SUM(COUNT(10)))",
+        )
+        .q(Q);
+    assert!(validate(&f, &Limits::new(3, 3, 100)).is_empty());
+
+    let e = parse("of:=IF(1;MAX(1;2;3;4);0)");
+    let v = validate(&e, &limits);
+    assert_eq!(v.len(), 1);
+    assert_eq!(v[0].path(), &[1]);
+    assert_eq!(v[0].limit(), &Limit::Params("MAX".into(), 4));
+    assert!(!v[0].is_fixable());
+    assert_eq!(fix(&e, &limits), e);
+
+    test_ok(report(&parse(
+        "of:=ABS(ABS(ABS(ABS(ABS([.A1])))))+ABS((1))",
+    )))
+    .test(eq, "nesting depth 5 at 0.0.0.0")
+    .q(Q);
+    test_ok(report(&parse(
+        "of:=\"aaaaaaaaaaaaaaaaaaaa\"&\"bbbbbbbbbbbbbbbbbbbb\"",
+    )))
+    .test(eq, "formula with 45 characters")
+    .q(Q);
+
+    let f = of::countifs(std::array::from_fn::<_, 200, _>(|i| {
+        let i = i as u32;
+        (
+            CellRange::local(0, i, 9, i),
+            CriterionBuilder::default().gt(i).expect("gt"),
+        )
+    }));
+    let v = validate(&Expr::from_formula(&f).expect("expr"), &Limits::default());
+    test_ok(
+        v.iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+    .test(eq, "COUNTIFS with 400 parameters")
+    .q(Q);
+
+    let deep = (0..100).fold(Expr::Number(1.0), |e, _| Expr::func("ABS", vec![e]));
+    assert!(validate(&deep, &Limits::default()).is_empty());
+    assert_eq!(Limits::default().depth(), usize::MAX);
+}
+
+#[test]
+fn test_sheet() {
+    let t = |f: &str| Expr::parse(f).expect("expr").formula_type();
+    assert_eq!(t("of:=DATE(2024;1;1)"), FormulaType::Date);
+    assert_eq!(t("of:=DATE(2024;1;1)+7"), FormulaType::Date);
+    assert_eq!(t("of:=TODAY()-DATE(2024;1;1)"), FormulaType::Number);
+    assert_eq!(t("of:=NOW()"), FormulaType::DateTime);
+    assert_eq!(t("of:=TIME(12;0;0)"), FormulaType::Time);
+    assert_eq!(t("of:=TIME(12;0;0)-TIME(8;0;0)"), FormulaType::Time);
+    assert_eq!(t("of:=DATE(2024;1;1)-TIME(8;0;0)"), FormulaType::DateTime);
+    assert_eq!(t("of:=EDATE([.A1];1)"), FormulaType::Number);
+    assert_eq!(t("of:=DOLLAR([.A1])"), FormulaType::Text);
+    assert_eq!(t("of:=[.A1]&\"x\""), FormulaType::Text);
+    assert_eq!(t("of:=PMT(0.05/12;60;10000)*2"), FormulaType::Currency);
+    assert_eq!(t("of:=IRR([.A1:.A9])"), FormulaType::Percentage);
+    assert_eq!(t("of:=[.A1]%"), FormulaType::Percentage);
+    assert_eq!(t("of:=AND([.A1]>0;ISBLANK([.B1]))"), FormulaType::Boolean);
+    assert_eq!(t("of:=IF([.A1];\"a\";UPPER([.B1]))"), FormulaType::Text);
+    assert_eq!(t("of:=IF([.A1];\"a\";1)"), FormulaType::Number);
+    assert_eq!(
+        t("of:=COM.MICROSOFT.SWITCH([.A1];1;DATE(2024;1;1);TODAY())"),
+        FormulaType::Date
+    );
+    assert_eq!(t("of:=[.A1]"), FormulaType::Number);
+
+    let mut sheet = Sheet::new("Typed");
+    let f = of::dollar(CellRef::local(0, 0));
+    assert_eq!(sheet.set_typed_formula(0, 1, &f), FormulaType::Text);
+    assert_eq!(
+        sheet.formula(0, 1).map(|v| v.as_str()),
+        Some("of:=DOLLAR([.A1])")
+    );
+    assert_eq!(sheet.value(0, 1), &Value::Text(String::new()));
+    assert_eq!(sheet.cellstyle(0, 1), None);
+
+    let f = of::date(2024, 1, 1);
+    assert_eq!(sheet.set_styled_formula(1, 1, &f), FormulaType::Date);
+    assert!(matches!(sheet.value(1, 1), Value::DateTime(_)));
+    assert_eq!(
+        sheet.cellstyle(1, 1),
+        Some(&CellStyleRef::from("default-date"))
+    );
+
+    let f = num(CellRef::local(0, 0)).gt(0);
+    assert_eq!(sheet.set_styled_formula(2, 1, &f), FormulaType::Boolean);
+    assert_eq!(sheet.value(2, 1), &Value::Boolean(false));
+    assert_eq!(
+        sheet.cellstyle(2, 1),
+        Some(&CellStyleRef::from("default-bool"))
+    );
+
+    // Without parsing, from the node types.
+    assert_eq!(
+        (of::time(12, 0, 0) - of::time(8, 0, 0)).formula_type(),
+        FormulaType::Time
+    );
+    assert_eq!(
+        of::concat("a", CellRef::local(0, 0)).formula_type(),
+        FormulaType::Text
+    );
+    assert_eq!(
+        of::pmt(0.05, 60, 10000).formula_type(),
+        FormulaType::Currency
+    );
+    assert_eq!(
+        of::rate(60, -200, 10000).formula_type(),
+        FormulaType::Percentage
+    );
+    let d = NaiveDate::from_ymd_opt(2024, 3, 1).expect("date");
+    assert_eq!((num(d) + 7).formula_type(), FormulaType::Date);
+    assert_eq!(
+        of::if___(CellRef::local(0, 0), "a", of::upper(CellRef::local(1, 0))).formula_type(),
+        FormulaType::Text
+    );
+    assert_eq!(
+        Value::TimeDuration(Duration::hours(1)).formula_type(),
+        FormulaType::Time
+    );
+    assert_eq!(CellRef::local(0, 0).formula_type(), FormulaType::Number);
+}